@@ -0,0 +1,107 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum BadgeKind {
+    Ci,
+    Coverage,
+    Version,
+    Other,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct Badge {
+    pub kind: BadgeKind,
+    pub alt: String,
+    pub url: String,
+}
+
+/// Extracts shields.io badges from README markdown, i.e. images of the form
+/// `![alt](https://img.shields.io/...)` (including ones wrapped in links).
+pub fn extract_badges(markdown: &str) -> Vec<Badge> {
+    let mut badges = Vec::new();
+    let mut rest = markdown;
+
+    while let Some(start) = rest.find("![") {
+        rest = &rest[start + 2..];
+        let Some(alt_end) = rest.find("](") else {
+            break;
+        };
+        let alt = &rest[..alt_end];
+        let target = &rest[alt_end + 2..];
+        let Some(url_end) = target.find(')') else {
+            break;
+        };
+        // Drop an optional title: ![alt](url "title")
+        let url = target[..url_end].split_whitespace().next().unwrap_or("");
+        rest = &target[url_end + 1..];
+
+        if url.starts_with("https://img.shields.io/") || url.starts_with("http://img.shields.io/") {
+            badges.push(Badge {
+                kind: classify(alt, url),
+                alt: alt.to_string(),
+                url: url.to_string(),
+            });
+        }
+    }
+
+    badges
+}
+
+fn classify(alt: &str, url: &str) -> BadgeKind {
+    let haystack = format!("{} {}", alt.to_lowercase(), url.to_lowercase());
+    let mentions = |needles: &[&str]| needles.iter().any(|n| haystack.contains(n));
+
+    if mentions(&["coverage", "codecov", "coveralls"]) {
+        BadgeKind::Coverage
+    } else if mentions(&[
+        "build",
+        "workflow",
+        "/actions/",
+        "travis",
+        "circleci",
+        "appveyor",
+        "pipeline",
+    ]) || alt.eq_ignore_ascii_case("ci")
+    {
+        BadgeKind::Ci
+    } else if mentions(&["version", "release", "/v/", "crates/v", "npm/v", "pypi/v"]) {
+        BadgeKind::Version
+    } else {
+        BadgeKind::Other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_shields_badges_only() {
+        let readme = "# Project\n\
+            [![Build](https://img.shields.io/github/actions/workflow/status/o/r/ci.yml)](https://github.com/o/r/actions)\n\
+            ![Coverage](https://img.shields.io/codecov/c/github/o/r \"coverage\")\n\
+            ![Crates.io](https://img.shields.io/crates/v/gitorg)\n\
+            ![License](https://img.shields.io/badge/license-MIT-blue)\n\
+            ![screenshot](docs/screenshot.png)\n";
+
+        let badges = extract_badges(readme);
+        let kinds: Vec<BadgeKind> = badges.iter().map(|b| b.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                BadgeKind::Ci,
+                BadgeKind::Coverage,
+                BadgeKind::Version,
+                BadgeKind::Other
+            ]
+        );
+        assert_eq!(badges[1].url, "https://img.shields.io/codecov/c/github/o/r");
+        assert_eq!(badges[3].alt, "License");
+    }
+
+    #[test]
+    fn no_badges_in_plain_readme() {
+        assert!(extract_badges("# Hello\n\nJust text. ![broken").is_empty());
+    }
+}
@@ -0,0 +1,383 @@
+use crossterm::style::{Attribute, SetAttribute};
+use crossterm::{cursor, queue, terminal};
+use std::io::{self, Read, Write};
+
+/// What `browse` needs from a list item.
+pub trait Browsable {
+    /// Matched against the filter and shown first, e.g. `org/name`
+    fn label(&self) -> String;
+    /// The rest of the row
+    fn details(&self) -> String;
+    /// Opened in the browser on Enter
+    fn url(&self) -> String;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Key {
+    Char(char),
+    Backspace,
+    Enter,
+    Tab,
+    Up,
+    Down,
+    PageUp,
+    PageDown,
+    Esc,
+    /// Ctrl-C, or stdin closing
+    Quit,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Continue,
+    Open(String),
+    Quit,
+}
+
+/// A full-screen list to filter, sort, and open items from
+/// (`repos --interactive`), apart from the terminal itself.
+struct Browser<T> {
+    items: Vec<T>,
+    sorts: &'static [&'static str],
+    sort_by: fn(&mut [T], &str),
+    sort: usize,
+    filter: String,
+    selected: usize,
+    offset: usize,
+    /// A one-off message for the footer, e.g. a failed open
+    status: Option<String>,
+}
+
+impl<T: Browsable> Browser<T> {
+    fn new(
+        items: Vec<T>,
+        sorts: &'static [&'static str],
+        sort_by: fn(&mut [T], &str),
+        initial_sort: &str,
+    ) -> Self {
+        Self {
+            items,
+            sorts,
+            sort_by,
+            sort: sorts.iter().position(|s| *s == initial_sort).unwrap_or(0),
+            filter: String::new(),
+            selected: 0,
+            offset: 0,
+            status: None,
+        }
+    }
+
+    /// Items whose label contains the filter, ignoring case.
+    fn visible(&self) -> Vec<&T> {
+        let filter = self.filter.to_lowercase();
+        self.items
+            .iter()
+            .filter(|item| item.label().to_lowercase().contains(&filter))
+            .collect()
+    }
+
+    fn handle(&mut self, key: Key, page: usize) -> Action {
+        self.status = None;
+        let last = self.visible().len().saturating_sub(1);
+        match key {
+            Key::Quit => return Action::Quit,
+            Key::Esc if self.filter.is_empty() => return Action::Quit,
+            Key::Esc => self.set_filter(String::new()),
+            Key::Char(c) => {
+                let filter = format!("{}{c}", self.filter);
+                self.set_filter(filter);
+            }
+            Key::Backspace => {
+                let mut filter = self.filter.clone();
+                filter.pop();
+                self.set_filter(filter);
+            }
+            Key::Up => self.selected = self.selected.saturating_sub(1),
+            Key::Down => self.selected = (self.selected + 1).min(last),
+            Key::PageUp => self.selected = self.selected.saturating_sub(page.max(1)),
+            Key::PageDown => self.selected = (self.selected + page.max(1)).min(last),
+            Key::Tab => {
+                self.sort = (self.sort + 1) % self.sorts.len();
+                (self.sort_by)(&mut self.items, self.sorts[self.sort]);
+                self.selected = 0;
+            }
+            Key::Enter => {
+                if let Some(item) = self.visible().get(self.selected) {
+                    return Action::Open(item.url());
+                }
+            }
+        }
+        Action::Continue
+    }
+
+    fn set_filter(&mut self, filter: String) {
+        self.filter = filter;
+        self.selected = 0;
+    }
+
+    /// Scrolls just enough to keep the selection among `height` rows.
+    fn scroll(&mut self, height: usize) {
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + height {
+            self.offset = self.selected + 1 - height;
+        }
+    }
+
+    fn draw(&mut self, out: &mut impl Write, width: usize, height: usize) -> io::Result<()> {
+        let rows = height.saturating_sub(2).max(1);
+        self.scroll(rows);
+        let visible = self.visible();
+        let label_width = visible
+            .iter()
+            .map(|item| item.label().chars().count())
+            .max()
+            .unwrap_or(0)
+            .min(48);
+
+        queue!(
+            out,
+            terminal::Clear(terminal::ClearType::All),
+            cursor::MoveTo(0, 0)
+        )?;
+        let header = format!(
+            "Filter: {}_   Sort: {} (Tab)   {} of {} shown",
+            self.filter,
+            self.sorts[self.sort],
+            visible.len(),
+            self.items.len()
+        );
+        write!(out, "{}\r\n", fit(&header, width))?;
+
+        for (i, item) in visible.iter().enumerate().skip(self.offset).take(rows) {
+            let line = format!("{:<label_width$}  {}", item.label(), item.details());
+            let line = fit(&line, width);
+            if i == self.selected {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+                write!(out, "{line:<width$}")?;
+                queue!(out, SetAttribute(Attribute::Reset))?;
+                write!(out, "\r\n")?;
+            } else {
+                write!(out, "{line}\r\n")?;
+            }
+        }
+
+        let footer = self.status.clone().unwrap_or_else(|| {
+            "Type to filter  Up/Down/PgUp/PgDn move  Enter open  Tab sort  Esc clear/quit"
+                .to_string()
+        });
+        queue!(out, cursor::MoveTo(0, height.saturating_sub(1) as u16))?;
+        write!(out, "{}", fit(&footer, width))?;
+        out.flush()
+    }
+}
+
+/// Cuts `line` to `width` characters.
+fn fit(line: &str, width: usize) -> String {
+    line.chars().take(width).collect()
+}
+
+/// Decodes the bytes one read of a raw-mode terminal returns.
+pub fn parse_keys(bytes: &[u8]) -> Vec<Key> {
+    let mut keys = Vec::new();
+    let text = String::from_utf8_lossy(bytes);
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        let key = match c {
+            '\x03' | '\x04' => Key::Quit,
+            '\r' | '\n' => Key::Enter,
+            '\t' => Key::Tab,
+            '\x7f' | '\x08' => Key::Backspace,
+            '\x1b' if chars.peek() == Some(&'[') => {
+                chars.next();
+                let mut params = String::new();
+                while let Some(&p) = chars.peek().filter(|p| p.is_ascii_digit() || **p == ';') {
+                    params.push(p);
+                    chars.next();
+                }
+                match (params.as_str(), chars.next()) {
+                    ("", Some('A')) => Key::Up,
+                    ("", Some('B')) => Key::Down,
+                    ("5", Some('~')) => Key::PageUp,
+                    ("6", Some('~')) => Key::PageDown,
+                    // Other sequences (Left/Right, F-keys, modifiers) are ignored
+                    _ => continue,
+                }
+            }
+            '\x1b' => Key::Esc,
+            c if c.is_control() => continue,
+            c => Key::Char(c),
+        };
+        keys.push(key);
+    }
+    keys
+}
+
+/// Puts the terminal back however the browser exits.
+struct RawTerminal;
+
+impl RawTerminal {
+    fn enter() -> io::Result<Self> {
+        terminal::enable_raw_mode()?;
+        let guard = RawTerminal;
+        crossterm::execute!(io::stdout(), terminal::EnterAlternateScreen, cursor::Hide)?;
+        Ok(guard)
+    }
+}
+
+impl Drop for RawTerminal {
+    fn drop(&mut self) {
+        let _ = crossterm::execute!(io::stdout(), cursor::Show, terminal::LeaveAlternateScreen);
+        let _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Runs the browser until Esc or Ctrl-C. `sorts` are the orders Tab cycles
+/// through, applied with `sort_by`.
+pub fn browse<T: Browsable>(
+    items: Vec<T>,
+    sorts: &'static [&'static str],
+    sort_by: fn(&mut [T], &str),
+    initial_sort: &str,
+) -> crate::error::Result<()> {
+    let mut browser = Browser::new(items, sorts, sort_by, initial_sort);
+    let _terminal = RawTerminal::enter()?;
+    let mut stdout = io::stdout();
+    let mut stdin = io::stdin();
+    let mut buf = [0u8; 64];
+    loop {
+        let (width, height) = terminal::size()?;
+        browser.draw(&mut stdout, width as usize, height as usize)?;
+
+        let n = stdin.read(&mut buf)?;
+        let keys = if n == 0 {
+            vec![Key::Quit]
+        } else {
+            parse_keys(&buf[..n])
+        };
+        let page = (height as usize).saturating_sub(2);
+        for key in keys {
+            match browser.handle(key, page) {
+                Action::Continue => {}
+                Action::Quit => return Ok(()),
+                Action::Open(url) => {
+                    if open::that(&url).is_err() {
+                        browser.status = Some(format!("Could not open browser. Visit: {url}"));
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Item(&'static str, u32);
+
+    impl Browsable for Item {
+        fn label(&self) -> String {
+            self.0.to_string()
+        }
+        fn details(&self) -> String {
+            self.1.to_string()
+        }
+        fn url(&self) -> String {
+            format!("https://github.com/{}", self.0)
+        }
+    }
+
+    fn sort_items(items: &mut [Item], sort: &str) {
+        match sort {
+            "stars" => items.sort_by_key(|i| std::cmp::Reverse(i.1)),
+            _ => items.sort_by_key(|i| i.0),
+        }
+    }
+
+    fn browser() -> Browser<Item> {
+        let items = vec![
+            Item("acme/api", 5),
+            Item("acme/web", 40),
+            Item("beta/API-docs", 1),
+        ];
+        Browser::new(items, &["name", "stars"], sort_items, "name")
+    }
+
+    fn labels(browser: &Browser<Item>) -> Vec<&str> {
+        browser.visible().iter().map(|i| i.0).collect()
+    }
+
+    #[test]
+    fn keys_are_decoded_from_raw_bytes() {
+        assert_eq!(
+            parse_keys(b"ab\x7f\x1b[A\x1b[B\x1b[5~\x1b[6~\t\r"),
+            vec![
+                Key::Char('a'),
+                Key::Char('b'),
+                Key::Backspace,
+                Key::Up,
+                Key::Down,
+                Key::PageUp,
+                Key::PageDown,
+                Key::Tab,
+                Key::Enter,
+            ]
+        );
+        assert_eq!(parse_keys(b"\x1b"), vec![Key::Esc]);
+        assert_eq!(parse_keys(b"\x03"), vec![Key::Quit]);
+        assert_eq!(
+            parse_keys("é\x1b[C\x1b[1;5Cz".as_bytes()),
+            vec![Key::Char('é'), Key::Char('z')]
+        );
+    }
+
+    #[test]
+    fn typing_filters_by_label_ignoring_case() {
+        let mut browser = browser();
+        for c in "api".chars() {
+            browser.handle(Key::Char(c), 10);
+        }
+        assert_eq!(labels(&browser), vec!["acme/api", "beta/API-docs"]);
+
+        browser.handle(Key::Down, 10);
+        assert_eq!(
+            browser.handle(Key::Enter, 10),
+            Action::Open("https://github.com/beta/API-docs".to_string())
+        );
+
+        // Esc clears the filter first, then quits
+        assert_eq!(browser.handle(Key::Esc, 10), Action::Continue);
+        assert_eq!(labels(&browser).len(), 3);
+        assert_eq!(browser.handle(Key::Esc, 10), Action::Quit);
+    }
+
+    #[test]
+    fn tab_cycles_sorts_and_selection_stays_in_bounds() {
+        let mut browser = browser();
+        browser.handle(Key::Tab, 10);
+        assert_eq!(browser.sorts[browser.sort], "stars");
+        assert_eq!(
+            labels(&browser),
+            vec!["acme/web", "acme/api", "beta/API-docs"]
+        );
+
+        browser.handle(Key::PageDown, 10);
+        assert_eq!(browser.selected, 2);
+        browser.handle(Key::Tab, 10);
+        assert_eq!(browser.selected, 0);
+        browser.handle(Key::Up, 10);
+        assert_eq!(browser.selected, 0);
+    }
+
+    #[test]
+    fn scrolling_keeps_the_selection_on_screen() {
+        let mut browser = browser();
+        browser.selected = 2;
+        browser.scroll(2);
+        assert_eq!(browser.offset, 1);
+        browser.selected = 0;
+        browser.scroll(2);
+        assert_eq!(browser.offset, 0);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::config::config_dir;
+use crate::error::{GitorgError, Result};
+use crate::github::OrgRepo;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+/// On-disk cache of org repo listings, one JSON file per org. Each page keeps
+/// its ETag so an expired listing can be revalidated with conditional
+/// requests instead of refetched.
+pub struct RepoCache {
+    dir: PathBuf,
+    ttl: Duration,
+    /// When false (`--no-cache`), entries are refreshed but never read.
+    read: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: DateTime<Utc>,
+    pages: Vec<CachedPage>,
+}
+
+/// One page of a listing as GitHub returned it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedPage {
+    pub etag: Option<String>,
+    pub has_next: bool,
+    pub repos: Vec<OrgRepo>,
+}
+
+/// Flattens pages back into the listing.
+pub fn page_repos(pages: &[CachedPage]) -> Vec<OrgRepo> {
+    pages.iter().flat_map(|p| p.repos.iter().cloned()).collect()
+}
+
+pub fn cache_dir() -> Result<PathBuf> {
+    Ok(config_dir()?.join("cache").join("repos"))
+}
+
+/// Removes every cached listing, returning how many were deleted.
+pub fn clear_cache() -> Result<usize> {
+    let dir = cache_dir()?;
+    if !dir.exists() {
+        return Ok(0);
+    }
+    let mut removed = 0;
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            fs::remove_file(&path)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn is_fresh(fetched_at: DateTime<Utc>, now: DateTime<Utc>, ttl: Duration) -> bool {
+    now - fetched_at < ttl
+}
+
+impl RepoCache {
+    pub fn new(dir: PathBuf, ttl_minutes: u64, read: bool) -> Self {
+        Self {
+            dir,
+            ttl: Duration::minutes(ttl_minutes as i64),
+            read,
+        }
+    }
+
+    /// Stops serving entries, as if built with `read` false.
+    pub fn write_only(self) -> Self {
+        Self {
+            read: false,
+            ..self
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key.to_lowercase()))
+    }
+
+    fn read_entry(&self, key: &str) -> Option<CacheEntry> {
+        if !self.read {
+            return None;
+        }
+        let contents = fs::read_to_string(self.entry_path(key)).ok()?;
+        serde_json::from_str(&contents).ok()
+    }
+
+    /// Returns the cached repos and their age if present and within the TTL.
+    pub fn load(&self, key: &str) -> Option<(Vec<OrgRepo>, Duration)> {
+        let entry = self.read_entry(key)?;
+        let now = Utc::now();
+        is_fresh(entry.fetched_at, now, self.ttl)
+            .then(|| (page_repos(&entry.pages), now - entry.fetched_at))
+    }
+
+    /// Returns the cached pages whatever their age, for revalidating with
+    /// their ETags.
+    pub fn load_pages(&self, key: &str) -> Option<Vec<CachedPage>> {
+        self.read_entry(key).map(|entry| entry.pages)
+    }
+
+    /// Deletes the entry for `key`, if there is one.
+    pub fn remove(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.entry_path(key)) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn store(&self, key: &str, pages: &[CachedPage]) -> Result<()> {
+        self.store_at(key, pages, Utc::now())
+    }
+
+    fn store_at(&self, key: &str, pages: &[CachedPage], fetched_at: DateTime<Utc>) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        let entry = CacheEntry {
+            fetched_at,
+            pages: pages.to_vec(),
+        };
+        let json = serde_json::to_string(&entry)
+            .map_err(|e| GitorgError::Config(format!("Failed to encode repo cache: {e}")))?;
+        fs::write(self.entry_path(key), json)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str) -> OrgRepo {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": name,
+            "url": format!("https://api.github.com/repos/org/{name}"),
+        }))
+        .unwrap()
+    }
+
+    fn page(etag: &str, names: &[&str]) -> CachedPage {
+        CachedPage {
+            etag: Some(etag.to_string()),
+            has_next: false,
+            repos: names.iter().map(|n| repo(n)).collect(),
+        }
+    }
+
+    #[test]
+    fn freshness_respects_ttl() {
+        let now = Utc::now();
+        let ttl = Duration::minutes(10);
+        assert!(is_fresh(now - Duration::minutes(9), now, ttl));
+        assert!(!is_fresh(now - Duration::minutes(10), now, ttl));
+        assert!(!is_fresh(now - Duration::hours(2), now, ttl));
+    }
+
+    #[test]
+    fn cached_entry_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RepoCache::new(dir.path().to_path_buf(), 10, true);
+        cache
+            .store("MyOrg", &[page("\"e1\"", &["a"]), page("\"e2\"", &["b"])])
+            .unwrap();
+
+        let (repos, _age) = cache.load("myorg").unwrap();
+        assert_eq!(repos.len(), 2);
+        assert_eq!(repos[1].name, "b");
+        assert!(cache.load("other").is_none());
+    }
+
+    #[test]
+    fn expired_entry_is_ignored() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = RepoCache::new(dir.path().to_path_buf(), 10, true);
+        cache
+            .store_at(
+                "org",
+                &[page("\"e1\"", &["a"])],
+                Utc::now() - Duration::minutes(11),
+            )
+            .unwrap();
+        assert!(cache.load("org").is_none());
+
+        // Still available for revalidation, ETag intact
+        let pages = cache.load_pages("org").unwrap();
+        assert_eq!(pages[0].etag.as_deref(), Some("\"e1\""));
+        assert_eq!(pages[0].repos[0].name, "a");
+    }
+
+    #[test]
+    fn no_cache_skips_reads() {
+        let dir = tempfile::tempdir().unwrap();
+        RepoCache::new(dir.path().to_path_buf(), 10, true)
+            .store("org", &[page("\"e1\"", &["a"])])
+            .unwrap();
+
+        let cache = RepoCache::new(dir.path().to_path_buf(), 10, false);
+        assert!(cache.load("org").is_none());
+        assert!(cache.load_pages("org").is_none());
+
+        let cache = RepoCache::new(dir.path().to_path_buf(), 10, true).write_only();
+        assert!(cache.load("org").is_none());
+    }
+}
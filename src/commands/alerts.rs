@@ -0,0 +1,136 @@
+use crate::commands::{github_client, resolve_orgs, GlobalArgs, OrgFailures};
+use crate::display;
+use crate::error::Result;
+use crate::github::RepoVisibility;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::cmp::Reverse;
+
+#[derive(Debug, Serialize)]
+pub struct AlertCount {
+    pub org: String,
+    pub name: String,
+    /// `None` when Dependabot alerts are disabled or not visible to the token
+    pub open_alerts: Option<u32>,
+}
+
+pub async fn run(org: &Option<String>, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    client.warn_if_rate_limited().await.ok();
+
+    let orgs = resolve_orgs(org, &global.exclude_orgs, &config, &client).await?;
+
+    let mut counts = Vec::new();
+    let mut failures = OrgFailures::new(orgs.len());
+    for org_name in &orgs {
+        let repos = match client.list_org_repos(org_name, RepoVisibility::All).await {
+            Ok(repos) => repos,
+            Err(e) => {
+                failures.repos_failed(org, org_name, e)?;
+                continue;
+            }
+        };
+
+        // Archived repos are read-only; their alerts can't be acted on
+        let client = &client;
+        let results: Vec<(String, Result<Option<u32>>)> =
+            stream::iter(repos.iter().filter(|repo| !repo.archived.unwrap_or(false)))
+                .map(|repo| async move {
+                    let count = client.count_dependabot_alerts(org_name, &repo.name).await;
+                    (repo.name.clone(), count)
+                })
+                .buffer_unordered(client.max_concurrency())
+                .collect()
+                .await;
+
+        for (name, result) in results {
+            let open_alerts = match result {
+                Ok(count) => count,
+                Err(e) => {
+                    display::warn(&format!(
+                        "Failed to count alerts for {org_name}/{name}: {e}"
+                    ));
+                    None
+                }
+            };
+            counts.push(AlertCount {
+                org: org_name.clone(),
+                name,
+                open_alerts,
+            });
+        }
+    }
+    failures.finish()?;
+
+    sort_alerts(&mut counts);
+
+    display::output_records(global.json, &counts, |data| {
+        render_alerts_table(data);
+    })?;
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// Most alerts first; repos without alert data go last.
+fn sort_alerts(counts: &mut [AlertCount]) {
+    counts.sort_by(|a, b| {
+        Reverse(a.open_alerts)
+            .cmp(&Reverse(b.open_alerts))
+            .then_with(|| (&a.org, &a.name).cmp(&(&b.org, &b.name)))
+    });
+}
+
+fn render_alerts_table(counts: &[AlertCount]) {
+    if counts.is_empty() {
+        display::warn("No repositories found.");
+        return;
+    }
+
+    let total: u32 = counts.iter().filter_map(|c| c.open_alerts).sum();
+    display::section_header(&format!("Open Dependabot Alerts ({total} total)"));
+
+    let mut table = display::new_table(&["Org", "Repo", "Open Alerts"]);
+    for c in counts {
+        table.add_row(vec![
+            c.org.clone(),
+            c.name.clone(),
+            c.open_alerts
+                .map_or("n/a".to_string(), |count| count.to_string()),
+        ]);
+    }
+
+    display::print_table(&table);
+    display::count_footer(counts.len(), counts.len(), "repository(ies)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(name: &str, open_alerts: Option<u32>) -> AlertCount {
+        AlertCount {
+            org: "acme".into(),
+            name: name.into(),
+            open_alerts,
+        }
+    }
+
+    #[test]
+    fn most_alerts_first_and_unknown_last() {
+        let mut counts = vec![
+            count("disabled", None),
+            count("clean", Some(0)),
+            count("risky", Some(12)),
+            count("meh", Some(3)),
+        ];
+        sort_alerts(&mut counts);
+        let names: Vec<&str> = counts.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["risky", "meh", "clean", "disabled"]);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::commands::repo::parse_slug;
+use crate::commands::{github_client, GlobalArgs};
+use crate::display;
+use crate::error::{GitorgError, Result};
+use serde::Serialize;
+use std::io::IsTerminal;
+
+#[derive(Debug, Serialize)]
+pub struct ArchiveChange {
+    pub org: String,
+    pub name: String,
+    pub archived_before: bool,
+    pub archived_after: bool,
+}
+
+/// Archives (`archived`) or unarchives a repo. Without `--yes` this asks
+/// first, and refuses outright when there's no terminal to ask on.
+pub async fn run(slug: &str, archived: bool, global: &GlobalArgs) -> Result<()> {
+    let (owner, name) = parse_slug(slug)?;
+    let yes = global.yes;
+    let verb = if archived { "archive" } else { "unarchive" };
+    if !yes && !std::io::stdin().is_terminal() {
+        return Err(GitorgError::Config(format!(
+            "Refusing to {verb} {owner}/{name} without --yes when not running interactively"
+        )));
+    }
+
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let before = client
+        .get_repo(owner, name)
+        .await?
+        .archived
+        .unwrap_or(false);
+
+    let after = if before == archived {
+        display::warn(&format!(
+            "{owner}/{name} is already {}",
+            archive_state(archived)
+        ));
+        before
+    } else {
+        if !yes && !display::confirm(&format!("{} {owner}/{name}?", capitalize(verb))) {
+            display::warn("Cancelled; nothing was changed.");
+            return Ok(());
+        }
+        client
+            .set_repo_archived(owner, name, archived)
+            .await?
+            .archived
+            .unwrap_or(archived)
+    };
+
+    let change = ArchiveChange {
+        org: owner.to_string(),
+        name: name.to_string(),
+        archived_before: before,
+        archived_after: after,
+    };
+    display::output(global.json, &change, |data| {
+        if data.archived_before != data.archived_after {
+            display::success(&format!(
+                "{}/{}: {} -> {}",
+                data.org,
+                data.name,
+                archive_state(data.archived_before),
+                archive_state(data.archived_after)
+            ));
+        }
+    });
+
+    Ok(())
+}
+
+fn archive_state(archived: bool) -> &'static str {
+    if archived {
+        "archived"
+    } else {
+        "active"
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|c| c.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn capitalize_first_letter() {
+        assert_eq!(capitalize("archive"), "Archive");
+        assert_eq!(capitalize(""), "");
+    }
+}
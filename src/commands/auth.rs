@@ -1,15 +1,31 @@
-use crate::config::{load_config, save_config};
+use crate::commands::{web_url, GlobalArgs};
+use crate::config::{save_config, DefaultsConfig, TokenStorage};
 use crate::display;
-use crate::error::Result;
-use crate::github::GithubClient;
+use crate::error::{GitorgError, Result};
+use crate::github::{is_fine_grained, GithubClient};
+use futures::stream::{self, StreamExt};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::PathBuf;
+use std::process::Command;
+
+pub async fn run(
+    token: &Option<String>,
+    import_gh: bool,
+    storage: Option<TokenStorage>,
+    dry_run: bool,
+    global: &GlobalArgs,
+) -> Result<()> {
+    let mut config = global.load_config()?;
+    let api_url = global.api_url(&config).map(str::to_string);
+    let pick_orgs = token.is_none() && std::io::stdin().is_terminal();
 
-pub async fn run(token: &Option<String>) -> Result<()> {
     let token = match token {
         Some(t) => t.clone(),
+        None if import_gh => gh_token(&gh_host(api_url.as_deref()))?,
         None => {
-            let url = "https://github.com/settings/tokens/new?description=gitorg&scopes=read:org,repo";
+            let url = token_page_url(api_url.as_deref());
             eprintln!("Opening GitHub token creation page in your browser...");
-            if open::that(url).is_err() {
+            if open::that(&url).is_err() {
                 eprintln!("Could not open browser. Visit: {url}");
             }
             eprintln!("\nPaste your token below:");
@@ -19,18 +35,342 @@ pub async fn run(token: &Option<String>) -> Result<()> {
 
     let token = token.trim().to_string();
 
-    let client = GithubClient::new(&token, false)?;
+    let client = GithubClient::new(
+        &token,
+        api_url.as_deref(),
+        false,
+        global.timeout(&config),
+        global.proxy(&config)?,
+    )?;
     let user = client.validate_token().await?;
 
-    let mut config = load_config()?;
-    config.auth.token = Some(token);
-    save_config(&config)?;
+    if let Some(scopes) = &user.scopes {
+        let missing = missing_scopes(scopes);
+        if !missing.is_empty() {
+            display::warn(&format!(
+                "Token is missing the {} scope(s); some orgs and private repos may not be visible",
+                missing.join(", ")
+            ));
+        }
+    }
+
+    if is_fine_grained(&token) {
+        warn_unreachable_orgs(&client).await;
+    }
+
+    if dry_run {
+        display::success(&format!(
+            "Token is valid for {} ({}); nothing was saved",
+            user.login,
+            user.name.as_deref().unwrap_or("no name set"),
+        ));
+        return Ok(());
+    }
+
+    let profile = config.profile_name().to_string();
+    let auth = config.auth_mut();
+    let in_keyring = storage.unwrap_or(auth.storage) == TokenStorage::Keyring
+        && match crate::keyring::store(&profile, &token) {
+            Ok(()) => true,
+            Err(e) => {
+                display::warn(&format!("{e}; storing the token in config.toml instead"));
+                false
+            }
+        };
+    auth.storage = if in_keyring {
+        TokenStorage::Keyring
+    } else {
+        TokenStorage::File
+    };
+    auth.token = (!in_keyring).then_some(token);
+    auth.base_url = api_url;
+
+    if pick_orgs {
+        select_default_orgs(&client, config.defaults_mut()).await;
+    }
+
+    save_config(&global.config_path()?, &config)?;
 
     display::success(&format!(
-        "Authenticated as {} ({})",
+        "Authenticated as {} ({}) in profile '{}'",
         user.login,
-        user.name.as_deref().unwrap_or("no name set")
+        user.name.as_deref().unwrap_or("no name set"),
+        config.profile_name()
     ));
 
     Ok(())
 }
+
+/// Classic-token scopes gitorg needs that `scopes` doesn't grant.
+/// `write:org` and `admin:org` include `read:org`.
+pub fn missing_scopes(scopes: &[String]) -> Vec<&'static str> {
+    let has = |name: &str| scopes.iter().any(|s| s == name);
+    let mut missing = Vec::new();
+    if !has("repo") {
+        missing.push("repo");
+    }
+    if !(has("read:org") || has("write:org") || has("admin:org")) {
+        missing.push("read:org");
+    }
+    missing
+}
+
+/// Fine-grained tokens only reach the orgs they were granted, and other
+/// orgs just look empty, so check each org up front and say which ones
+/// the token can't see into.
+async fn warn_unreachable_orgs(client: &GithubClient) {
+    let orgs = match client.list_user_orgs().await {
+        Ok(orgs) => orgs,
+        Err(e) => {
+            display::warn(&format!("Could not list organizations: {e}"));
+            return;
+        }
+    };
+
+    let checks: Vec<_> = stream::iter(orgs.iter())
+        .map(|org| async move { (&org.login, client.can_see_org_repos(&org.login).await) })
+        .buffered(client.max_concurrency())
+        .collect()
+        .await;
+    for (org, check) in checks {
+        match check {
+            Ok(true) => {}
+            Ok(false) => display::warn(&format!(
+                "Token lacks access to org {org} (or it has no repos); \
+                 grant the fine-grained token that org to include it"
+            )),
+            Err(e) => display::warn(&format!("Could not check access to {org}: {e}")),
+        }
+    }
+}
+
+/// Offers the token's orgs as a numbered list and saves the picks as
+/// `defaults.orgs`. Pressing Enter, or input ending early, keeps the
+/// current setting.
+async fn select_default_orgs(client: &GithubClient, defaults: &mut DefaultsConfig) {
+    let orgs = match client.list_user_orgs().await {
+        Ok(orgs) if !orgs.is_empty() => orgs,
+        Ok(_) => return,
+        Err(e) => {
+            display::warn(&format!("Could not list organizations: {e}"));
+            return;
+        }
+    };
+
+    let current = defaults.orgs.clone().unwrap_or_default();
+    eprintln!("\nChoose default organizations (used when --org isn't given):");
+    for (i, org) in orgs.iter().enumerate() {
+        let mark = if current.iter().any(|c| c.eq_ignore_ascii_case(&org.login)) {
+            "[x]"
+        } else {
+            "[ ]"
+        };
+        eprintln!("  {mark} {}. {}", i + 1, org.login);
+    }
+
+    let stdin = std::io::stdin();
+    loop {
+        eprint!("Numbers or ranges (e.g. 1,3-4), \"all\", \"none\", or Enter to keep: ");
+        std::io::stderr().flush().ok();
+
+        let mut line = String::new();
+        match stdin.lock().read_line(&mut line) {
+            Ok(0) => {
+                eprintln!();
+                display::warn("No selection read; default organizations left unchanged");
+                return;
+            }
+            Ok(_) => {}
+            Err(e) => {
+                display::warn(&format!(
+                    "Could not read the selection ({e}); default organizations left unchanged"
+                ));
+                return;
+            }
+        }
+        match parse_selection(&line, orgs.len()) {
+            Ok(None) => return,
+            Ok(Some(picked)) => {
+                let picked: Vec<String> = picked.iter().map(|&i| orgs[i].login.clone()).collect();
+                defaults.orgs = (!picked.is_empty()).then_some(picked);
+                return;
+            }
+            Err(e) => eprintln!("{e}"),
+        }
+    }
+}
+
+/// Parses a 1-based selection like `1,3-4` into sorted 0-based indices.
+/// `Ok(None)` means no change (blank input); `none` selects nothing.
+fn parse_selection(input: &str, count: usize) -> std::result::Result<Option<Vec<usize>>, String> {
+    let input = input.trim();
+    match input.to_ascii_lowercase().as_str() {
+        "" => return Ok(None),
+        "all" => return Ok(Some((0..count).collect())),
+        "none" => return Ok(Some(Vec::new())),
+        _ => {}
+    }
+
+    let number = |s: &str| match s.trim().parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Ok(n - 1),
+        _ => Err(format!("'{}' is not a number from 1 to {count}", s.trim())),
+    };
+
+    let mut picked = Vec::new();
+    for part in input.split(',').filter(|p| !p.trim().is_empty()) {
+        match part.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (number(start)?, number(end)?);
+                picked.extend(start.min(end)..=start.max(end));
+            }
+            None => picked.push(number(part)?),
+        }
+    }
+    picked.sort_unstable();
+    picked.dedup();
+    Ok(Some(picked))
+}
+
+/// The `gh` hostname for `api_url`: github.com, or the Enterprise Server host.
+fn gh_host(api_url: Option<&str>) -> String {
+    api_url
+        .map(|url| {
+            let url = url.split_once("://").map_or(url, |(_, rest)| rest);
+            url.split('/').next().unwrap_or(url).to_string()
+        })
+        .unwrap_or_else(|| "github.com".to_string())
+}
+
+/// Asks `gh auth token` first (it knows about keyring storage), then falls
+/// back to reading `oauth_token` from gh's `hosts.yml`.
+fn gh_token(host: &str) -> Result<String> {
+    let output = Command::new("gh")
+        .args(["auth", "token", "--hostname", host])
+        .output();
+    if let Ok(output) = output {
+        let token = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        if output.status.success() && !token.is_empty() {
+            return Ok(token);
+        }
+    }
+
+    gh_hosts_path()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|hosts| hosts_yml_token(&hosts, host))
+        .ok_or_else(|| {
+            GitorgError::Config(format!(
+                "No GitHub CLI token found for {host}. Run `gh auth login` or pass --token."
+            ))
+        })
+}
+
+fn gh_hosts_path() -> Option<PathBuf> {
+    let dir = if let Ok(dir) = std::env::var("GH_CONFIG_DIR") {
+        PathBuf::from(dir)
+    } else if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
+        PathBuf::from(xdg).join("gh")
+    } else {
+        dirs::home_dir()?.join(".config").join("gh")
+    };
+    Some(dir.join("hosts.yml"))
+}
+
+/// Pulls `<host>.oauth_token` out of gh's hosts.yml. The file is a flat
+/// two-level mapping, so a line scan is enough without a YAML parser.
+fn hosts_yml_token(hosts: &str, host: &str) -> Option<String> {
+    let mut in_host = false;
+    for line in hosts.lines() {
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            continue;
+        }
+        if !line.starts_with(char::is_whitespace) {
+            in_host = line.trim_end().trim_end_matches(':') == host;
+            continue;
+        }
+        if in_host {
+            if let Some(value) = line.trim().strip_prefix("oauth_token:") {
+                let value = value.trim().trim_matches(|c| c == '"' || c == '\'');
+                if !value.is_empty() {
+                    return Some(value.to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Token creation page on the web host behind `api_url` (github.com by default).
+fn token_page_url(api_url: Option<&str>) -> String {
+    format!(
+        "{}/settings/tokens/new?description=gitorg&scopes=read:org,repo",
+        web_url(api_url)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_scopes_accepts_broader_org_scopes() {
+        let scopes = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(missing_scopes(&scopes(&["repo", "read:org"])).is_empty());
+        assert!(missing_scopes(&scopes(&["repo", "admin:org"])).is_empty());
+        assert_eq!(missing_scopes(&scopes(&["repo"])), vec!["read:org"]);
+        assert_eq!(missing_scopes(&[]), vec!["repo", "read:org"]);
+    }
+
+    #[test]
+    fn selection_accepts_lists_ranges_and_keywords() {
+        assert_eq!(parse_selection("\n", 4), Ok(None));
+        assert_eq!(parse_selection("all", 3), Ok(Some(vec![0, 1, 2])));
+        assert_eq!(parse_selection("None", 3), Ok(Some(vec![])));
+        assert_eq!(parse_selection("3, 1", 4), Ok(Some(vec![0, 2])));
+        assert_eq!(parse_selection("4-2,2", 4), Ok(Some(vec![1, 2, 3])));
+    }
+
+    #[test]
+    fn selection_rejects_out_of_range_input() {
+        assert!(parse_selection("0", 3).is_err());
+        assert!(parse_selection("4", 3).is_err());
+        assert!(parse_selection("1-9", 3).is_err());
+        assert!(parse_selection("acme", 3).is_err());
+    }
+
+    #[test]
+    fn gh_host_defaults_to_github_com() {
+        assert_eq!(gh_host(None), "github.com");
+        assert_eq!(
+            gh_host(Some("https://ghe.example.com/api/v3")),
+            "ghe.example.com"
+        );
+    }
+
+    #[test]
+    fn hosts_yml_token_matches_host() {
+        let hosts = "\
+github.com:
+    user: octocat
+    oauth_token: gho_public
+    git_protocol: https
+ghe.example.com:
+    oauth_token: \"gho_enterprise\"
+";
+        assert_eq!(
+            hosts_yml_token(hosts, "github.com").as_deref(),
+            Some("gho_public")
+        );
+        assert_eq!(
+            hosts_yml_token(hosts, "ghe.example.com").as_deref(),
+            Some("gho_enterprise")
+        );
+        assert_eq!(hosts_yml_token(hosts, "other.example.com"), None);
+    }
+
+    #[test]
+    fn hosts_yml_without_token_yields_none() {
+        // Newer gh versions keep the token in the system keyring
+        let hosts = "github.com:\n    user: octocat\n    git_protocol: ssh\n";
+        assert_eq!(hosts_yml_token(hosts, "github.com"), None);
+    }
+}
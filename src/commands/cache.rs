@@ -0,0 +1,9 @@
+use crate::cache::clear_cache;
+use crate::display;
+use crate::error::Result;
+
+pub fn clear() -> Result<()> {
+    let removed = clear_cache()?;
+    display::success(&format!("Removed {removed} cached repo listing(s)"));
+    Ok(())
+}
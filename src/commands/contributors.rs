@@ -0,0 +1,262 @@
+use crate::commands::{github_client, resolve_orgs, GlobalArgs, OrgFailures};
+use crate::config::Config;
+use crate::display;
+use crate::error::Result;
+use crate::github::{Contributor, GithubClient, RepoVisibility};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize)]
+pub struct ContributorSummary {
+    pub login: String,
+    pub contributions: u32,
+    pub repos: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LeaderboardEntry {
+    pub rank: usize,
+    pub login: String,
+    pub contributions: u32,
+    pub repos: usize,
+}
+
+pub async fn run(org: &Option<String>, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let summaries = collect(org, global, &config, &client).await?;
+
+    display::output_records(global.json, &summaries, |data| {
+        render_contributors_table(data);
+    })?;
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// Ranks the `top` contributors across every repo of the orgs.
+pub async fn leaderboard(org: &Option<String>, top: usize, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let summaries = collect(org, global, &config, &client).await?;
+    let total = summaries.len();
+    let entries = rank(summaries, top);
+
+    display::output_records(global.json, &entries, |data| {
+        render_leaderboard(data, total);
+    })?;
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// Contributors summed across the non-empty, unarchived repos of the orgs.
+async fn collect(
+    org: &Option<String>,
+    global: &GlobalArgs,
+    config: &Config,
+    client: &GithubClient,
+) -> Result<Vec<ContributorSummary>> {
+    client.warn_if_rate_limited().await.ok();
+
+    let orgs = resolve_orgs(org, &global.exclude_orgs, config, client).await?;
+
+    let mut failures = OrgFailures::new(orgs.len());
+    let mut per_repo = Vec::new();
+    for org_name in &orgs {
+        let repos = match client.list_org_repos(org_name, RepoVisibility::All).await {
+            Ok(r) => r,
+            Err(e) => {
+                failures.repos_failed(org, org_name, e)?;
+                continue;
+            }
+        };
+        failures.listed(org_name, repos.len());
+
+        let candidates = repos
+            .iter()
+            .filter(|repo| !repo.archived.unwrap_or(false) && repo.size.unwrap_or(0) > 0);
+        let results: Vec<_> = stream::iter(candidates)
+            .map(|repo| async move {
+                let contributors = client.list_repo_contributors(org_name, &repo.name).await;
+                (&repo.name, contributors)
+            })
+            .buffer_unordered(client.max_concurrency())
+            .collect()
+            .await;
+
+        for (repo, result) in results {
+            match result {
+                Ok(contributors) => per_repo.push(contributors),
+                Err(e) => {
+                    display::warn(&format!(
+                        "Failed to fetch contributors for {org_name}/{repo}: {e}"
+                    ));
+                    failures.partly_failed();
+                }
+            }
+        }
+    }
+    failures.finish()?;
+
+    Ok(aggregate(&per_repo))
+}
+
+/// Sums contributions by login across repos, most active first.
+fn aggregate(per_repo: &[Vec<Contributor>]) -> Vec<ContributorSummary> {
+    let mut totals: HashMap<&str, (u32, usize)> = HashMap::new();
+    for contributors in per_repo {
+        for c in contributors {
+            let entry = totals.entry(&c.login).or_insert((0, 0));
+            entry.0 += c.contributions;
+            entry.1 += 1;
+        }
+    }
+
+    let mut summaries: Vec<ContributorSummary> = totals
+        .into_iter()
+        .map(|(login, (contributions, repos))| ContributorSummary {
+            login: login.to_string(),
+            contributions,
+            repos,
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        b.contributions
+            .cmp(&a.contributions)
+            .then_with(|| a.login.cmp(&b.login))
+    });
+    summaries
+}
+
+/// Numbers the first `top` of `summaries` (most active first); equal
+/// totals share a rank, so 1, 2, 2, 4.
+fn rank(summaries: Vec<ContributorSummary>, top: usize) -> Vec<LeaderboardEntry> {
+    let mut entries: Vec<LeaderboardEntry> = Vec::new();
+    for (i, summary) in summaries.into_iter().take(top).enumerate() {
+        let rank = match entries.last() {
+            Some(prev) if prev.contributions == summary.contributions => prev.rank,
+            _ => i + 1,
+        };
+        entries.push(LeaderboardEntry {
+            rank,
+            login: summary.login,
+            contributions: summary.contributions,
+            repos: summary.repos,
+        });
+    }
+    entries
+}
+
+fn render_leaderboard(entries: &[LeaderboardEntry], total: usize) {
+    if entries.is_empty() {
+        display::warn("No contributors found.");
+        return;
+    }
+
+    display::section_header("Leaderboard");
+
+    let mut table = display::new_table(&["Rank", "Login", "Contributions", "Repos"]);
+
+    for e in entries {
+        table.add_row(vec![
+            e.rank.to_string(),
+            e.login.clone(),
+            e.contributions.to_string(),
+            e.repos.to_string(),
+        ]);
+    }
+
+    display::print_table(&table);
+    display::count_footer(entries.len(), total, "contributor(s)");
+}
+
+fn render_contributors_table(contributors: &[ContributorSummary]) {
+    if contributors.is_empty() {
+        display::warn("No contributors found.");
+        return;
+    }
+
+    display::section_header("Contributors");
+
+    let mut table = display::new_table(&["Login", "Contributions", "Repos"]);
+
+    for c in contributors {
+        table.add_row(vec![
+            c.login.clone(),
+            c.contributions.to_string(),
+            c.repos.to_string(),
+        ]);
+    }
+
+    display::print_table(&table);
+    display::count_footer(contributors.len(), contributors.len(), "contributor(s)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contributor(login: &str, contributions: u32) -> Contributor {
+        Contributor {
+            login: login.to_string(),
+            contributions,
+        }
+    }
+
+    #[test]
+    fn aggregates_across_repos_by_contributions() {
+        let per_repo = vec![
+            vec![contributor("alice", 10), contributor("bob", 3)],
+            vec![contributor("bob", 20), contributor("carol", 1)],
+            vec![contributor("alice", 5)],
+        ];
+
+        let summaries = aggregate(&per_repo);
+        assert_eq!(summaries.len(), 3);
+        assert_eq!(summaries[0].login, "bob");
+        assert_eq!(summaries[0].contributions, 23);
+        assert_eq!(summaries[0].repos, 2);
+        assert_eq!(summaries[1].login, "alice");
+        assert_eq!(summaries[1].contributions, 15);
+        assert_eq!(summaries[1].repos, 2);
+        assert_eq!(summaries[2].login, "carol");
+    }
+
+    #[test]
+    fn leaderboard_shares_ranks_on_ties_and_cuts_at_top() {
+        let per_repo = vec![vec![
+            contributor("alice", 9),
+            contributor("bob", 5),
+            contributor("carol", 5),
+            contributor("dave", 2),
+            contributor("erin", 1),
+        ]];
+
+        let entries = rank(aggregate(&per_repo), 4);
+        let ranks: Vec<(usize, &str)> =
+            entries.iter().map(|e| (e.rank, e.login.as_str())).collect();
+        assert_eq!(
+            ranks,
+            vec![(1, "alice"), (2, "bob"), (2, "carol"), (4, "dave")]
+        );
+        assert!(rank(Vec::new(), 20).is_empty());
+    }
+
+    #[test]
+    fn ties_break_by_login() {
+        let per_repo = vec![vec![contributor("zed", 4), contributor("amy", 4)]];
+        let summaries = aggregate(&per_repo);
+        assert_eq!(summaries[0].login, "amy");
+        assert_eq!(summaries[1].login, "zed");
+    }
+}
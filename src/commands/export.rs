@@ -0,0 +1,158 @@
+use crate::commands::issues::{self, IssueSummary, IssuesArgs, LabelMatch};
+use crate::commands::orgs::{self, OrgSummary};
+use crate::commands::stale::{self, StaleArgs, StaleCutoff, StaleRepo};
+use crate::commands::stats::{self, OrgStats, StatsArgs};
+use crate::commands::{github_client, resolve_orgs, ActivityMetric, GlobalArgs};
+use crate::display::{self, RecordSink};
+use crate::error::Result;
+use crate::github::RepoVisibility;
+use crate::webhook::WebhookFormat;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// Everything `export` writes: the output of `orgs`, `stats`, `stale`, and
+/// `issues` with their default options, gathered in one run.
+#[derive(Debug, Serialize)]
+pub struct ExportReport {
+    pub generated_at: DateTime<Utc>,
+    pub orgs: Vec<OrgSummary>,
+    pub stats: OrgStats,
+    pub stale: Vec<StaleRepo>,
+    pub stale_cutoff: StaleCutoff,
+    pub issues: Vec<IssueSummary>,
+}
+
+/// Writes the combined report as JSON with `--json`, Markdown otherwise.
+pub async fn run(org: &Option<String>, days: Option<u64>, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+    let generated_at = Utc::now();
+
+    let names = resolve_orgs(org, &global.exclude_orgs, &config, &client).await?;
+    let mut org_summaries = orgs::collect(&client, false).await?;
+    org_summaries.retain(|o| names.iter().any(|n| n.eq_ignore_ascii_case(&o.name)));
+
+    let stats_args = StatsArgs {
+        org: org.clone(),
+        ..Default::default()
+    };
+    let stats = stats::collect(&stats_args, false, global, &config, &client)
+        .await?
+        .total(&stats_args, None);
+
+    let stale_args = StaleArgs {
+        org: org.clone(),
+        days,
+        since: None,
+        sort: "days".to_string(),
+        limit: None,
+        visibility: RepoVisibility::default(),
+        activity_metric: ActivityMetric::default(),
+        fail_on_match: false,
+        webhook: None,
+        webhook_format: WebhookFormat::default(),
+    };
+    let stale_cutoff = StaleCutoff::from_args(&stale_args, &config)?;
+    let stale = stale::collect(&stale_args, stale_cutoff, global, &config, &client).await?;
+
+    let issues_args = IssuesArgs {
+        org: org.clone(),
+        since_last_run: false,
+        milestone: None,
+        sort: None,
+        limit: None,
+        visibility: RepoVisibility::default(),
+        no_search: false,
+        all_repos: false,
+        author: None,
+        labels: Vec::new(),
+        label_match: LabelMatch::default(),
+        updated_within: None,
+        stale_within: None,
+        fail_on_match: false,
+    };
+    let (issues, _) = issues::collect(
+        &issues_args,
+        generated_at,
+        global,
+        &config,
+        &client,
+        &mut RecordSink::collecting(),
+    )
+    .await?;
+
+    let report = ExportReport {
+        generated_at,
+        orgs: org_summaries,
+        stats,
+        stale,
+        stale_cutoff,
+        issues,
+    };
+
+    if !global.json {
+        // The report is a document to keep, so never colored
+        display::init_color(true);
+        display::set_markdown(true);
+    }
+    display::output(global.json, &report, render_report);
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// A Markdown document with one section per command, each rendered the
+/// way that command's `--markdown` output is.
+fn render_report(report: &ExportReport) {
+    display::outln!("# gitorg report");
+    display::outln!(
+        "\nGenerated {}.",
+        report.generated_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    orgs::render_orgs_table(&report.orgs, false);
+    stats::render_stats("Organization Statistics", &report.stats);
+    stale::render_stale_repos(
+        &report.stale,
+        report.stale_cutoff,
+        ActivityMetric::default(),
+        report.stale.len(),
+    );
+    issues::render_issues_table(&report.issues, report.issues.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_serializes_one_key_per_section() {
+        let stats_args = StatsArgs::default();
+        let report = ExportReport {
+            generated_at: Utc::now(),
+            orgs: Vec::new(),
+            stats: stats::CollectedStats::default().total(&stats_args, None),
+            stale: Vec::new(),
+            stale_cutoff: StaleCutoff::Days(90),
+            issues: Vec::new(),
+        };
+        let value = serde_json::to_value(&report).unwrap();
+        let mut keys: Vec<&String> = value.as_object().unwrap().keys().collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            [
+                "generated_at",
+                "issues",
+                "orgs",
+                "stale",
+                "stale_cutoff",
+                "stats"
+            ]
+        );
+        assert_eq!(value["stale_cutoff"], serde_json::json!({"days": 90}));
+    }
+}
@@ -0,0 +1,255 @@
+use crate::commands::GlobalArgs;
+use crate::display;
+use crate::error::{GitorgError, Result};
+use serde::Serialize;
+
+/// The record fields of each list command: its `--json` keys, and the
+/// names `--format` templates can use. Keep in step with the record
+/// structs; the tests below check the simple ones.
+const FIELDS: &[(&str, &[&str])] = &[
+    ("alerts", &["org", "name", "open_alerts"]),
+    ("contributors", &["login", "contributions", "repos"]),
+    (
+        "issues",
+        &[
+            "org",
+            "repo",
+            "number",
+            "title",
+            "author",
+            "labels",
+            "milestone",
+            "assignees",
+            "comments",
+            "created_at",
+            "updated_at",
+        ],
+    ),
+    ("leaderboard", &["rank", "login", "contributions", "repos"]),
+    ("members", &["org", "login", "role", "two_factor"]),
+    (
+        "orgs",
+        &[
+            "name",
+            "description",
+            "url",
+            "display_name",
+            "public_repos",
+            "members",
+            "website",
+            "avatar_url",
+        ],
+    ),
+    ("profile", &["name", "active", "authenticated", "orgs"]),
+    (
+        "protection",
+        &[
+            "org",
+            "name",
+            "branch",
+            "protected",
+            "required_reviews",
+            "required_status_checks",
+        ],
+    ),
+    (
+        "releases",
+        &["org", "name", "tag", "published_at", "days_since_release"],
+    ),
+    (
+        "repos",
+        &[
+            "org",
+            "name",
+            "description",
+            "homepage",
+            "language",
+            "topics",
+            "stars",
+            "forks",
+            "open_issues",
+            "size_kb",
+            "pushed_at",
+            "created_at",
+            "status",
+            "has_discussions",
+            "has_license",
+            "has_readme",
+            "health",
+            "badges",
+        ],
+    ),
+    ("snapshot list", &["name", "taken_at", "repos"]),
+    (
+        "stale",
+        &[
+            "org",
+            "name",
+            "last_push",
+            "days_stale",
+            "stars",
+            "language",
+            "cutoff",
+        ],
+    ),
+    ("teams", &["org", "name", "slug", "privacy", "members"]),
+];
+
+#[derive(Debug, Serialize)]
+pub struct CommandFields {
+    pub command: String,
+    pub fields: Vec<String>,
+}
+
+/// Lists the fields of `command` (e.g. `repos` or `snapshot list`), or of
+/// every list command.
+pub fn run(command: &[String], global: &GlobalArgs) -> Result<()> {
+    let entries = select(&command.join(" "))?;
+    display::output_records(global.json, &entries, |data| {
+        render_fields_table(data);
+    })
+}
+
+fn select(command: &str) -> Result<Vec<CommandFields>> {
+    let command = command.trim().to_lowercase();
+    let entries: Vec<CommandFields> = FIELDS
+        .iter()
+        .filter(|(name, _)| command.is_empty() || *name == command)
+        .map(|(name, fields)| CommandFields {
+            command: name.to_string(),
+            fields: fields.iter().map(|f| f.to_string()).collect(),
+        })
+        .collect();
+    if entries.is_empty() {
+        let known: Vec<&str> = FIELDS.iter().map(|(name, _)| *name).collect();
+        return Err(GitorgError::Config(format!(
+            "'{command}' has no record fields. Commands that do: {}",
+            known.join(", ")
+        )));
+    }
+    Ok(entries)
+}
+
+fn render_fields_table(entries: &[CommandFields]) {
+    display::section_header("Fields for --format (and --json keys)");
+
+    let mut table = display::new_table(&["Command", "Fields"]);
+    for entry in entries {
+        table.add_row(vec![entry.command.clone(), entry.fields.join(", ")]);
+    }
+    display::print_table(&table);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::alerts::AlertCount;
+    use crate::commands::contributors::{ContributorSummary, LeaderboardEntry};
+    use crate::commands::members::MemberSeat;
+    use crate::commands::protection::ProtectionStatus;
+    use crate::commands::releases::RepoRelease;
+    use crate::commands::snapshot::SnapshotInfo;
+    use crate::commands::teams::TeamSummary;
+
+    fn keys<T: Serialize>(record: &T) -> Vec<String> {
+        let value = serde_json::to_value(record).unwrap();
+        let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    fn listed(command: &str) -> Vec<String> {
+        let mut fields = select(command).unwrap().remove(0).fields;
+        fields.sort();
+        fields
+    }
+
+    #[test]
+    fn lists_match_the_records() {
+        let org = || "acme".to_string();
+        assert_eq!(
+            keys(&AlertCount {
+                org: org(),
+                name: "api".into(),
+                open_alerts: None,
+            }),
+            listed("alerts")
+        );
+        assert_eq!(
+            keys(&ContributorSummary {
+                login: "octocat".into(),
+                contributions: 3,
+                repos: 1,
+            }),
+            listed("contributors")
+        );
+        assert_eq!(
+            keys(&LeaderboardEntry {
+                rank: 1,
+                login: "octocat".into(),
+                contributions: 3,
+                repos: 1,
+            }),
+            listed("leaderboard")
+        );
+        assert_eq!(
+            keys(&MemberSeat {
+                org: org(),
+                login: "octocat".into(),
+                role: "admin".into(),
+                two_factor: None,
+            }),
+            listed("members")
+        );
+        assert_eq!(
+            keys(&ProtectionStatus {
+                org: org(),
+                name: "api".into(),
+                branch: "main".into(),
+                protected: None,
+                required_reviews: None,
+                required_status_checks: None,
+            }),
+            listed("protection")
+        );
+        assert_eq!(
+            keys(&RepoRelease {
+                org: org(),
+                name: "api".into(),
+                tag: None,
+                published_at: None,
+                days_since_release: None,
+            }),
+            listed("releases")
+        );
+        assert_eq!(
+            keys(&SnapshotInfo {
+                name: "20240101T000000Z".into(),
+                taken_at: chrono::Utc::now(),
+                repos: 3,
+            }),
+            listed("snapshot list")
+        );
+        assert_eq!(
+            keys(&TeamSummary {
+                org: org(),
+                name: "Core".into(),
+                slug: "core".into(),
+                privacy: "closed".into(),
+                members: None,
+            }),
+            listed("teams")
+        );
+    }
+
+    #[test]
+    fn select_filters_by_command() {
+        assert_eq!(select("").unwrap().len(), FIELDS.len());
+        assert_eq!(select(" Repos ").unwrap()[0].command, "repos");
+        let err = select("overview").unwrap_err();
+        assert!(
+            err.to_string().contains("Commands that do: alerts"),
+            "{err}"
+        );
+    }
+}
@@ -1,9 +1,71 @@
-use crate::commands::resolve_orgs;
-use crate::config::load_config;
-use crate::display;
+use crate::commands::{apply_limit, github_client, resolve_orgs, GlobalArgs, OrgFailures};
+use crate::config::Config;
+use crate::display::{self, RecordSink};
 use crate::error::Result;
-use crate::github::GithubClient;
+use crate::github::{GithubClient, RepoVisibility};
+use crate::last_run::{changed_since, load_last_run, record_last_run};
+use chrono::{DateTime, Duration, Utc};
+use clap::Args;
+use futures::stream::{self, StreamExt};
+use octocrab::models::issues::Issue;
 use serde::Serialize;
+use std::cmp::Reverse;
+
+#[derive(Args, Debug)]
+pub struct IssuesArgs {
+    /// Filter to specific organizations (comma-separated)
+    #[arg(long)]
+    pub org: Option<String>,
+    /// Only show issues updated since the last `--since-last-run` invocation
+    #[arg(long)]
+    pub since_last_run: bool,
+    /// Only show issues in this milestone (title, number, or "none")
+    #[arg(long)]
+    pub milestone: Option<String>,
+    /// Sort by: updated, created, comments, repo [default: updated]
+    #[arg(long)]
+    pub sort: Option<String>,
+    /// Show at most this many issues (after sorting)
+    #[arg(long)]
+    pub limit: Option<usize>,
+    /// Which repos to include: all, public, or private
+    #[arg(long, value_enum, default_value_t)]
+    pub visibility: RepoVisibility,
+    /// Fetch issues repo by repo instead of via the search API (slower, but
+    /// never behind the search index)
+    #[arg(long)]
+    pub no_search: bool,
+    /// With the per-repo fetch, also query repos whose open-issue count is
+    /// zero, since that count can lag (implies --no-search; one more request
+    /// per repo)
+    #[arg(long)]
+    pub all_repos: bool,
+    /// Only show issues opened by this user
+    #[arg(long, value_name = "LOGIN")]
+    pub author: Option<String>,
+    /// Only show issues with this label (repeatable)
+    #[arg(long = "label", value_name = "NAME")]
+    pub labels: Vec<String>,
+    /// Whether issues need all of the `--label`s or any one of them
+    #[arg(long = "match", value_enum, default_value_t)]
+    pub label_match: LabelMatch,
+    /// Only show issues updated in the last N days
+    #[arg(long, value_name = "DAYS")]
+    pub updated_within: Option<u64>,
+    /// Only show issues not updated in the last N days
+    #[arg(long, value_name = "DAYS")]
+    pub stale_within: Option<u64>,
+    /// Exit with status 2 if any issues are found (for CI gating)
+    #[arg(long)]
+    pub fail_on_match: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LabelMatch {
+    #[default]
+    All,
+    Any,
+}
 
 #[derive(Debug, Serialize)]
 pub struct IssueSummary {
@@ -13,83 +75,355 @@ pub struct IssueSummary {
     pub title: String,
     pub author: String,
     pub labels: String,
-    pub updated: String,
+    pub milestone: Option<String>,
+    /// Comma-joined assignee logins, "-" when unassigned
+    pub assignees: String,
+    pub comments: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// How `--milestone` maps onto the issues API. Numbers, `none`, and `*` are
+/// passed straight through; anything else is a title that has to be resolved
+/// to a milestone number in each repo.
+#[derive(Debug, PartialEq)]
+enum MilestoneFilter {
+    Param(String),
+    Title(String),
+}
+
+impl MilestoneFilter {
+    fn parse(value: &str) -> Self {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("none") {
+            MilestoneFilter::Param("none".to_string())
+        } else if value == "*" || value.parse::<u64>().is_ok() {
+            MilestoneFilter::Param(value.to_string())
+        } else {
+            MilestoneFilter::Title(value.to_string())
+        }
+    }
 }
 
-pub async fn run(org: &Option<String>, json: bool, verbose: bool) -> Result<()> {
-    let config = load_config()?;
-    let token = config.token()?;
-    let client = GithubClient::new(token, verbose)?;
+/// Returns how many issues matched (before `--limit`).
+pub async fn run(args: &IssuesArgs, global: &GlobalArgs) -> Result<usize> {
+    let config = global.load_config()?;
+    let mut client = github_client(&config, global)?;
+    if args.since_last_run {
+        // A cached repo listing predates `started_at`
+        client = client.with_fresh_listings();
+    }
+
+    // Without --sort or --limit, --ndjson prints each issue as it's found
+    let mut sink = RecordSink::new(global.json, args.sort.is_none() && args.limit.is_none());
+    let started_at = Utc::now();
+    let (mut all_issues, complete) =
+        collect(args, started_at, global, &config, &client, &mut sink).await?;
+    let total = sink.count();
+    apply_limit(&mut all_issues, args.limit);
+
+    display::output_records(global.json, &all_issues, |data| {
+        render_issues_table(data, total);
+    })?;
+
+    // Orgs or repos that failed would otherwise be skipped past for good
+    if args.since_last_run && complete {
+        record_last_run("issues", started_at)?;
+    }
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
 
+    Ok(total)
+}
+
+/// The open issues (not PRs) across the orgs that pass the filters in
+/// `args`, in `--sort` order, and whether every org loaded in full. Each
+/// issue goes through `sink`, so a streaming one returns none.
+/// `started_at` anchors the day windows.
+pub async fn collect(
+    args: &IssuesArgs,
+    started_at: DateTime<Utc>,
+    global: &GlobalArgs,
+    config: &Config,
+    client: &GithubClient,
+    sink: &mut RecordSink<IssueSummary>,
+) -> Result<(Vec<IssueSummary>, bool)> {
     client.warn_if_rate_limited().await.ok();
 
-    let orgs = resolve_orgs(org, &config, &client).await?;
+    let since = if args.since_last_run {
+        load_last_run()?.get("issues")
+    } else {
+        None
+    };
 
-    let mut all_issues = Vec::new();
+    let orgs = resolve_orgs(&args.org, &global.exclude_orgs, config, client).await?;
+    let milestone_filter = args.milestone.as_deref().map(MilestoneFilter::parse);
 
+    let mut failures = OrgFailures::new(orgs.len());
     for org_name in &orgs {
-        let repos = match client.list_org_repos(org_name).await {
-            Ok(r) => r,
-            Err(e) => {
-                display::warn(&format!("Failed to fetch repos for {org_name}: {e}"));
-                continue;
+        let query = if args.no_search || args.all_repos {
+            None
+        } else {
+            search_query(
+                org_name,
+                args.visibility,
+                milestone_filter.as_ref(),
+                args.author.as_deref(),
+            )
+        };
+
+        let searched = match query {
+            Some(query) => match client.search_org_issues(org_name, &query).await {
+                Ok(Some(issues)) => Some(issues),
+                Ok(None) => {
+                    display::warn(&format!(
+                        "Over 1,000 open issues in {org_name} match, more than a search \
+                         returns; listing them repo by repo instead"
+                    ));
+                    None
+                }
+                Err(e) => {
+                    failures.repos_failed(&args.org, org_name, e)?;
+                    continue;
+                }
+            },
+            None => None,
+        };
+        let issues = match searched {
+            Some(issues) => issues
+                .into_iter()
+                .map(|issue| (repo_name(&issue), issue))
+                .collect(),
+            None => {
+                list_issues_per_repo(
+                    client,
+                    args,
+                    org_name,
+                    milestone_filter.as_ref(),
+                    &mut failures,
+                )
+                .await?
             }
         };
 
-        for repo in &repos {
-            if repo.archived.unwrap_or(false) {
+        for (repo, issue) in &issues {
+            // Filter out pull requests
+            if issue.pull_request.is_some() {
+                continue;
+            }
+            if !changed_since(Some(issue.updated_at), since) {
+                continue;
+            }
+            if !updated_in_window(
+                issue.updated_at,
+                args.updated_within,
+                args.stale_within,
+                started_at,
+            ) {
                 continue;
             }
-            if repo.open_issues_count.unwrap_or(0) == 0 {
+            if !args
+                .author
+                .as_ref()
+                .map_or(true, |author| issue.user.login.eq_ignore_ascii_case(author))
+            {
                 continue;
             }
 
-            let issues = match client.list_repo_issues(org_name, &repo.name).await {
-                Ok(i) => i,
-                Err(e) => {
-                    display::warn(&format!(
-                        "Failed to fetch issues for {}/{}: {e}",
-                        org_name, repo.name
-                    ));
-                    continue;
-                }
-            };
+            let labels: Vec<String> = issue.labels.iter().map(|l| l.name.clone()).collect();
+            if !matches_labels(&labels, &args.labels, args.label_match) {
+                continue;
+            }
 
-            for issue in &issues {
-                // Filter out pull requests
-                if issue.pull_request.is_some() {
-                    continue;
-                }
+            sink.push(IssueSummary {
+                org: org_name.clone(),
+                repo: repo.clone(),
+                number: issue.number,
+                title: issue.title.clone(),
+                author: issue.user.login.clone(),
+                labels: join_or_dash(labels.iter().map(String::as_str)),
+                milestone: issue.milestone.as_ref().map(|m| m.title.clone()),
+                assignees: join_or_dash(issue.assignees.iter().map(|a| a.login.as_str())),
+                comments: issue.comments,
+                created_at: issue.created_at,
+                updated_at: issue.updated_at,
+            });
+        }
+    }
+
+    let complete = failures.finish()?;
+
+    let mut all_issues = sink.take_records();
+    sort_issues(&mut all_issues, args.sort.as_deref().unwrap_or("updated"));
+    Ok((all_issues, complete))
+}
+
+fn sort_issues(issues: &mut [IssueSummary], sort: &str) {
+    match sort {
+        "created" => issues.sort_by_key(|i| Reverse(i.created_at)),
+        "comments" => issues.sort_by_key(|i| Reverse(i.comments)),
+        "repo" => issues.sort_by(|a, b| {
+            (a.org.to_lowercase(), a.repo.to_lowercase(), a.number).cmp(&(
+                b.org.to_lowercase(),
+                b.repo.to_lowercase(),
+                b.number,
+            ))
+        }),
+        _ => issues.sort_by_key(|i| Reverse(i.updated_at)), // updated (most recent first)
+    }
+}
+
+/// Comma-joins names for a table cell, or "-" when there are none.
+fn join_or_dash<'a>(names: impl Iterator<Item = &'a str>) -> String {
+    let joined = names.collect::<Vec<_>>().join(", ");
+    if joined.is_empty() {
+        "-".to_string()
+    } else {
+        joined
+    }
+}
 
-                let labels: Vec<String> = issue.labels.iter().map(|l| l.name.clone()).collect();
-
-                all_issues.push(IssueSummary {
-                    org: org_name.clone(),
-                    repo: repo.name.clone(),
-                    number: issue.number,
-                    title: issue.title.clone(),
-                    author: issue.user.login.clone(),
-                    labels: if labels.is_empty() {
-                        "-".to_string()
-                    } else {
-                        labels.join(", ")
-                    },
-                    updated: issue.updated_at.format("%Y-%m-%d").to_string(),
-                });
+/// Whether `updated_at` falls inside `--updated-within` and outside
+/// `--stale-within`; given both, issues last touched between the two ages.
+fn updated_in_window(
+    updated_at: DateTime<Utc>,
+    updated_within: Option<u64>,
+    stale_within: Option<u64>,
+    now: DateTime<Utc>,
+) -> bool {
+    let age = now - updated_at;
+    let days = |d: u64| Duration::days(d as i64);
+    updated_within.map_or(true, |d| age < days(d)) && stale_within.map_or(true, |d| age >= days(d))
+}
+
+/// Case-insensitive label check; no wanted labels matches everything.
+fn matches_labels(labels: &[String], wanted: &[String], mode: LabelMatch) -> bool {
+    let has = |w: &String| labels.iter().any(|l| l.eq_ignore_ascii_case(w));
+    match mode {
+        LabelMatch::All => wanted.iter().all(has),
+        LabelMatch::Any => wanted.is_empty() || wanted.iter().any(has),
+    }
+}
+
+/// The search query covering every open issue `run` wants from `org`, or
+/// `None` when the filters can only be applied repo by repo (milestone
+/// numbers and `*` have no search qualifier).
+fn search_query(
+    org: &str,
+    visibility: RepoVisibility,
+    milestone: Option<&MilestoneFilter>,
+    author: Option<&str>,
+) -> Option<String> {
+    let mut query = format!("org:{org} is:issue is:open archived:false");
+    if let Some(author) = author {
+        query.push_str(&format!(" author:{}", author.trim()));
+    }
+    match visibility {
+        RepoVisibility::All => {}
+        RepoVisibility::Public => query.push_str(" is:public"),
+        RepoVisibility::Private => query.push_str(" is:private"),
+    }
+    match milestone {
+        None => {}
+        Some(MilestoneFilter::Param(param)) if param == "none" => query.push_str(" no:milestone"),
+        Some(MilestoneFilter::Param(_)) => return None,
+        Some(MilestoneFilter::Title(title)) => {
+            query.push_str(&format!(" milestone:\"{}\"", title.replace('"', "")))
+        }
+    }
+    Some(query)
+}
+
+/// Repo name from a search result's `repository_url` (`.../repos/{owner}/{repo}`).
+fn repo_name(issue: &Issue) -> String {
+    issue
+        .repository_url
+        .path_segments()
+        .and_then(|mut segments| segments.next_back())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// The pre-search path: list the org's repos, then fetch each one's issues,
+/// `--max-concurrency` repos at a time. One request per repo, but never
+/// stale the way the search index can be.
+async fn list_issues_per_repo(
+    client: &GithubClient,
+    args: &IssuesArgs,
+    org_name: &str,
+    milestone_filter: Option<&MilestoneFilter>,
+    failures: &mut OrgFailures,
+) -> Result<Vec<(String, Issue)>> {
+    let mut all_issues = Vec::new();
+
+    let repos = match client.list_org_repos(org_name, args.visibility).await {
+        Ok(r) => r,
+        Err(e) => {
+            failures.repos_failed(&args.org, org_name, e)?;
+            return Ok(all_issues);
+        }
+    };
+
+    let candidates = repos
+        .iter()
+        .filter(|repo| !repo.archived.unwrap_or(false))
+        .filter(|repo| args.all_repos || repo.open_issues_count.unwrap_or(0) > 0);
+
+    let results: Vec<(String, Result<Vec<Issue>>)> = stream::iter(candidates)
+        .map(|repo| async move {
+            let issues = fetch_repo_issues(client, org_name, &repo.name, milestone_filter).await;
+            (repo.name.clone(), issues)
+        })
+        .buffer_unordered(client.max_concurrency())
+        .collect()
+        .await;
+
+    for (repo, result) in results {
+        match result {
+            Ok(issues) => all_issues.extend(issues.into_iter().map(|issue| (repo.clone(), issue))),
+            Err(e) => {
+                display::warn(&format!(
+                    "Failed to fetch issues for {org_name}/{repo}: {e}"
+                ));
+                failures.partly_failed();
             }
         }
     }
 
-    display::output(json, &all_issues, |data| {
-        render_issues_table(data);
-    });
+    Ok(all_issues)
+}
 
-    client.check_rate_limit_if_verbose().await;
+/// Open issues for one repo, resolving a milestone title to its number first.
+async fn fetch_repo_issues(
+    client: &GithubClient,
+    org_name: &str,
+    repo: &str,
+    milestone_filter: Option<&MilestoneFilter>,
+) -> Result<Vec<Issue>> {
+    let milestone_param = match milestone_filter {
+        None => None,
+        Some(MilestoneFilter::Param(param)) => Some(param.clone()),
+        Some(MilestoneFilter::Title(title)) => {
+            let milestones = client.list_repo_milestones(org_name, repo).await?;
+            match milestones
+                .iter()
+                .find(|m| m.title.eq_ignore_ascii_case(title))
+            {
+                Some(m) => Some(m.number.to_string()),
+                // This repo has no such milestone, so no matching issues
+                None => return Ok(Vec::new()),
+            }
+        }
+    };
 
-    Ok(())
+    client
+        .list_repo_issues(org_name, repo, milestone_param.as_deref())
+        .await
 }
 
-fn render_issues_table(issues: &[IssueSummary]) {
+pub fn render_issues_table(issues: &[IssueSummary], total: usize) {
     if issues.is_empty() {
         display::success("No open issues found.");
         return;
@@ -97,8 +431,17 @@ fn render_issues_table(issues: &[IssueSummary]) {
 
     display::section_header("Open Issues");
 
-    let mut table =
-        display::new_table(&["Org", "Repo", "#", "Title", "Author", "Labels", "Updated"]);
+    let mut table = display::new_table(&[
+        "Org",
+        "Repo",
+        "#",
+        "Title",
+        "Author",
+        "Labels",
+        "Milestone",
+        "Assignees",
+        "Updated",
+    ]);
 
     for i in issues {
         table.add_row(vec![
@@ -108,10 +451,189 @@ fn render_issues_table(issues: &[IssueSummary]) {
             &i.title,
             &i.author,
             &i.labels,
-            &i.updated,
+            i.milestone.as_deref().unwrap_or("-"),
+            &i.assignees,
+            &i.updated_at.format("%Y-%m-%d").to_string(),
         ]);
     }
 
-    println!("{table}");
-    println!("\n{} open issue(s) found.", issues.len());
+    display::print_table(&table);
+    display::count_footer(issues.len(), total, "open issue(s)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn milestone_numbers_and_keywords_pass_through() {
+        assert_eq!(
+            MilestoneFilter::parse("12"),
+            MilestoneFilter::Param("12".to_string())
+        );
+        assert_eq!(
+            MilestoneFilter::parse("None"),
+            MilestoneFilter::Param("none".to_string())
+        );
+        assert_eq!(
+            MilestoneFilter::parse("*"),
+            MilestoneFilter::Param("*".to_string())
+        );
+    }
+
+    fn make_issue(
+        repo: &str,
+        number: u64,
+        comments: u32,
+        created: &str,
+        updated: &str,
+    ) -> IssueSummary {
+        IssueSummary {
+            org: "test-org".to_string(),
+            repo: repo.to_string(),
+            number,
+            title: format!("Issue {number}"),
+            author: "octocat".to_string(),
+            labels: "-".to_string(),
+            milestone: None,
+            assignees: "-".to_string(),
+            comments,
+            created_at: created.parse().unwrap(),
+            updated_at: updated.parse().unwrap(),
+        }
+    }
+
+    fn sample_issues() -> Vec<IssueSummary> {
+        vec![
+            make_issue("beta", 1, 5, "2024-01-01T00:00:00Z", "2024-06-01T08:00:00Z"),
+            make_issue(
+                "alpha",
+                7,
+                0,
+                "2024-03-01T00:00:00Z",
+                "2024-06-01T20:00:00Z",
+            ),
+            make_issue(
+                "alpha",
+                2,
+                12,
+                "2023-12-01T00:00:00Z",
+                "2024-05-01T00:00:00Z",
+            ),
+        ]
+    }
+
+    #[test]
+    fn sort_by_updated_uses_full_timestamp() {
+        let mut issues = sample_issues();
+        sort_issues(&mut issues, "updated");
+        let numbers: Vec<u64> = issues.iter().map(|i| i.number).collect();
+        assert_eq!(numbers, vec![7, 1, 2]);
+    }
+
+    #[test]
+    fn sort_by_created_and_comments_descending() {
+        let mut issues = sample_issues();
+        sort_issues(&mut issues, "created");
+        let numbers: Vec<u64> = issues.iter().map(|i| i.number).collect();
+        assert_eq!(numbers, vec![7, 1, 2]);
+
+        sort_issues(&mut issues, "comments");
+        let numbers: Vec<u64> = issues.iter().map(|i| i.number).collect();
+        assert_eq!(numbers, vec![2, 1, 7]);
+    }
+
+    #[test]
+    fn sort_by_repo_then_number() {
+        let mut issues = sample_issues();
+        sort_issues(&mut issues, "repo");
+        let keys: Vec<(&str, u64)> = issues.iter().map(|i| (i.repo.as_str(), i.number)).collect();
+        assert_eq!(keys, vec![("alpha", 2), ("alpha", 7), ("beta", 1)]);
+    }
+
+    #[test]
+    fn names_join_with_dash_for_none() {
+        assert_eq!(join_or_dash(["alice", "bob"].into_iter()), "alice, bob");
+        assert_eq!(join_or_dash(std::iter::empty()), "-");
+    }
+
+    #[test]
+    fn label_match_all_and_any() {
+        let labels = vec!["bug".to_string(), "P1".to_string()];
+        let wanted = vec!["Bug".to_string(), "p1".to_string()];
+        assert!(matches_labels(&labels, &wanted, LabelMatch::All));
+        assert!(matches_labels(&labels, &[], LabelMatch::All));
+        assert!(matches_labels(&labels, &[], LabelMatch::Any));
+
+        let wanted = vec!["bug".to_string(), "docs".to_string()];
+        assert!(!matches_labels(&labels, &wanted, LabelMatch::All));
+        assert!(matches_labels(&labels, &wanted, LabelMatch::Any));
+        assert!(!matches_labels(&[], &wanted, LabelMatch::Any));
+    }
+
+    #[test]
+    fn updated_window_splits_active_from_forgotten() {
+        let now: DateTime<Utc> = "2024-06-30T00:00:00Z".parse().unwrap();
+        let recent: DateTime<Utc> = "2024-06-25T00:00:00Z".parse().unwrap();
+        let dormant: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+
+        assert!(updated_in_window(recent, None, None, now));
+        assert!(updated_in_window(recent, Some(30), None, now));
+        assert!(!updated_in_window(dormant, Some(30), None, now));
+        assert!(updated_in_window(dormant, None, Some(90), now));
+        assert!(!updated_in_window(recent, None, Some(90), now));
+
+        // Both: touched between one week and a year ago
+        assert!(!updated_in_window(recent, Some(365), Some(7), now));
+        assert!(updated_in_window(dormant, Some(365), Some(7), now));
+    }
+
+    #[test]
+    fn search_query_covers_filters() {
+        assert_eq!(
+            search_query("acme", RepoVisibility::All, None, None).as_deref(),
+            Some("org:acme is:issue is:open archived:false")
+        );
+        assert_eq!(
+            search_query(
+                "acme",
+                RepoVisibility::Private,
+                Some(&MilestoneFilter::Title("v1.2".into())),
+                None
+            )
+            .as_deref(),
+            Some("org:acme is:issue is:open archived:false is:private milestone:\"v1.2\"")
+        );
+        assert_eq!(
+            search_query(
+                "acme",
+                RepoVisibility::All,
+                Some(&MilestoneFilter::Param("none".into())),
+                None
+            )
+            .as_deref(),
+            Some("org:acme is:issue is:open archived:false no:milestone")
+        );
+        assert_eq!(
+            search_query("acme", RepoVisibility::All, None, Some("octocat")).as_deref(),
+            Some("org:acme is:issue is:open archived:false author:octocat")
+        );
+    }
+
+    #[test]
+    fn milestone_numbers_fall_back_to_per_repo() {
+        let filter = MilestoneFilter::Param("12".into());
+        assert_eq!(
+            search_query("acme", RepoVisibility::All, Some(&filter), None),
+            None
+        );
+    }
+
+    #[test]
+    fn milestone_titles_need_resolving() {
+        assert_eq!(
+            MilestoneFilter::parse("v1.2"),
+            MilestoneFilter::Title("v1.2".to_string())
+        );
+    }
 }
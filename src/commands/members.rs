@@ -0,0 +1,126 @@
+use crate::commands::{github_client, resolve_orgs, single_org_flag, GlobalArgs, OrgFailures};
+use crate::display;
+use crate::error::{GitorgError, Result};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct MemberSeat {
+    pub org: String,
+    pub login: String,
+    /// `admin` (an org owner) or `member`
+    pub role: String,
+    /// `None` when the token can't see 2FA status (only owners can)
+    pub two_factor: Option<bool>,
+}
+
+pub async fn run(org: &Option<String>, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let orgs = resolve_orgs(org, &global.exclude_orgs, &config, &client).await?;
+
+    let mut seats = Vec::new();
+    let mut failures = OrgFailures::new(orgs.len());
+    for org_name in &orgs {
+        let members = match client.list_org_members(org_name).await {
+            Ok(members) => members,
+            Err(e @ GitorgError::OrgNotFound(_)) if single_org_flag(org) => return Err(e),
+            Err(e) => {
+                display::warn(&format!("Failed to list members of {org_name}: {e}"));
+                failures.failed();
+                continue;
+            }
+        };
+        let without_2fa = client.list_members_without_2fa(org_name).await?;
+
+        seats.extend(members.into_iter().map(|member| {
+            MemberSeat {
+                org: org_name.clone(),
+                two_factor: without_2fa
+                    .as_ref()
+                    .map(|logins| !logins.contains(&member.login)),
+                login: member.login,
+                role: member.role,
+            }
+        }));
+    }
+    failures.finish()?;
+
+    sort_members(&mut seats);
+
+    display::output_records(global.json, &seats, |data| {
+        render_members_table(data);
+    })?;
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// By org, admins first, then alphabetically by login.
+fn sort_members(seats: &mut [MemberSeat]) {
+    seats.sort_by(|a, b| {
+        (&a.org, a.role != "admin", a.login.to_lowercase()).cmp(&(
+            &b.org,
+            b.role != "admin",
+            b.login.to_lowercase(),
+        ))
+    });
+}
+
+fn render_members_table(seats: &[MemberSeat]) {
+    if seats.is_empty() {
+        display::warn("No members found.");
+        return;
+    }
+
+    let admins = seats.iter().filter(|s| s.role == "admin").count();
+    display::section_header(&format!(
+        "Org Members ({} seats, {admins} admin)",
+        seats.len()
+    ));
+
+    let mut table = display::new_table(&["Org", "Login", "Role", "2FA"]);
+    for s in seats {
+        table.add_row(vec![
+            s.org.clone(),
+            s.login.clone(),
+            s.role.clone(),
+            s.two_factor
+                .map_or("?", |on| if on { "on" } else { "off" })
+                .to_string(),
+        ]);
+    }
+
+    display::print_table(&table);
+    display::count_footer(seats.len(), seats.len(), "member(s)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seat(org: &str, login: &str, role: &str) -> MemberSeat {
+        MemberSeat {
+            org: org.into(),
+            login: login.into(),
+            role: role.into(),
+            two_factor: None,
+        }
+    }
+
+    #[test]
+    fn admins_sort_first_within_each_org() {
+        let mut seats = vec![
+            seat("beta", "ann", "member"),
+            seat("acme", "zed", "member"),
+            seat("acme", "Bob", "member"),
+            seat("acme", "yan", "admin"),
+        ];
+        sort_members(&mut seats);
+        let logins: Vec<&str> = seats.iter().map(|s| s.login.as_str()).collect();
+        assert_eq!(logins, vec!["yan", "Bob", "zed", "ann"]);
+    }
+}
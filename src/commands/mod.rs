@@ -1,30 +1,1057 @@
+pub mod alerts;
+pub mod archive;
 pub mod auth;
+pub mod cache;
+pub mod contributors;
+pub mod export;
+pub mod fields;
 pub mod issues;
+pub mod members;
+pub mod open;
 pub mod orgs;
 pub mod overview;
+pub mod profile;
+pub mod protection;
+pub mod rate_limit;
+pub mod releases;
+pub mod repo;
 pub mod repos;
+pub mod snapshot;
 pub mod stale;
 pub mod stats;
+pub mod teams;
+pub mod whoami;
 
-use crate::config::Config;
-use crate::github::GithubClient;
+use crate::cache::{cache_dir, RepoCache};
+use crate::config::{Config, OutputFormat};
+use crate::display::{self, TableStyle};
+use crate::error::GitorgError;
+use crate::github::ProxySettings;
+use crate::github::{
+    GithubClient, OrgRepo, OverCap, DEFAULT_BASE_DELAY, DEFAULT_MAX_CONCURRENCY, DEFAULT_MAX_REPOS,
+    DEFAULT_MAX_RETRIES, DEFAULT_RATE_LIMIT_HEADROOM, DEFAULT_TIMEOUT,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Args, ValueEnum};
+use futures::stream::{self, StreamExt};
+use octocrab::models::{Repository, RepositoryId};
+use std::collections::{HashMap, HashSet};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
+/// Days without a push before a repo counts as stale, unless `--days` or
+/// `defaults.stale_days` says otherwise.
+pub const DEFAULT_STALE_DAYS: u64 = 90;
+
+/// Flags accepted by every command.
+#[derive(Args, Debug, Default)]
+pub struct GlobalArgs {
+    /// Output results as JSON
+    #[arg(long, global = true)]
+    pub json: bool,
+
+    /// Show verbose output (rate limits, debug info)
+    #[arg(long, global = true)]
+    pub verbose: bool,
+
+    /// GitHub API base URL (e.g. https://ghe.example.com/api/v3 for Enterprise Server)
+    #[arg(long, global = true)]
+    pub api_url: Option<String>,
+
+    /// Config file to use instead of ~/.config/gitorg/config.toml
+    #[arg(long, global = true, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Read the API token from this file (e.g. a mounted Docker/Kubernetes secret)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub token_file: Option<PathBuf>,
+
+    /// Config profile to use instead of the active one
+    #[arg(long, global = true)]
+    pub profile: Option<String>,
+
+    /// Minutes to reuse cached org repo listings (0 disables the cache)
+    #[arg(long, global = true, default_value = "10")]
+    pub cache_ttl: u64,
+
+    /// Ignore cached repo listings and fetch fresh ones
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+
+    /// Render tables as GitHub-flavored Markdown
+    #[arg(long, global = true, conflicts_with = "json")]
+    pub markdown: bool,
+
+    /// Disable colored output (also honors the NO_COLOR environment variable)
+    #[arg(long, global = true)]
+    pub no_color: bool,
+
+    /// Write results to this file instead of stdout; a .json or .md
+    /// extension implies --json or --markdown
+    #[arg(long, global = true, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// Stop paginated listings after this many pages of 100 (default: no cap)
+    #[arg(long, global = true, value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_pages: Option<u32>,
+
+    /// Print JSON Lines: one compact object per line (implies --json). `repos`
+    /// and `issues` print each record as it's found unless --sort or --limit
+    /// needs the whole set first
+    #[arg(long, global = true, conflicts_with = "markdown")]
+    pub ndjson: bool,
+
+    /// Leave this organization out, however the org list was chosen
+    /// (repeatable; case-insensitive)
+    #[arg(long = "exclude-org", global = true, value_name = "ORG")]
+    pub exclude_orgs: Vec<String>,
+
+    /// Print only the data: no section headers, count footers, or success
+    /// messages (warnings still go to stderr)
+    #[arg(long, global = true)]
+    pub quiet: bool,
+
+    /// With --json, print compact single-line JSON instead of pretty-printed
+    #[arg(long, global = true)]
+    pub json_compact: bool,
+
+    /// With --json, wrap single-object results (e.g. `stats`) in an array
+    #[arg(long, global = true)]
+    pub wrap_array: bool,
+
+    /// Seconds to wait on GitHub before giving up on a request (default: 30)
+    #[arg(long, global = true, value_name = "SECONDS", value_parser = clap::value_parser!(u64).range(1..))]
+    pub timeout: Option<u64>,
+
+    /// HTTP proxy for API requests, e.g. http://proxy.corp:3128 (default:
+    /// HTTPS_PROXY/HTTP_PROXY/ALL_PROXY; NO_PROXY hosts are reached directly)
+    #[arg(long, global = true, value_name = "URL")]
+    pub proxy: Option<String>,
+
+    /// Retry a request up to this many times after a secondary ("abuse")
+    /// rate limit
+    #[arg(long, global = true, value_name = "MAX", default_value_t = DEFAULT_MAX_RETRIES)]
+    pub retry_on_abuse: u32,
+
+    /// Milliseconds to wait before the first such retry when GitHub sends
+    /// no Retry-After; each later retry waits twice as long
+    #[arg(long, global = true, value_name = "MS", default_value_t = DEFAULT_BASE_DELAY.as_millis() as u64)]
+    pub retry_delay: u64,
+
+    /// When the API rate limit runs out, sleep until it resets and resume
+    /// instead of failing
+    #[arg(long, global = true)]
+    pub wait: bool,
+
+    /// Most API requests in flight at once; fewer run as the rate limit
+    /// runs low
+    #[arg(long, global = true, value_name = "N", value_parser = clap::value_parser!(u64).range(1..))]
+    pub max_concurrency: Option<u64>,
+
+    /// Core rate-limit requests to leave unspent: near it requests go one
+    /// at a time (with --wait, they pause until the reset)
+    #[arg(long, global = true, value_name = "N")]
+    pub rate_limit_headroom: Option<u32>,
+
+    /// Print each result of a list command as this template, e.g.
+    /// '{org}/{name} {stars}'; fields are the JSON keys (see `gitorg fields`)
+    #[arg(long, global = true, value_name = "TEMPLATE", conflicts_with_all = ["json", "markdown", "ndjson"])]
+    pub format: Option<String>,
+
+    /// Stop crawling org repo listings once a run has passed this many repos
+    /// (0 for no cap); asks first when running in a terminal
+    #[arg(long, global = true, value_name = "N", default_value_t = DEFAULT_MAX_REPOS)]
+    pub max_repos: usize,
+
+    /// Answer yes to confirmation prompts (archiving, passing --max-repos)
+    #[arg(long, global = true)]
+    pub yes: bool,
+
+    /// Print tables even when defaults.output_format picks another format
+    #[arg(long, global = true, conflicts_with_all = ["json", "markdown", "ndjson", "format"])]
+    pub table: bool,
+
+    /// Table borders: full, condensed, ascii, or borderless
+    #[arg(long, global = true, value_enum, default_value_t)]
+    pub table_style: TableStyle,
+}
+
+/// Inclusive star-count bounds for commands that audit repos.
+#[derive(Args, Debug, Default, Clone, Copy)]
+pub struct StarFilter {
+    /// Only include repos with at least this many stars
+    #[arg(long)]
+    pub min_stars: Option<u32>,
+
+    /// Only include repos with at most this many stars
+    #[arg(long)]
+    pub max_stars: Option<u32>,
+}
+
+impl StarFilter {
+    pub fn matches(&self, stars: u32) -> bool {
+        self.min_stars.map_or(true, |min| stars >= min)
+            && self.max_stars.map_or(true, |max| stars <= max)
+    }
+}
+
+/// Archived-repo selection for commands that would otherwise list them.
+#[derive(Args, Debug, Default, Clone, Copy)]
+pub struct ArchivedFilter {
+    /// Leave archived repos out entirely
+    #[arg(long, conflicts_with = "only_archived")]
+    pub exclude_archived: bool,
+
+    /// Only include archived repos
+    #[arg(long)]
+    pub only_archived: bool,
+}
+
+impl ArchivedFilter {
+    pub fn matches(&self, archived: bool) -> bool {
+        if self.exclude_archived {
+            !archived
+        } else if self.only_archived {
+            archived
+        } else {
+            true
+        }
+    }
+}
+
+/// Which timestamp decides whether a repo is still alive.
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityMetric {
+    /// Last code push (`pushed_at`)
+    #[default]
+    Push,
+    /// Last change of any kind (`updated_at`), e.g. settings or topics edits
+    Update,
+}
+
+impl ActivityMetric {
+    pub fn last_activity(self, repo: &OrgRepo) -> Option<DateTime<Utc>> {
+        match self {
+            ActivityMetric::Push => repo.pushed_at,
+            ActivityMetric::Update => repo.updated_at,
+        }
+    }
+
+    /// "push" or "update", for headers and messages.
+    pub fn noun(self) -> &'static str {
+        match self {
+            ActivityMetric::Push => "push",
+            ActivityMetric::Update => "update",
+        }
+    }
+}
+
+impl GlobalArgs {
+    /// Settles the output format: `--ndjson` implies `--json`, and without
+    /// an explicit format flag the `--output` file extension picks one,
+    /// then `default` (from `defaults.output_format`).
+    pub fn infer_output_format(&mut self, default: Option<OutputFormat>) {
+        if self.ndjson {
+            self.json = true;
+        }
+        if self.json || self.markdown || self.table || self.format.is_some() {
+            return;
+        }
+        let ext = self
+            .output
+            .as_deref()
+            .and_then(|p| p.extension())
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        match ext.as_deref() {
+            Some("json") => self.json = true,
+            Some("ndjson" | "jsonl") => {
+                self.json = true;
+                self.ndjson = true;
+            }
+            Some("md") => self.markdown = true,
+            _ => match default {
+                Some(OutputFormat::Json) => self.json = true,
+                Some(OutputFormat::Ndjson) => {
+                    self.json = true;
+                    self.ndjson = true;
+                }
+                Some(OutputFormat::Markdown) => self.markdown = true,
+                Some(OutputFormat::Table) | None => {}
+            },
+        }
+    }
+
+    /// The config file in effect: `--config`, else the XDG/home default.
+    pub fn config_path(&self) -> crate::error::Result<PathBuf> {
+        match &self.config {
+            Some(path) => Ok(path.clone()),
+            None => crate::config::config_path(),
+        }
+    }
+
+    /// Loads the config file with the `--profile` selection applied.
+    pub fn load_config(&self) -> crate::error::Result<Config> {
+        let mut config = crate::config::load_config(&self.config_path()?)?;
+        config.select_profile(self.profile.as_deref());
+        Ok(config)
+    }
+
+    /// The API base URL: `--api-url` wins over `auth.base_url` in config.
+    pub fn api_url<'a>(&'a self, config: &'a Config) -> Option<&'a str> {
+        self.api_url
+            .as_deref()
+            .or(config.auth().base_url.as_deref())
+    }
+
+    /// The request timeout: `--timeout`, then `defaults.timeout` in config.
+    pub fn timeout(&self, config: &Config) -> Duration {
+        self.timeout
+            .or(config.defaults().timeout)
+            .map_or(DEFAULT_TIMEOUT, Duration::from_secs)
+    }
+
+    /// Proxies for API requests: `--proxy`, then `defaults.proxy` in config,
+    /// then the proxy environment variables.
+    pub fn proxy(&self, config: &Config) -> crate::error::Result<ProxySettings> {
+        ProxySettings::from_env(self.proxy.as_deref().or(config.defaults().proxy.as_deref()))
+    }
+
+    /// Requests in flight at once: `--max-concurrency`, then
+    /// `defaults.max_concurrency` in config.
+    pub fn max_concurrency(&self, config: &Config) -> usize {
+        self.max_concurrency
+            .map(|n| n as usize)
+            .or(config.defaults().max_concurrency)
+            .unwrap_or(DEFAULT_MAX_CONCURRENCY)
+            .max(1)
+    }
+
+    /// Rate-limit headroom: `--rate-limit-headroom`, then
+    /// `defaults.rate_limit_headroom` in config.
+    pub fn rate_limit_headroom(&self, config: &Config) -> u32 {
+        self.rate_limit_headroom
+            .or(config.defaults().rate_limit_headroom)
+            .unwrap_or(DEFAULT_RATE_LIMIT_HEADROOM)
+    }
+}
+
+/// The web root of the GitHub instance behind `api_url`: github.com, or
+/// the Enterprise Server host serving `/api/v3`.
+pub fn web_url(api_url: Option<&str>) -> String {
+    match api_url.map(|url| url.trim_end_matches('/')) {
+        None | Some("https://api.github.com") => "https://github.com".to_string(),
+        Some(url) => url.trim_end_matches("/api/v3").to_string(),
+    }
+}
+
+/// The stale threshold: `--days`, then `defaults.stale_days` in config.
+pub fn stale_days(flag: Option<u64>, config: &Config) -> u64 {
+    flag.or(config.defaults().stale_days)
+        .unwrap_or(DEFAULT_STALE_DAYS)
+}
+
+pub fn github_client(config: &Config, global: &GlobalArgs) -> crate::error::Result<GithubClient> {
+    let env_token = std::env::var("GITHUB_TOKEN").ok();
+    let token = resolve_token(env_token, global.token_file.as_deref(), config)?;
+    let mut client = GithubClient::new(
+        &token,
+        global.api_url(config),
+        global.verbose,
+        global.timeout(config),
+        global.proxy(config)?,
+    )?
+    .with_max_pages(global.max_pages)
+    .with_wait_on_rate_limit(global.wait)
+    .with_max_retries(global.retry_on_abuse)
+    .with_retry_delay(Duration::from_millis(global.retry_delay))
+    .with_concurrency(
+        global.max_concurrency(config),
+        global.rate_limit_headroom(config),
+    )
+    .with_max_repos(
+        Some(global.max_repos).filter(|&max| max > 0),
+        if global.yes {
+            OverCap::Continue
+        } else if std::io::stdin().is_terminal() {
+            OverCap::Ask
+        } else {
+            OverCap::Stop
+        },
+    );
+    if global.cache_ttl > 0 {
+        client = client.with_cache(RepoCache::new(
+            cache_dir()?,
+            global.cache_ttl,
+            !global.no_cache,
+        ));
+    }
+    Ok(client)
+}
+
+/// The API token: a non-empty `GITHUB_TOKEN`, then `--token-file`, then
+/// the config (file or keyring).
+fn resolve_token(
+    env_token: Option<String>,
+    token_file: Option<&Path>,
+    config: &Config,
+) -> crate::error::Result<String> {
+    if let Some(token) = env_token.filter(|t| !t.trim().is_empty()) {
+        return Ok(token.trim().to_string());
+    }
+    match token_file {
+        Some(path) => read_token_file(path),
+        None => config.token(),
+    }
+}
+
+fn read_token_file(path: &Path) -> crate::error::Result<String> {
+    let contents = std::fs::read_to_string(path).map_err(|e| {
+        GitorgError::Config(format!("Cannot read token file {}: {e}", path.display()))
+    })?;
+    let token = contents.trim();
+    if token.is_empty() {
+        return Err(GitorgError::Config(format!(
+            "Token file {} is empty",
+            path.display()
+        )));
+    }
+    Ok(token.to_string())
+}
+
+/// Open PR counts by repo name, for separating PRs from the
+/// `open_issues_count` GitHub reports (which includes them). Costs a request
+/// per repo with anything open; repos that fail are warned about and left out.
+pub async fn open_pr_counts<'a>(
+    client: &GithubClient,
+    owner: &str,
+    repos: impl IntoIterator<Item = &'a OrgRepo>,
+) -> HashMap<String, u32> {
+    let candidates = repos
+        .into_iter()
+        .filter(|repo| repo.open_issues_count.unwrap_or(0) > 0);
+
+    let results: Vec<_> = stream::iter(candidates)
+        .map(|repo| async move {
+            let pulls = client.list_repo_pulls(owner, &repo.name).await;
+            (repo.name.clone(), pulls)
+        })
+        .buffer_unordered(client.max_concurrency())
+        .collect()
+        .await;
+
+    let mut counts = HashMap::new();
+    for (repo, result) in results {
+        match result {
+            Ok(pulls) => {
+                counts.insert(repo, pulls.len() as u32);
+            }
+            Err(e) => display::warn(&format!(
+                "Failed to count pull requests for {owner}/{repo}: {e}"
+            )),
+        }
+    }
+    counts
+}
+
+/// Whole days since the repo's last push, or `None` if it has never been
+/// pushed to. A push timestamp ahead of `now` (clock skew) counts as 0.
+pub fn days_since_push(repo: &OrgRepo, now: DateTime<Utc>) -> Option<i64> {
+    days_since_activity(repo, ActivityMetric::Push, now)
+}
+
+/// Like [`days_since_push`], measured against whichever timestamp `metric`
+/// selects.
+pub fn days_since_activity(
+    repo: &OrgRepo,
+    metric: ActivityMetric,
+    now: DateTime<Utc>,
+) -> Option<i64> {
+    metric
+        .last_activity(repo)
+        .map(|at| (now - at).num_days().max(0))
+}
+
+/// Parses a `YYYY-MM-DD` value given to `--{flag}`.
+pub fn parse_date(flag: &str, value: &str) -> crate::error::Result<NaiveDate> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        GitorgError::Config(format!(
+            "Invalid --{flag} date '{value}'. Expected YYYY-MM-DD."
+        ))
+    })
+}
+
+/// Truncates `items` to `limit` (if any), returning the count before truncation.
+pub fn apply_limit<T>(items: &mut Vec<T>, limit: Option<usize>) -> usize {
+    let total = items.len();
+    if let Some(limit) = limit {
+        items.truncate(limit);
+    }
+    total
+}
+
+/// Handles a failed org repo listing. The sole org named with `--org` being
+/// unknown or out of the token's reach is fatal; otherwise the failure is
+/// reported and the remaining orgs continue.
+pub fn org_fetch_failed(
+    org_flag: &Option<String>,
+    org: &str,
+    err: GitorgError,
+) -> crate::error::Result<()> {
+    match err {
+        GitorgError::OrgNotFound(_) | GitorgError::OrgAccessDenied(_)
+            if single_org_flag(org_flag) =>
+        {
+            Err(err)
+        }
+        err => {
+            display::warn(&format!("Failed to fetch repos for {org}: {err}"));
+            Ok(())
+        }
+    }
+}
+
+/// Counts orgs that couldn't be loaded during a multi-org run, so an empty
+/// result can be told apart from an unreachable API, and notes orgs that
+/// listed no repos at all.
+#[derive(Debug)]
+pub struct OrgFailures {
+    orgs: usize,
+    failed: usize,
+    /// Some org loaded only in part
+    partial: bool,
+    empty: Vec<String>,
+    report_empty: bool,
+}
+
+impl OrgFailures {
+    pub fn new(orgs: usize) -> Self {
+        Self {
+            orgs,
+            failed: 0,
+            partial: false,
+            empty: Vec::new(),
+            report_empty: false,
+        }
+    }
+
+    /// Makes `finish` name the orgs that listed zero repos
+    /// (`--include-empty-orgs` or `--verbose`).
+    pub fn with_empty_report(mut self, report_empty: bool) -> Self {
+        self.report_empty = report_empty;
+        self
+    }
+
+    /// Records a successful repo listing of `repos` repos.
+    pub fn listed(&mut self, org: &str, repos: usize) {
+        if repos == 0 {
+            self.empty.push(org.to_string());
+        }
+    }
+
+    /// Records a failed repo listing; see `org_fetch_failed`.
+    pub fn repos_failed(
+        &mut self,
+        org_flag: &Option<String>,
+        org: &str,
+        err: GitorgError,
+    ) -> crate::error::Result<()> {
+        self.failed += 1;
+        org_fetch_failed(org_flag, org, err)
+    }
+
+    /// Records a failure already reported by the caller.
+    pub fn failed(&mut self) {
+        self.failed += 1;
+    }
+
+    /// Records part of an org's data (say, one repo's issues) missing from
+    /// an otherwise loaded org.
+    pub fn partly_failed(&mut self) {
+        self.partial = true;
+    }
+
+    /// One line per org that listed no repos, when asked for.
+    fn empty_report(&self) -> Vec<String> {
+        if !self.report_empty {
+            return Vec::new();
+        }
+        self.empty
+            .iter()
+            .map(|org| format!("org {org}: 0 repos"))
+            .collect()
+    }
+
+    /// Warns how many orgs failed, and errors if none could be loaded.
+    /// Returns whether everything loaded, so `--since-last-run` only moves
+    /// on past a complete run.
+    pub fn finish(&self) -> crate::error::Result<bool> {
+        for line in self.empty_report() {
+            eprintln!("{line}");
+        }
+        if self.failed == 0 {
+            return Ok(!self.partial);
+        }
+        if self.failed == self.orgs {
+            return Err(GitorgError::GitHub(format!(
+                "None of the {} org(s) could be loaded",
+                self.orgs
+            )));
+        }
+        display::warn(&format!(
+            "{} of {} orgs failed to load",
+            self.failed, self.orgs
+        ));
+        Ok(false)
+    }
+}
+
+/// The orgs to work on: `--org`, then `defaults.orgs`, then every org the
+/// token belongs to, minus any `--exclude-org`.
 pub async fn resolve_orgs(
     org_flag: &Option<String>,
+    exclude: &[String],
     config: &Config,
     client: &GithubClient,
 ) -> crate::error::Result<Vec<String>> {
-    if let Some(org) = org_flag {
-        return Ok(vec![org.clone()]);
+    let orgs = match (org_flag, &config.defaults().orgs) {
+        (Some(org), _) => parse_org_list(org)?,
+        (None, Some(orgs)) if !orgs.is_empty() => dedup_orgs(orgs.clone()),
+        _ => {
+            let orgs = client.list_user_orgs().await?;
+            dedup_orgs(orgs.into_iter().map(|o| o.login).collect())
+        }
+    };
+    exclude_orgs(orgs, exclude)
+}
+
+/// Drops `exclude` (case-insensitively) from `orgs`; excluding every org is
+/// an error rather than a silently empty report.
+fn exclude_orgs(orgs: Vec<String>, exclude: &[String]) -> crate::error::Result<Vec<String>> {
+    if exclude.is_empty() {
+        return Ok(orgs);
+    }
+    let remaining: Vec<String> = orgs
+        .into_iter()
+        .filter(|org| !exclude.iter().any(|e| e.eq_ignore_ascii_case(org)))
+        .collect();
+    if remaining.is_empty() {
+        return Err(GitorgError::Config(
+            "Every organization was removed by --exclude-org".to_string(),
+        ));
     }
+    Ok(remaining)
+}
+
+/// Whether `--org` names exactly one org, so a failure to load it leaves
+/// nothing to report on.
+pub fn single_org_flag(org_flag: &Option<String>) -> bool {
+    org_flag
+        .as_deref()
+        .and_then(|flag| parse_org_list(flag).ok())
+        .is_some_and(|orgs| orgs.len() == 1)
+}
+
+/// Splits `--org a,b,c` into names, rejecting empty entries.
+fn parse_org_list(flag: &str) -> crate::error::Result<Vec<String>> {
+    let orgs = flag
+        .split(',')
+        .map(|org| {
+            let org = org.trim();
+            if org.is_empty() {
+                Err(GitorgError::Config(format!(
+                    "Invalid --org '{flag}': organization names can't be empty"
+                )))
+            } else {
+                Ok(org.to_string())
+            }
+        })
+        .collect::<crate::error::Result<Vec<_>>>()?;
+    Ok(dedup_orgs(orgs))
+}
+
+/// Drops repeated org names (GitHub logins are case-insensitive), keeping
+/// the first spelling and the original order.
+fn dedup_orgs(orgs: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    orgs.into_iter()
+        .filter(|org| seen.insert(org.to_lowercase()))
+        .collect()
+}
+
+/// Repos already counted towards an aggregate, by id, so one reachable
+/// through more than one org listing is only counted once.
+#[derive(Debug, Default)]
+pub struct SeenRepos(HashSet<RepositoryId>);
+
+impl SeenRepos {
+    /// Records `repo`, returning `false` if it was already seen.
+    pub fn insert(&mut self, repo: &Repository) -> bool {
+        self.0.insert(repo.id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pushed_repo(pushed_at: Option<&str>) -> OrgRepo {
+        serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "repo",
+            "url": "https://api.github.com/repos/org/repo",
+            "pushed_at": pushed_at,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn days_since_push_handles_null_and_future_dates() {
+        let now: DateTime<Utc> = "2024-06-10T12:00:00Z".parse().unwrap();
+        assert_eq!(days_since_push(&pushed_repo(None), now), None);
+        assert_eq!(
+            days_since_push(&pushed_repo(Some("2024-06-01T12:00:00Z")), now),
+            Some(9)
+        );
+        // Clock skew: a push "tomorrow" is treated as today
+        assert_eq!(
+            days_since_push(&pushed_repo(Some("2024-06-11T12:00:00Z")), now),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn activity_metric_picks_the_timestamp() {
+        let now: DateTime<Utc> = "2024-06-10T12:00:00Z".parse().unwrap();
+        let repo: OrgRepo = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "repo",
+            "url": "https://api.github.com/repos/org/repo",
+            "pushed_at": "2024-01-10T12:00:00Z",
+            "updated_at": "2024-06-05T12:00:00Z",
+        }))
+        .unwrap();
+        assert_eq!(
+            days_since_activity(&repo, ActivityMetric::Push, now),
+            Some(152)
+        );
+        assert_eq!(
+            days_since_activity(&repo, ActivityMetric::Update, now),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn token_precedence_is_env_then_file_then_config() {
+        let dir = std::env::temp_dir().join(format!("gitorg_token_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = dir.join("github_token");
+        std::fs::write(&file, "ghp_from_file\n").unwrap();
+
+        let mut config = Config::default();
+        config.auth.token = Some("ghp_from_config".to_string());
+
+        let env = Some("ghp_from_env".to_string());
+        assert_eq!(
+            resolve_token(env, Some(&file), &config).unwrap(),
+            "ghp_from_env"
+        );
+        let blank_env = Some(" ".to_string());
+        assert_eq!(
+            resolve_token(blank_env, Some(&file), &config).unwrap(),
+            "ghp_from_file"
+        );
+        assert_eq!(
+            resolve_token(None, None, &config).unwrap(),
+            "ghp_from_config"
+        );
 
-    if let Some(ref orgs) = config.defaults.orgs {
-        if !orgs.is_empty() {
-            return Ok(orgs.clone());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn token_file_must_exist_and_be_non_empty() {
+        let dir = std::env::temp_dir().join(format!("gitorg_token_empty_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let empty = dir.join("empty");
+        std::fs::write(&empty, "  \n").unwrap();
+
+        let config = Config::default();
+        let err = resolve_token(None, Some(&empty), &config).unwrap_err();
+        assert!(matches!(err, GitorgError::Config(ref m) if m.contains("is empty")));
+        let err = resolve_token(None, Some(&dir.join("missing")), &config).unwrap_err();
+        assert!(matches!(err, GitorgError::Config(ref m) if m.contains("Cannot read token file")));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn dedup_orgs_ignores_case_and_keeps_order() {
+        let orgs = ["acme", "Other", "ACME", "other", "third"]
+            .map(String::from)
+            .to_vec();
+        assert_eq!(dedup_orgs(orgs), vec!["acme", "Other", "third"]);
+    }
+
+    #[test]
+    fn excluded_orgs_are_dropped_ignoring_case() {
+        let orgs = ["acme", "Noise", "other"].map(String::from).to_vec();
+        assert_eq!(
+            exclude_orgs(orgs.clone(), &["noise".to_string()]).unwrap(),
+            vec!["acme", "other"]
+        );
+        assert_eq!(exclude_orgs(orgs.clone(), &[]).unwrap(), orgs);
+
+        let err = exclude_orgs(vec!["acme".to_string()], &["ACME".to_string()]).unwrap_err();
+        assert!(matches!(err, GitorgError::Config(_)));
+    }
+
+    #[test]
+    fn org_flag_accepts_a_comma_list() {
+        assert_eq!(parse_org_list("acme").unwrap(), vec!["acme"]);
+        assert_eq!(
+            parse_org_list("acme, other,ACME").unwrap(),
+            vec!["acme", "other"]
+        );
+        for bad in ["", "acme,", "acme, ,other"] {
+            let err = parse_org_list(bad).unwrap_err();
+            assert!(matches!(err, GitorgError::Config(_)), "{bad}: {err}");
         }
     }
 
-    let orgs = client.list_user_orgs().await?;
-    let names: Vec<String> = orgs.into_iter().map(|o| o.login).collect();
-    Ok(names)
+    #[test]
+    fn output_extension_picks_format() {
+        let inferred = |path: &str, json: bool| {
+            let mut global = GlobalArgs {
+                output: Some(PathBuf::from(path)),
+                json,
+                ..GlobalArgs::default()
+            };
+            global.infer_output_format(None);
+            (global.json, global.markdown)
+        };
+        assert_eq!(inferred("report.json", false), (true, false));
+        assert_eq!(inferred("out/REPORT.MD", false), (false, true));
+        assert_eq!(inferred("report.txt", false), (false, false));
+        assert_eq!(inferred("events.jsonl", false), (true, false));
+        // An explicit flag wins over the extension
+        assert_eq!(inferred("report.md", true), (true, false));
+    }
+
+    #[test]
+    fn config_output_format_applies_without_a_flag() {
+        let inferred = |global: GlobalArgs, default: OutputFormat| {
+            let mut global = global;
+            global.infer_output_format(Some(default));
+            (global.json, global.ndjson, global.markdown)
+        };
+        let plain = GlobalArgs::default;
+        assert_eq!(inferred(plain(), OutputFormat::Json), (true, false, false));
+        assert_eq!(inferred(plain(), OutputFormat::Ndjson), (true, true, false));
+        assert_eq!(
+            inferred(plain(), OutputFormat::Markdown),
+            (false, false, true)
+        );
+        assert_eq!(
+            inferred(plain(), OutputFormat::Table),
+            (false, false, false)
+        );
+
+        // Explicit flags and the --output extension win over the config
+        let table = GlobalArgs {
+            table: true,
+            ..GlobalArgs::default()
+        };
+        assert_eq!(inferred(table, OutputFormat::Json), (false, false, false));
+        let markdown = GlobalArgs {
+            markdown: true,
+            ..GlobalArgs::default()
+        };
+        assert_eq!(inferred(markdown, OutputFormat::Json), (false, false, true));
+        let output = GlobalArgs {
+            output: Some(PathBuf::from("report.md")),
+            ..GlobalArgs::default()
+        };
+        assert_eq!(inferred(output, OutputFormat::Json), (false, false, true));
+    }
+
+    #[test]
+    fn api_url_flag_overrides_config() {
+        let mut config = Config::default();
+        config.auth.base_url = Some("https://ghe.example.com/api/v3".to_string());
+
+        let global = GlobalArgs::default();
+        assert_eq!(
+            global.api_url(&config),
+            Some("https://ghe.example.com/api/v3")
+        );
+
+        let global = GlobalArgs {
+            api_url: Some("https://other.example.com/api/v3".to_string()),
+            ..GlobalArgs::default()
+        };
+        assert_eq!(
+            global.api_url(&config),
+            Some("https://other.example.com/api/v3")
+        );
+
+        assert_eq!(GlobalArgs::default().api_url(&Config::default()), None);
+    }
+
+    #[test]
+    fn stale_days_falls_back_to_config_then_default() {
+        let mut config = Config::default();
+        assert_eq!(stale_days(None, &config), DEFAULT_STALE_DAYS);
+
+        config.defaults.stale_days = Some(180);
+        assert_eq!(stale_days(None, &config), 180);
+        assert_eq!(stale_days(Some(30), &config), 30);
+    }
+
+    #[test]
+    fn web_url_strips_the_api_path() {
+        assert_eq!(web_url(None), "https://github.com");
+        assert_eq!(
+            web_url(Some("https://api.github.com/")),
+            "https://github.com"
+        );
+        assert_eq!(
+            web_url(Some("https://ghe.example.com/api/v3/")),
+            "https://ghe.example.com"
+        );
+    }
+
+    #[test]
+    fn timeout_flag_overrides_config_default() {
+        let mut config = Config::default();
+        assert_eq!(GlobalArgs::default().timeout(&config), DEFAULT_TIMEOUT);
+
+        config.defaults.timeout = Some(5);
+        assert_eq!(
+            GlobalArgs::default().timeout(&config),
+            Duration::from_secs(5)
+        );
+
+        let global = GlobalArgs {
+            timeout: Some(60),
+            ..GlobalArgs::default()
+        };
+        assert_eq!(global.timeout(&config), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn star_filter_bounds_are_inclusive() {
+        let filter = StarFilter {
+            min_stars: Some(10),
+            max_stars: Some(100),
+        };
+        assert!(!filter.matches(9));
+        assert!(filter.matches(10));
+        assert!(filter.matches(100));
+        assert!(!filter.matches(101));
+        assert!(StarFilter::default().matches(0));
+    }
+
+    #[test]
+    fn archived_filter_modes() {
+        let all = ArchivedFilter::default();
+        assert!(all.matches(true) && all.matches(false));
+
+        let exclude = ArchivedFilter {
+            exclude_archived: true,
+            ..ArchivedFilter::default()
+        };
+        assert!(!exclude.matches(true) && exclude.matches(false));
+
+        let only = ArchivedFilter {
+            only_archived: true,
+            ..ArchivedFilter::default()
+        };
+        assert!(only.matches(true) && !only.matches(false));
+    }
+
+    #[test]
+    fn missing_org_is_fatal_only_with_org_flag() {
+        let single = Some("typo-org".to_string());
+        let err = org_fetch_failed(
+            &single,
+            "typo-org",
+            GitorgError::OrgNotFound("typo-org".into()),
+        );
+        assert!(matches!(err, Err(GitorgError::OrgNotFound(org)) if org == "typo-org"));
+
+        assert!(org_fetch_failed(
+            &None,
+            "typo-org",
+            GitorgError::OrgNotFound("typo-org".into())
+        )
+        .is_ok());
+        assert!(org_fetch_failed(&single, "typo-org", GitorgError::GitHub("boom".into())).is_ok());
+        assert!(org_fetch_failed(
+            &single,
+            "typo-org",
+            GitorgError::OrgAccessDenied("typo-org".into())
+        )
+        .is_err());
+
+        let several = Some("acme,typo-org".to_string());
+        assert!(org_fetch_failed(
+            &several,
+            "typo-org",
+            GitorgError::OrgNotFound("typo-org".into())
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn all_orgs_failing_is_an_error() {
+        let mut failures = OrgFailures::new(2);
+        assert!(failures.finish().unwrap());
+
+        failures.failed();
+        assert!(!failures.finish().unwrap());
+
+        failures
+            .repos_failed(&None, "other", GitorgError::GitHub("boom".into()))
+            .unwrap();
+        let err = failures.finish().unwrap_err();
+        assert!(err.to_string().contains("None of the 2 org(s)"), "{err}");
+    }
+
+    #[test]
+    fn partly_loaded_org_is_not_a_complete_run() {
+        let mut failures = OrgFailures::new(1);
+        failures.partly_failed();
+        assert!(!failures.finish().unwrap());
+    }
+
+    #[test]
+    fn empty_orgs_are_reported_only_when_asked() {
+        let mut failures = OrgFailures::new(3);
+        failures.listed("acme", 12);
+        failures.listed("ghost", 0);
+        failures.failed();
+        assert!(failures.empty_report().is_empty());
+
+        let failures = failures.with_empty_report(true);
+        assert_eq!(failures.empty_report(), vec!["org ghost: 0 repos"]);
+    }
+
+    #[test]
+    fn apply_limit_truncates_and_reports_total() {
+        let mut items = vec![1, 2, 3, 4, 5];
+        assert_eq!(apply_limit(&mut items, Some(2)), 5);
+        assert_eq!(items, vec![1, 2]);
+    }
+
+    #[test]
+    fn apply_limit_none_keeps_everything() {
+        let mut items = vec![1, 2, 3];
+        assert_eq!(apply_limit(&mut items, None), 3);
+        assert_eq!(items.len(), 3);
+
+        assert_eq!(apply_limit(&mut items, Some(10)), 3);
+        assert_eq!(items.len(), 3);
+    }
 }
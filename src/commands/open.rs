@@ -0,0 +1,73 @@
+use crate::commands::repo::parse_slug;
+use crate::commands::{web_url, GlobalArgs};
+use crate::error::{GitorgError, Result};
+
+/// Opens the GitHub page of `target`: `<org>`, `<org>/<name>`, or
+/// `<org>/<name>#<number>` for an issue or pull request.
+pub fn run(target: &str, global: &GlobalArgs) -> Result<()> {
+    let path = target_path(target)?;
+    let config = global.load_config()?;
+    open_in_browser(&format!("{}/{path}", web_url(global.api_url(&config))));
+    Ok(())
+}
+
+/// The page path of a target, relative to the web root.
+fn target_path(target: &str) -> Result<String> {
+    let Some((repo, number)) = target.split_once('#') else {
+        if !target.is_empty() && !target.contains('/') {
+            return Ok(target.to_string());
+        }
+        let (owner, name) = parse_slug(target)?;
+        return Ok(format!("{owner}/{name}"));
+    };
+    let (owner, name) = parse_slug(repo)?;
+    let number = number
+        .parse::<u64>()
+        .ok()
+        .filter(|&n| n > 0)
+        .ok_or_else(|| {
+            GitorgError::Config(format!(
+                "Expected an issue number after '#', got '{target}'"
+            ))
+        })?;
+    // GitHub redirects to the pull request when the number is one
+    Ok(format!("{owner}/{name}/issues/{number}"))
+}
+
+/// Opens `url` in the default browser, or prints it when that fails.
+pub fn open_in_browser(url: &str) {
+    eprintln!("Opening {url}");
+    if open::that(url).is_err() {
+        eprintln!("Could not open browser. Visit: {url}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn targets_map_to_org_repo_and_issue_pages() {
+        assert_eq!(target_path("acme").unwrap(), "acme");
+        assert_eq!(target_path("acme/api").unwrap(), "acme/api");
+        assert_eq!(target_path("acme/api#42").unwrap(), "acme/api/issues/42");
+    }
+
+    #[test]
+    fn malformed_targets_are_rejected() {
+        for target in [
+            "",
+            "acme/",
+            "acme/api/x",
+            "acme#1",
+            "acme/api#",
+            "acme/api#0",
+            "acme/api#x",
+        ] {
+            assert!(
+                matches!(target_path(target), Err(GitorgError::Config(_))),
+                "{target}"
+            );
+        }
+    }
+}
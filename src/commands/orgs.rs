@@ -1,7 +1,8 @@
-use crate::config::load_config;
+use crate::commands::{github_client, GlobalArgs};
 use crate::display;
 use crate::error::Result;
-use crate::github::GithubClient;
+use crate::github::{GithubClient, OrgDetail};
+use futures::stream::{self, StreamExt};
 use serde::Serialize;
 
 #[derive(Debug, Serialize)]
@@ -9,34 +10,99 @@ pub struct OrgSummary {
     pub name: String,
     pub description: String,
     pub url: String,
+    /// The profile's display name; this and the fields below need `--detailed`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_repos: Option<u32>,
+    /// `None` when the token may not list the org's members
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avatar_url: Option<String>,
 }
 
-pub async fn run(json: bool, verbose: bool) -> Result<()> {
-    let config = load_config()?;
-    let token = config.token()?;
-    let client = GithubClient::new(token, verbose)?;
+impl OrgSummary {
+    fn add_detail(&mut self, detail: OrgDetail, members: Option<u32>) {
+        self.display_name = detail.name.filter(|n| !n.trim().is_empty());
+        self.public_repos = detail.public_repos;
+        self.members = members;
+        self.website = detail.blog.filter(|b| !b.trim().is_empty());
+        self.avatar_url = detail.avatar_url;
+    }
+}
+
+pub async fn run(detailed: bool, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let summaries = collect(&client, detailed).await?;
+
+    display::output_records(global.json, &summaries, |data| {
+        render_orgs_table(data, detailed);
+    })?;
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
 
+/// The orgs the token's user belongs to; `detailed` adds their profiles.
+pub async fn collect(client: &GithubClient, detailed: bool) -> Result<Vec<OrgSummary>> {
     let orgs = client.list_user_orgs().await?;
 
-    let summaries: Vec<OrgSummary> = orgs
+    let mut summaries: Vec<OrgSummary> = orgs
         .into_iter()
         .map(|o| OrgSummary {
             name: o.login.clone(),
             description: o.description.unwrap_or_default(),
             url: format!("https://github.com/{}", o.login),
+            display_name: None,
+            public_repos: None,
+            members: None,
+            website: None,
+            avatar_url: None,
         })
         .collect();
 
-    display::output(json, &summaries, |data| {
-        render_orgs_table(data);
-    });
+    if detailed {
+        add_details(client, &mut summaries).await;
+    }
+    Ok(summaries)
+}
 
-    client.check_rate_limit_if_verbose().await;
+/// Fetches each org's profile and member count (two requests per org).
+async fn add_details(client: &GithubClient, summaries: &mut [OrgSummary]) {
+    let details: Vec<_> = stream::iter(summaries.iter().map(|s| s.name.clone()))
+        .map(|org| async move {
+            let detail = client.get_org(&org).await;
+            let members = client.count_org_members(&org).await;
+            (detail, members)
+        })
+        .buffered(client.max_concurrency())
+        .collect()
+        .await;
 
-    Ok(())
+    for (summary, (detail, members)) in summaries.iter_mut().zip(details) {
+        let members = members.unwrap_or_else(|e| {
+            display::warn(&format!("Failed to count members of {}: {e}", summary.name));
+            None
+        });
+        match detail {
+            Ok(detail) => summary.add_detail(detail, members),
+            Err(e) => display::warn(&format!(
+                "Failed to fetch details for {}: {e}",
+                summary.name
+            )),
+        }
+    }
 }
 
-fn render_orgs_table(orgs: &[OrgSummary]) {
+pub fn render_orgs_table(orgs: &[OrgSummary], detailed: bool) {
     if orgs.is_empty() {
         display::warn("No organizations found.");
         return;
@@ -44,12 +110,64 @@ fn render_orgs_table(orgs: &[OrgSummary]) {
 
     display::section_header("Organizations");
 
-    let mut table = display::new_table(&["Name", "Description", "URL"]);
+    let mut headers = vec!["Name", "Description"];
+    if detailed {
+        headers.extend(["Repos", "Members", "Website"]);
+    }
+    headers.push("URL");
+    let mut table = display::new_table(&headers);
 
+    let or_dash = |value: Option<u32>| value.map_or("-".to_string(), |v| v.to_string());
     for org in orgs {
-        table.add_row(vec![&org.name, &org.description, &org.url]);
+        let mut row = vec![org.name.clone(), org.description.clone()];
+        if detailed {
+            row.extend([
+                or_dash(org.public_repos),
+                or_dash(org.members),
+                org.website.clone().unwrap_or_else(|| "-".to_string()),
+            ]);
+        }
+        row.push(org.url.clone());
+        table.add_row(row);
     }
 
-    println!("{table}");
-    println!("\n{} organization(s) found.", orgs.len());
+    display::print_table(&table);
+    display::count_footer(orgs.len(), orgs.len(), "organization(s)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn details_drop_blank_profile_fields() {
+        let mut summary = OrgSummary {
+            name: "acme".into(),
+            description: String::new(),
+            url: "https://github.com/acme".into(),
+            display_name: None,
+            public_repos: None,
+            members: None,
+            website: None,
+            avatar_url: None,
+        };
+        let detail: OrgDetail = serde_json::from_value(serde_json::json!({
+            "login": "acme",
+            "name": "Acme Corp",
+            "blog": "",
+            "avatar_url": "https://avatars.githubusercontent.com/u/1",
+            "public_repos": 12,
+        }))
+        .unwrap();
+        summary.add_detail(detail, Some(40));
+
+        assert_eq!(summary.display_name.as_deref(), Some("Acme Corp"));
+        assert_eq!(summary.website, None);
+        assert_eq!(summary.public_repos, Some(12));
+        assert_eq!(summary.members, Some(40));
+
+        let json = serde_json::to_value(&summary).unwrap();
+        assert_eq!(json["members"], 40);
+        assert!(json.get("website").is_none());
+    }
 }
@@ -1,23 +1,72 @@
-use crate::commands::resolve_orgs;
-use crate::config::load_config;
+use crate::commands::{
+    days_since_push, github_client, resolve_orgs, stale_days, GlobalArgs, OrgFailures, SeenRepos,
+};
 use crate::display;
-use crate::error::Result;
-use crate::github::GithubClient;
+use crate::error::{GitorgError, Result};
+use crate::github::{GithubClient, OrgRepo, RepoVisibility};
 use chrono::Utc;
-use owo_colors::OwoColorize;
+use clap::Args;
+use futures::stream::{self, StreamExt};
 use serde::Serialize;
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::io::Write;
+use std::time::Duration;
+
+#[derive(Args, Debug)]
+pub struct OverviewArgs {
+    /// Filter to specific organizations (comma-separated)
+    #[arg(long)]
+    pub org: Option<String>,
+    /// Days threshold for stale repos in overview [default: 90, or defaults.stale_days]
+    #[arg(long)]
+    pub days: Option<u64>,
+    /// Which repos to include: all, public, or private
+    #[arg(long, value_enum, default_value_t)]
+    pub visibility: RepoVisibility,
+    /// Keep refreshing the dashboard until Ctrl-C
+    #[arg(long)]
+    pub watch: bool,
+    /// Seconds between refreshes with --watch
+    #[arg(long, default_value = "60", value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+    /// Count open PRs separately from issues (also lists archived repos' issues)
+    #[arg(long)]
+    pub accurate_issue_counts: bool,
+    /// Add a weekly commit sparkline per org (one extra request per repo)
+    #[arg(long)]
+    pub commit_activity: bool,
+    /// Weeks of history in the commit sparkline
+    #[arg(long, default_value = "12", value_parser = clap::value_parser!(u32).range(1..=52))]
+    pub weeks: u32,
+    /// Name orgs that have no repos at all, to tell them from failed ones
+    /// (also shown with --verbose)
+    #[arg(long)]
+    pub include_empty_orgs: bool,
+}
 
 #[derive(Debug, Serialize)]
 pub struct OverviewData {
     pub total_repos: usize,
     pub total_stars: u32,
     pub total_forks: u32,
+    /// Open issues, excluding PRs only when `total_open_prs` is present.
     pub total_open_issues: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_open_prs: Option<u32>,
     pub top_languages: Vec<LangEntry>,
     pub recently_active: Vec<RepoEntry>,
     pub stale_repos: Vec<RepoEntry>,
     pub recent_issues: Vec<IssueEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit_activity: Option<Vec<OrgActivity>>,
+}
+
+/// Commits per week across an org's repos, oldest week first.
+#[derive(Debug, Serialize)]
+pub struct OrgActivity {
+    pub org: String,
+    pub weekly_commits: Vec<u32>,
 }
 
 #[derive(Debug, Serialize)]
@@ -32,7 +81,8 @@ pub struct RepoEntry {
     pub name: String,
     pub stars: u32,
     pub last_push: String,
-    pub days_since_push: i64,
+    /// `None` when the repo has never been pushed to
+    pub days_since_push: Option<i64>,
 }
 
 #[derive(Debug, Serialize)]
@@ -44,34 +94,121 @@ pub struct IssueEntry {
     pub updated: String,
 }
 
-pub async fn run(org: &Option<String>, days: u64, json: bool, verbose: bool) -> Result<()> {
-    let config = load_config()?;
-    let token = config.token()?;
-    let client = GithubClient::new(token, verbose)?;
+pub async fn run(args: &OverviewArgs, global: &GlobalArgs) -> Result<()> {
+    if args.watch && global.json {
+        return Err(GitorgError::Config(
+            "--watch can't be combined with --json; run without --watch to get a JSON snapshot."
+                .into(),
+        ));
+    }
+    if args.watch && global.output.is_some() {
+        return Err(GitorgError::Config(
+            "--watch can't be combined with --output; it redraws the terminal.".into(),
+        ));
+    }
+
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
 
     client.warn_if_rate_limited().await.ok();
 
-    let orgs = resolve_orgs(org, &config, &client).await?;
+    let orgs = resolve_orgs(&args.org, &global.exclude_orgs, &config, &client).await?;
+
+    let days = stale_days(args.days, &config);
+    if args.watch {
+        return watch_overview(&client, args, days, &orgs).await;
+    }
+
+    let overview = collect_overview(&client, args, days, &orgs).await?;
+
+    display::output(global.json, &overview, |data| {
+        render_overview(data);
+    });
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// Clears the screen and re-renders the dashboard every `--interval` seconds
+/// until Ctrl-C, which may arrive mid-fetch.
+async fn watch_overview(
+    client: &GithubClient,
+    args: &OverviewArgs,
+    days: u64,
+    orgs: &[String],
+) -> Result<()> {
+    let interval = args.interval;
+    let mut ticker = tokio::time::interval(Duration::from_secs(interval));
+    loop {
+        let refresh = async {
+            ticker.tick().await;
+            let overview = collect_overview(client, args, days, orgs).await?;
+            display::out!("\x1b[2J\x1b[H");
+            render_overview(&overview);
+            display::outln!(
+                "\nRefreshed {}. Updating every {interval}s; press Ctrl-C to stop.",
+                Utc::now().format("%H:%M:%S UTC")
+            );
+            std::io::stdout().flush()?;
+            Ok::<(), GitorgError>(())
+        };
+
+        tokio::select! {
+            result = refresh => result?,
+            _ = tokio::signal::ctrl_c() => {
+                display::outln!();
+                return Ok(());
+            }
+        }
+    }
+}
+
+async fn collect_overview(
+    client: &GithubClient,
+    args: &OverviewArgs,
+    days: u64,
+    orgs: &[String],
+) -> Result<OverviewData> {
     let now = Utc::now();
 
     let mut total_repos = 0usize;
     let mut total_stars = 0u32;
     let mut total_forks = 0u32;
     let mut total_open_issues = 0u32;
+    let mut total_open_prs = 0u32;
     let mut lang_map: HashMap<String, usize> = HashMap::new();
     let mut all_repo_entries = Vec::new();
     let mut recent_issues = Vec::new();
+    let mut seen = SeenRepos::default();
+    let mut commit_activity = Vec::new();
 
-    for org_name in &orgs {
-        let repos = match client.list_org_repos(org_name).await {
+    let mut failures = OrgFailures::new(orgs.len())
+        .with_empty_report(args.include_empty_orgs || client.is_verbose());
+    for org_name in orgs {
+        let repos = match client.list_org_repos(org_name, args.visibility).await {
             Ok(r) => r,
             Err(e) => {
-                display::warn(&format!("Failed to fetch repos for {org_name}: {e}"));
+                failures.repos_failed(&args.org, org_name, e)?;
                 continue;
             }
         };
+        failures.listed(org_name, repos.len());
+
+        if args.commit_activity {
+            let weekly_commits = org_commit_activity(client, org_name, &repos, args.weeks).await;
+            commit_activity.push(OrgActivity {
+                org: org_name.clone(),
+                weekly_commits,
+            });
+        }
 
         for repo in &repos {
+            if !seen.insert(repo) {
+                continue;
+            }
             total_repos += 1;
             let stars = repo.stargazers_count.unwrap_or(0);
             total_stars += stars;
@@ -86,11 +223,6 @@ pub async fn run(org: &Option<String>, days: u64, json: bool, verbose: bool) ->
                 .to_string();
             *lang_map.entry(language).or_insert(0) += 1;
 
-            let days_since = repo
-                .pushed_at
-                .map(|dt| (now - dt).num_days())
-                .unwrap_or(99999);
-
             all_repo_entries.push(RepoEntry {
                 org: org_name.clone(),
                 name: repo.name.clone(),
@@ -99,12 +231,24 @@ pub async fn run(org: &Option<String>, days: u64, json: bool, verbose: bool) ->
                     .pushed_at
                     .map(|dt| dt.format("%Y-%m-%d").to_string())
                     .unwrap_or_else(|| "never".to_string()),
-                days_since_push: days_since,
+                days_since_push: days_since_push(repo, now),
             });
 
-            // Fetch issues for repos that have them and aren't archived
-            if !repo.archived.unwrap_or(false) && repo.open_issues_count.unwrap_or(0) > 0 {
-                if let Ok(issues) = client.list_repo_issues(org_name, &repo.name).await {
+            // Fetch issues for repos that have them and aren't archived. The
+            // listing includes PRs, so with --accurate-issue-counts it also
+            // splits them out of open_issues_count, archived repos included.
+            let archived = repo.archived.unwrap_or(false);
+            let open_issues = repo.open_issues_count.unwrap_or(0);
+            if open_issues > 0 && (!archived || args.accurate_issue_counts) {
+                if let Ok(issues) = client.list_repo_issues(org_name, &repo.name, None).await {
+                    if args.accurate_issue_counts {
+                        let prs = issues.iter().filter(|i| i.pull_request.is_some()).count();
+                        total_open_prs += prs as u32;
+                        total_open_issues -= (prs as u32).min(open_issues);
+                    }
+                    if archived {
+                        continue;
+                    }
                     for issue in issues.into_iter().take(3) {
                         if issue.pull_request.is_some() {
                             continue;
@@ -122,11 +266,14 @@ pub async fn run(org: &Option<String>, days: u64, json: bool, verbose: bool) ->
         }
     }
 
+    failures.finish()?;
+
     // Sort and limit
-    all_repo_entries.sort_by(|a, b| a.days_since_push.cmp(&b.days_since_push));
+    // Never-pushed repos sort after everything else
+    all_repo_entries.sort_by_key(|r| r.days_since_push.unwrap_or(i64::MAX));
     let recently_active: Vec<RepoEntry> = all_repo_entries
         .iter()
-        .filter(|r| r.days_since_push < days as i64)
+        .filter(|r| r.days_since_push.is_some_and(|d| d < days as i64))
         .take(10)
         .map(|r| RepoEntry {
             org: r.org.clone(),
@@ -140,7 +287,7 @@ pub async fn run(org: &Option<String>, days: u64, json: bool, verbose: bool) ->
     let stale_repos: Vec<RepoEntry> = all_repo_entries
         .iter()
         .rev()
-        .filter(|r| r.days_since_push >= days as i64)
+        .filter(|r| r.days_since_push.map_or(true, |d| d >= days as i64))
         .take(10)
         .map(|r| RepoEntry {
             org: r.org.clone(),
@@ -158,49 +305,112 @@ pub async fn run(org: &Option<String>, days: u64, json: bool, verbose: bool) ->
         .into_iter()
         .map(|(language, count)| LangEntry { language, count })
         .collect();
-    top_languages.sort_by(|a, b| b.count.cmp(&a.count));
+    top_languages.sort_by_key(|l| Reverse(l.count));
     top_languages.truncate(5);
 
-    let overview = OverviewData {
+    Ok(OverviewData {
         total_repos,
         total_stars,
         total_forks,
         total_open_issues,
+        total_open_prs: args.accurate_issue_counts.then_some(total_open_prs),
         top_languages,
         recently_active,
         stale_repos,
         recent_issues,
-    };
+        commit_activity: args.commit_activity.then_some(commit_activity),
+    })
+}
 
-    display::output(json, &overview, |data| {
-        render_overview(data);
-    });
+/// Sums the last `weeks` of commit counts over an org's non-empty,
+/// unarchived repos. Repos whose stats can't be fetched are left out.
+async fn org_commit_activity(
+    client: &GithubClient,
+    org: &str,
+    repos: &[OrgRepo],
+    weeks: u32,
+) -> Vec<u32> {
+    let results: Vec<_> = stream::iter(
+        repos
+            .iter()
+            .filter(|r| !r.archived.unwrap_or(false) && r.size.unwrap_or(0) > 0),
+    )
+    .map(|repo| async move { (repo, client.get_commit_activity(org, &repo.name).await) })
+    .buffer_unordered(client.max_concurrency())
+    .collect()
+    .await;
 
-    client.check_rate_limit_if_verbose().await;
+    let mut totals = vec![0; weeks as usize];
+    for (repo, result) in results {
+        match result {
+            Ok(Some(counts)) => add_recent_weeks(&mut totals, &counts),
+            Ok(None) => {}
+            Err(e) => display::warn(&format!(
+                "Failed to fetch commit activity for {org}/{}: {e}",
+                repo.name
+            )),
+        }
+    }
+    totals
+}
 
-    Ok(())
+/// Adds the newest `totals.len()` weeks of `counts` onto `totals`, aligned
+/// at the most recent week.
+fn add_recent_weeks(totals: &mut [u32], counts: &[u32]) {
+    let offset = totals.len().saturating_sub(counts.len());
+    let recent = &counts[counts.len().saturating_sub(totals.len())..];
+    for (total, count) in totals[offset..].iter_mut().zip(recent) {
+        *total += count;
+    }
 }
 
 fn render_overview(data: &OverviewData) {
     // Summary
     display::section_header("Summary");
-    println!(
-        "  {} {}   {} {}   {} {}   {} {}",
-        "Repos:".bold(),
+    display::out!(
+        "  {} {}   {} {}   {} {}   ",
+        display::bold("Repos:"),
         data.total_repos,
-        "Stars:".bold(),
+        display::bold("Stars:"),
         data.total_stars,
-        "Forks:".bold(),
+        display::bold("Forks:"),
         data.total_forks,
-        "Issues:".bold(),
-        data.total_open_issues,
     );
+    match data.total_open_prs {
+        Some(prs) => display::outln!(
+            "{} {}   {} {}",
+            display::bold("Issues:"),
+            data.total_open_issues,
+            display::bold("PRs:"),
+            prs
+        ),
+        None => display::outln!(
+            "{} {}",
+            display::bold("Issues + PRs:"),
+            data.total_open_issues
+        ),
+    }
+
+    // Commit Activity
+    if let Some(activity) = &data.commit_activity {
+        let weeks = activity.first().map_or(0, |a| a.weekly_commits.len());
+        display::section_header(&format!("Commit Activity (last {weeks} weeks)"));
+        let width = activity.iter().map(|a| a.org.len()).max().unwrap_or(0);
+        for a in activity {
+            let commits: u32 = a.weekly_commits.iter().sum();
+            display::outln!(
+                "  {:<width$}  {}  {commits} commit(s)",
+                a.org,
+                display::sparkline(&a.weekly_commits)
+            );
+        }
+    }
 
     // Top Languages
     if !data.top_languages.is_empty() {
         display::section_header("Top Languages");
         for lang in &data.top_languages {
-            println!("  {} ({})", lang.language, lang.count);
+            display::outln!("  {} ({})", lang.language, lang.count);
         }
     }
 
@@ -211,7 +421,7 @@ fn render_overview(data: &OverviewData) {
         for r in &data.recently_active {
             table.add_row(vec![&r.org, &r.name, &r.stars.to_string(), &r.last_push]);
         }
-        println!("{table}");
+        display::print_table(&table);
     }
 
     // Stale Repos
@@ -224,10 +434,10 @@ fn render_overview(data: &OverviewData) {
                 &r.name,
                 &r.stars.to_string(),
                 &r.last_push,
-                &r.days_since_push.to_string(),
+                &r.days_since_push.map_or("-".to_string(), |d| d.to_string()),
             ]);
         }
-        println!("{table}");
+        display::print_table(&table);
     }
 
     // Recent Issues
@@ -243,6 +453,19 @@ fn render_overview(data: &OverviewData) {
                 &i.updated,
             ]);
         }
-        println!("{table}");
+        display::print_table(&table);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_weeks_align_at_the_newest() {
+        let mut totals = vec![0; 3];
+        add_recent_weeks(&mut totals, &[9, 9, 1, 2, 3]);
+        add_recent_weeks(&mut totals, &[5, 5]);
+        assert_eq!(totals, vec![1, 7, 8]);
     }
 }
@@ -0,0 +1,120 @@
+use crate::commands::GlobalArgs;
+use crate::config::{load_config, save_config, Config, DEFAULT_PROFILE};
+use crate::display;
+use crate::error::{GitorgError, Result};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct ProfileSummary {
+    pub name: String,
+    pub active: bool,
+    pub authenticated: bool,
+    pub orgs: Vec<String>,
+}
+
+pub fn list(global: &GlobalArgs) -> Result<()> {
+    let config = load_config(&global.config_path()?)?;
+    let summaries = summarize(&config);
+
+    display::output_records(global.json, &summaries, |data| {
+        render_profiles_table(data);
+    })?;
+
+    Ok(())
+}
+
+pub fn switch(name: &str, global: &GlobalArgs) -> Result<()> {
+    let path = global.config_path()?;
+    let mut config = load_config(&path)?;
+    if !config.has_profile(name) {
+        return Err(GitorgError::Config(format!(
+            "Unknown profile '{name}'. Create it with `gitorg auth --profile {name}`."
+        )));
+    }
+
+    config.active_profile = if name == DEFAULT_PROFILE {
+        None
+    } else {
+        Some(name.to_string())
+    };
+    save_config(&path, &config)?;
+
+    display::success(&format!("Switched to profile '{name}'"));
+    Ok(())
+}
+
+fn summarize(config: &Config) -> Vec<ProfileSummary> {
+    let active = config.profile_name();
+    let default = ProfileSummary {
+        name: DEFAULT_PROFILE.to_string(),
+        active: active == DEFAULT_PROFILE,
+        authenticated: config.auth.has_token(),
+        orgs: config.defaults.orgs.clone().unwrap_or_default(),
+    };
+
+    std::iter::once(default)
+        .chain(
+            config
+                .profiles
+                .iter()
+                .map(|(name, profile)| ProfileSummary {
+                    name: name.clone(),
+                    active: active == name,
+                    authenticated: profile.auth.has_token(),
+                    orgs: profile.defaults.orgs.clone().unwrap_or_default(),
+                }),
+        )
+        .collect()
+}
+
+fn render_profiles_table(profiles: &[ProfileSummary]) {
+    display::section_header("Profiles");
+
+    let mut table = display::new_table(&["Name", "Active", "Authenticated", "Default Orgs"]);
+
+    for p in profiles {
+        table.add_row(vec![
+            p.name.clone(),
+            if p.active { "*" } else { "" }.to_string(),
+            if p.authenticated { "yes" } else { "no" }.to_string(),
+            if p.orgs.is_empty() {
+                "-".to_string()
+            } else {
+                p.orgs.join(", ")
+            },
+        ]);
+    }
+
+    display::print_table(&table);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summary_lists_default_first_and_marks_active() {
+        let config: Config = toml::from_str(
+            r#"
+            active_profile = "work"
+
+            [auth]
+            token = "ghp_personal"
+
+            [profiles.work.defaults]
+            orgs = ["work-org"]
+            "#,
+        )
+        .unwrap();
+
+        let summaries = summarize(&config);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].name, DEFAULT_PROFILE);
+        assert!(!summaries[0].active);
+        assert!(summaries[0].authenticated);
+        assert_eq!(summaries[1].name, "work");
+        assert!(summaries[1].active);
+        assert!(!summaries[1].authenticated);
+        assert_eq!(summaries[1].orgs, vec!["work-org".to_string()]);
+    }
+}
@@ -0,0 +1,250 @@
+use crate::commands::{github_client, resolve_orgs, GlobalArgs, OrgFailures};
+use crate::display;
+use crate::error::Result;
+use crate::github::{BranchProtection, RepoVisibility};
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct ProtectionStatus {
+    pub org: String,
+    pub name: String,
+    pub branch: String,
+    /// `None` when the rule couldn't be read (it needs admin access)
+    pub protected: Option<bool>,
+    /// Approving reviews a pull request needs before merging
+    pub required_reviews: Option<u32>,
+    /// Names of the checks that must pass, `None` when none are enforced
+    pub required_status_checks: Option<Vec<String>>,
+}
+
+impl ProtectionStatus {
+    fn new(org: &str, name: &str, branch: &str, protection: Option<BranchProtection>) -> Self {
+        Self {
+            org: org.to_string(),
+            name: name.to_string(),
+            branch: branch.to_string(),
+            protected: Some(protection.is_some()),
+            required_reviews: protection
+                .as_ref()
+                .and_then(|p| p.required_pull_request_reviews.as_ref())
+                .map(|r| r.required_approving_review_count),
+            required_status_checks: protection
+                .and_then(|p| p.required_status_checks)
+                .map(|checks| checks.contexts),
+        }
+    }
+}
+
+/// With `unprotected_default`, only repos whose default branch is known to
+/// be unprotected are reported.
+pub async fn run(
+    org: &Option<String>,
+    unprotected_default: bool,
+    global: &GlobalArgs,
+) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    client.warn_if_rate_limited().await.ok();
+
+    let orgs = resolve_orgs(org, &global.exclude_orgs, &config, &client).await?;
+
+    let mut statuses = Vec::new();
+    let mut unreadable = Vec::new();
+    let mut failures = OrgFailures::new(orgs.len());
+    for org_name in &orgs {
+        let repos = match client.list_org_repos(org_name, RepoVisibility::All).await {
+            Ok(repos) => repos,
+            Err(e) => {
+                failures.repos_failed(org, org_name, e)?;
+                continue;
+            }
+        };
+
+        // Archived repos are read-only, so their branches can't change
+        let client = &client;
+        let results: Vec<_> = stream::iter(repos.iter().filter_map(|repo| {
+            let branch = repo.default_branch.as_deref()?;
+            (!repo.archived.unwrap_or(false)).then_some((repo.name.as_str(), branch))
+        }))
+        .map(|(name, branch)| async move {
+            let protection = client.get_branch_protection(org_name, name, branch).await;
+            (name, branch, protection)
+        })
+        .buffer_unordered(client.max_concurrency())
+        .collect()
+        .await;
+
+        for (name, branch, result) in results {
+            match result {
+                Ok(protection) => {
+                    statuses.push(ProtectionStatus::new(org_name, name, branch, protection))
+                }
+                Err(e) => {
+                    unreadable.push(e);
+                    statuses.push(ProtectionStatus {
+                        protected: None,
+                        ..ProtectionStatus::new(org_name, name, branch, None)
+                    });
+                }
+            }
+        }
+    }
+    failures.finish()?;
+
+    // One warning rather than one per repo: without admin rights, every
+    // repo fails the same way
+    if let Some(first) = unreadable.first() {
+        display::warn(&format!(
+            "Couldn't read branch protection for {} repo(s): {first}",
+            unreadable.len()
+        ));
+    }
+
+    sort_protection(&mut statuses);
+    if unprotected_default {
+        statuses.retain(|s| s.protected == Some(false));
+    }
+
+    display::output_records(global.json, &statuses, |data| {
+        if unprotected_default {
+            render_unprotected_table(data);
+        } else {
+            render_protection_table(data);
+        }
+    })?;
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// Unprotected branches first, then unknown, then protected; by name within
+/// each group.
+fn sort_protection(statuses: &mut [ProtectionStatus]) {
+    let rank = |s: &ProtectionStatus| match s.protected {
+        Some(false) => 0,
+        None => 1,
+        Some(true) => 2,
+    };
+    statuses.sort_by(|a, b| {
+        rank(a)
+            .cmp(&rank(b))
+            .then_with(|| (&a.org, &a.name).cmp(&(&b.org, &b.name)))
+    });
+}
+
+fn render_protection_table(statuses: &[ProtectionStatus]) {
+    if statuses.is_empty() {
+        display::warn("No repositories found.");
+        return;
+    }
+
+    let unprotected = statuses
+        .iter()
+        .filter(|s| s.protected == Some(false))
+        .count();
+    display::section_header(&format!(
+        "Default Branch Protection ({unprotected} unprotected)"
+    ));
+
+    let mut table = display::new_table(&[
+        "Org",
+        "Repo",
+        "Branch",
+        "Protected",
+        "Required Reviews",
+        "Status Checks",
+    ]);
+    for s in statuses {
+        let yes_no = |value: bool| if value { "yes" } else { "no" }.to_string();
+        table.add_row(vec![
+            s.org.clone(),
+            s.name.clone(),
+            s.branch.clone(),
+            s.protected.map_or("?".to_string(), yes_no),
+            s.required_reviews
+                .map_or("-".to_string(), |count| count.to_string()),
+            if s.protected == Some(true) {
+                yes_no(s.required_status_checks.is_some())
+            } else {
+                "-".to_string()
+            },
+        ]);
+    }
+
+    display::print_table(&table);
+    display::count_footer(statuses.len(), statuses.len(), "repository(ies)");
+}
+
+/// The `--unprotected-default` report: just the repos to fix.
+fn render_unprotected_table(statuses: &[ProtectionStatus]) {
+    if statuses.is_empty() {
+        display::success("Every readable default branch is protected.");
+        return;
+    }
+
+    display::section_header("Unprotected Default Branches");
+
+    let mut table = display::new_table(&["Org", "Repo", "Branch", "Required Reviews"]);
+    for s in statuses {
+        table.add_row(vec![
+            s.org.clone(),
+            s.name.clone(),
+            s.branch.clone(),
+            s.required_reviews
+                .map_or("-".to_string(), |count| count.to_string()),
+        ]);
+    }
+
+    display::print_table(&table);
+    display::count_footer(
+        statuses.len(),
+        statuses.len(),
+        "unprotected repository(ies)",
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(name: &str, protected: Option<bool>) -> ProtectionStatus {
+        ProtectionStatus {
+            protected,
+            ..ProtectionStatus::new("acme", name, "main", None)
+        }
+    }
+
+    #[test]
+    fn unprotected_branches_sort_first() {
+        let mut statuses = vec![
+            status("safe", Some(true)),
+            status("hidden", None),
+            status("zeta", Some(false)),
+            status("alpha", Some(false)),
+        ];
+        sort_protection(&mut statuses);
+        let names: Vec<&str> = statuses.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "zeta", "hidden", "safe"]);
+    }
+
+    #[test]
+    fn protection_rule_fills_reviews_and_checks() {
+        let protection: BranchProtection = serde_json::from_value(serde_json::json!({
+            "required_pull_request_reviews": { "required_approving_review_count": 1 },
+        }))
+        .unwrap();
+        let status = ProtectionStatus::new("acme", "tool", "main", Some(protection));
+        assert_eq!(status.protected, Some(true));
+        assert_eq!(status.required_reviews, Some(1));
+        assert_eq!(status.required_status_checks, None);
+
+        let status = ProtectionStatus::new("acme", "tool", "main", None);
+        assert_eq!(status.protected, Some(false));
+        assert_eq!(status.required_reviews, None);
+    }
+}
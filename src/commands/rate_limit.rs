@@ -0,0 +1,43 @@
+use crate::commands::{github_client, GlobalArgs};
+use crate::display;
+use crate::error::Result;
+use crate::github::RateLimit;
+
+pub async fn run(global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let rate_limit = client.get_rate_limit().await?;
+
+    display::output(global.json, &rate_limit, |data| {
+        render_rate_limit_table(data);
+    });
+
+    Ok(())
+}
+
+fn render_rate_limit_table(rate_limit: &RateLimit) {
+    display::section_header("Rate Limits");
+
+    let mut table = display::new_table(&["Resource", "Remaining", "Limit", "Resets At"]);
+
+    let resources = &rate_limit.resources;
+    let rows = [
+        ("core", Some(&resources.core)),
+        ("search", resources.search.as_ref()),
+        ("graphql", resources.graphql.as_ref()),
+    ];
+    for (name, resource) in rows {
+        let Some(r) = resource else {
+            continue;
+        };
+        table.add_row(vec![
+            name.to_string(),
+            r.remaining.to_string(),
+            r.limit.to_string(),
+            r.reset_time(),
+        ]);
+    }
+
+    display::print_table(&table);
+}
@@ -0,0 +1,207 @@
+use crate::commands::{apply_limit, github_client, resolve_orgs, GlobalArgs, OrgFailures};
+use crate::display;
+use crate::error::Result;
+use crate::github::RepoVisibility;
+use chrono::{DateTime, Utc};
+use clap::Args;
+use futures::stream::{self, StreamExt};
+use octocrab::models::repos::Release;
+use serde::Serialize;
+use std::cmp::Reverse;
+
+#[derive(Args, Debug)]
+pub struct ReleasesArgs {
+    /// Filter to specific organizations (comma-separated)
+    #[arg(long)]
+    pub org: Option<String>,
+    /// Sort by: recent (newest release first), oldest, name; repos that
+    /// never released go last
+    #[arg(long, default_value = "recent")]
+    pub sort: String,
+    /// Show at most this many repos (after sorting)
+    #[arg(long)]
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoRelease {
+    pub org: String,
+    pub name: String,
+    /// `None` when the repo has no published release
+    pub tag: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+    pub days_since_release: Option<i64>,
+}
+
+impl RepoRelease {
+    fn new(org: &str, name: &str, release: Option<Release>, now: DateTime<Utc>) -> Self {
+        let published_at = release
+            .as_ref()
+            .and_then(|r| r.published_at.or(r.created_at));
+        Self {
+            org: org.to_string(),
+            name: name.to_string(),
+            tag: release.map(|r| r.tag_name),
+            published_at,
+            days_since_release: published_at.map(|at| (now - at).num_days()),
+        }
+    }
+}
+
+pub async fn run(args: &ReleasesArgs, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    client.warn_if_rate_limited().await.ok();
+
+    let orgs = resolve_orgs(&args.org, &global.exclude_orgs, &config, &client).await?;
+    let now = Utc::now();
+
+    let mut releases = Vec::new();
+    let mut failures = OrgFailures::new(orgs.len());
+    for org_name in &orgs {
+        let repos = match client.list_org_repos(org_name, RepoVisibility::All).await {
+            Ok(repos) => repos,
+            Err(e) => {
+                failures.repos_failed(&args.org, org_name, e)?;
+                continue;
+            }
+        };
+
+        // Archived repos won't ship again
+        let client = &client;
+        let results: Vec<_> =
+            stream::iter(repos.iter().filter(|repo| !repo.archived.unwrap_or(false)))
+                .map(|repo| async move {
+                    let release = client.get_latest_release(org_name, &repo.name).await;
+                    (repo.name.as_str(), release)
+                })
+                .buffer_unordered(client.max_concurrency())
+                .collect()
+                .await;
+
+        for (name, result) in results {
+            let release = result.unwrap_or_else(|e| {
+                display::warn(&format!(
+                    "Failed to fetch the latest release of {org_name}/{name}: {e}"
+                ));
+                None
+            });
+            releases.push(RepoRelease::new(org_name, name, release, now));
+        }
+    }
+    failures.finish()?;
+
+    sort_releases(&mut releases, &args.sort);
+    let total = apply_limit(&mut releases, args.limit);
+
+    display::output_records(global.json, &releases, |data| {
+        render_releases_table(data, total);
+    })?;
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+fn sort_releases(releases: &mut [RepoRelease], sort: &str) {
+    let never = |r: &RepoRelease| r.published_at.is_none();
+    match sort {
+        "oldest" => releases.sort_by_key(|r| (never(r), r.published_at)),
+        "name" => releases.sort_by_key(|r| (r.org.to_lowercase(), r.name.to_lowercase())),
+        _ => releases.sort_by_key(|r| (never(r), Reverse(r.published_at))), // recent
+    }
+}
+
+fn render_releases_table(releases: &[RepoRelease], total: usize) {
+    if releases.is_empty() {
+        display::warn("No repositories found.");
+        return;
+    }
+
+    let released = releases.iter().filter(|r| r.tag.is_some()).count();
+    display::section_header(&format!(
+        "Latest Releases ({released} of {} repos have one)",
+        releases.len()
+    ));
+
+    let mut table = display::new_table(&["Org", "Repo", "Latest Tag", "Released", "Days Ago"]);
+    for r in releases {
+        table.add_row(vec![
+            r.org.clone(),
+            r.name.clone(),
+            r.tag.clone().unwrap_or_else(|| "-".to_string()),
+            r.published_at
+                .map_or("-".to_string(), |at| at.format("%Y-%m-%d").to_string()),
+            r.days_since_release
+                .map_or("-".to_string(), |days| days.to_string()),
+        ]);
+    }
+
+    display::print_table(&table);
+    display::count_footer(releases.len(), total, "repository(ies)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn release(name: &str, published_at: Option<&str>) -> RepoRelease {
+        let published_at = published_at.map(|at| at.parse().unwrap());
+        RepoRelease {
+            org: "acme".into(),
+            name: name.into(),
+            tag: published_at.map(|_| "v1.0.0".to_string()),
+            published_at,
+            days_since_release: None,
+        }
+    }
+
+    fn names(releases: &[RepoRelease]) -> Vec<&str> {
+        releases.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn never_released_repos_sort_last_either_way() {
+        let mut releases = vec![
+            release("never", None),
+            release("old", Some("2021-01-01T00:00:00Z")),
+            release("new", Some("2024-06-01T00:00:00Z")),
+        ];
+        sort_releases(&mut releases, "recent");
+        assert_eq!(names(&releases), vec!["new", "old", "never"]);
+        sort_releases(&mut releases, "oldest");
+        assert_eq!(names(&releases), vec!["old", "new", "never"]);
+    }
+
+    #[test]
+    fn days_since_release_counts_from_publication() {
+        let now = "2024-06-11T00:00:00Z".parse().unwrap();
+        let release: Release = serde_json::from_value(serde_json::json!({
+            "url": "https://api.github.com/repos/acme/tool/releases/1",
+            "html_url": "https://github.com/acme/tool/releases/tag/v2.1.0",
+            "assets_url": "https://api.github.com/repos/acme/tool/releases/1/assets",
+            "upload_url": "https://uploads.github.com/repos/acme/tool/releases/1/assets",
+            "id": 1,
+            "node_id": "RE_1",
+            "tag_name": "v2.1.0",
+            "target_commitish": "main",
+            "draft": false,
+            "prerelease": false,
+            "created_at": "2024-05-31T12:00:00Z",
+            "published_at": "2024-06-01T00:00:00Z",
+            "assets": [],
+        }))
+        .unwrap();
+
+        let summary = RepoRelease::new("acme", "tool", Some(release), now);
+        assert_eq!(summary.tag.as_deref(), Some("v2.1.0"));
+        assert_eq!(summary.days_since_release, Some(10));
+
+        let none = RepoRelease::new("acme", "quiet", None, now);
+        assert_eq!(none.tag, None);
+        assert_eq!(none.days_since_release, None);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::commands::open::open_in_browser;
+use crate::commands::{github_client, GlobalArgs};
+use crate::display;
+use crate::error::{GitorgError, Result};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct RepoDetail {
+    pub org: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub url: String,
+    pub stars: u32,
+    pub forks: u32,
+    pub open_issues: u32,
+    pub topics: Vec<String>,
+    pub license: Option<String>,
+    pub default_branch: Option<String>,
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub archived: bool,
+    pub latest_release: Option<ReleaseSummary>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReleaseSummary {
+    pub tag: String,
+    pub name: Option<String>,
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Shows a repo's details; with `open`, also opens its page in the browser.
+pub async fn run(slug: &str, open: bool, global: &GlobalArgs) -> Result<()> {
+    let (owner, name) = parse_slug(slug)?;
+
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let repo = client.get_repo(owner, name).await?;
+    let release = client.get_latest_release(owner, name).await?;
+
+    let detail = RepoDetail {
+        org: owner.to_string(),
+        name: repo.name.clone(),
+        description: repo.description.filter(|d| !d.is_empty()),
+        url: repo
+            .html_url
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| format!("https://github.com/{owner}/{name}")),
+        stars: repo.stargazers_count.unwrap_or(0),
+        forks: repo.forks_count.unwrap_or(0),
+        open_issues: repo.open_issues_count.unwrap_or(0),
+        topics: repo.topics.unwrap_or_default(),
+        license: repo.license.map(|l| l.spdx_id),
+        default_branch: repo.default_branch,
+        pushed_at: repo.pushed_at,
+        archived: repo.archived.unwrap_or(false),
+        latest_release: release.map(|r| ReleaseSummary {
+            tag: r.tag_name,
+            name: r.name.filter(|n| !n.is_empty()),
+            published_at: r.published_at,
+        }),
+    };
+
+    display::output_single(global.json, global.wrap_array, &detail, |data| {
+        render_repo(data);
+    });
+    if open {
+        open_in_browser(&detail.url);
+    }
+
+    client.report_retries(global.json);
+
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// Splits `org/name`, rejecting anything without exactly one slash.
+pub fn parse_slug(slug: &str) -> Result<(&str, &str)> {
+    match slug.split_once('/') {
+        Some((owner, name)) if !owner.is_empty() && !name.is_empty() && !name.contains('/') => {
+            Ok((owner, name))
+        }
+        _ => Err(GitorgError::Config(format!(
+            "Expected a repository as <org>/<name>, got '{slug}'"
+        ))),
+    }
+}
+
+fn render_repo(repo: &RepoDetail) {
+    display::section_header(&format!("{}/{}", repo.org, repo.name));
+
+    let or_dash = |value: Option<&str>| value.unwrap_or("-").to_string();
+
+    if let Some(description) = &repo.description {
+        display::outln!("  {description}\n");
+    }
+    display::outln!("  {} {}", display::bold("URL:"), repo.url);
+    display::outln!("  {} {}", display::bold("Stars:"), repo.stars);
+    display::outln!("  {} {}", display::bold("Forks:"), repo.forks);
+    display::outln!(
+        "  {} {}",
+        display::bold("Open Issues + PRs:"),
+        repo.open_issues
+    );
+    display::outln!(
+        "  {} {}",
+        display::bold("Topics:"),
+        if repo.topics.is_empty() {
+            "-".to_string()
+        } else {
+            repo.topics.join(", ")
+        }
+    );
+    display::outln!(
+        "  {} {}",
+        display::bold("License:"),
+        or_dash(repo.license.as_deref())
+    );
+    display::outln!(
+        "  {} {}",
+        display::bold("Default Branch:"),
+        or_dash(repo.default_branch.as_deref())
+    );
+    display::outln!(
+        "  {} {}",
+        display::bold("Last Push:"),
+        repo.pushed_at
+            .map(|dt| dt.format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "never".to_string())
+    );
+    if repo.archived {
+        display::outln!("  {} yes", display::bold("Archived:"));
+    }
+    display::outln!(
+        "  {} {}",
+        display::bold("Latest Release:"),
+        match &repo.latest_release {
+            Some(r) => format!(
+                "{}{}",
+                r.tag,
+                r.published_at
+                    .map(|dt| format!(" ({})", dt.format("%Y-%m-%d")))
+                    .unwrap_or_default()
+            ),
+            None => "-".to_string(),
+        }
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_slug_splits_org_and_name() {
+        assert_eq!(parse_slug("acme/widgets").unwrap(), ("acme", "widgets"));
+    }
+
+    #[test]
+    fn parse_slug_rejects_malformed_input() {
+        for slug in ["widgets", "/widgets", "acme/", "acme/widgets/extra"] {
+            assert!(
+                matches!(parse_slug(slug), Err(GitorgError::Config(_))),
+                "{slug}"
+            );
+        }
+    }
+}
@@ -1,26 +1,177 @@
-use crate::commands::resolve_orgs;
-use crate::config::load_config;
-use crate::display;
+use crate::badges::{extract_badges, Badge, BadgeKind};
+use crate::browser::{self, Browsable};
+use crate::commands::{
+    apply_limit, days_since_activity, github_client, parse_date, resolve_orgs, ActivityMetric,
+    ArchivedFilter, GlobalArgs, OrgFailures, StarFilter,
+};
+use crate::config::Config;
+use crate::display::{self, RecordSink};
 use crate::error::Result;
-use crate::github::GithubClient;
-use chrono::Utc;
-use octocrab::models::Repository;
+use crate::github::{GithubClient, OrgRepo, RepoVisibility};
+use crate::health::{health_score, HealthInputs};
+use crate::last_run::{changed_since, load_last_run, record_last_run};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::{Args, ValueEnum};
+use comfy_table::{Cell, ColumnConstraint, Table, Width};
+use futures::stream::{self, StreamExt};
 use serde::Serialize;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io::IsTerminal;
+
+/// Widest the Description column gets before wrapping.
+const DESCRIPTION_WIDTH: u16 = 50;
+
+/// The `--sort` orders, which Tab cycles through in `--interactive`.
+const SORTS: &[&str] = &["activity", "stars", "staleness", "name", "size"];
+
+#[derive(Args, Debug)]
+pub struct ReposArgs {
+    /// Filter to specific organizations (comma-separated)
+    #[arg(long)]
+    pub org: Option<String>,
+    /// Sort by: activity, stars, staleness, name, size [default: activity, or defaults.repo_sort]
+    #[arg(long)]
+    pub sort: Option<String>,
+    /// Only show repos updated since the last `--since-last-run` invocation
+    #[arg(long)]
+    pub since_last_run: bool,
+    /// Show at most this many repos (after sorting)
+    #[arg(long)]
+    pub limit: Option<usize>,
+    /// Fetch each README and report its shields.io badges (one extra request per repo)
+    #[arg(long)]
+    pub readme_badges: bool,
+    /// Only show repos whose primary language matches (repeatable)
+    #[arg(long = "language", value_name = "LANG")]
+    pub languages: Vec<String>,
+    #[command(flatten)]
+    pub stars: StarFilter,
+    #[command(flatten)]
+    pub archived: ArchivedFilter,
+    /// Only show repos with GitHub Discussions enabled
+    #[arg(long)]
+    pub with_discussions: bool,
+    /// Which repos to include: all, public, or private
+    #[arg(long, value_enum, default_value_t)]
+    pub visibility: RepoVisibility,
+    /// Only show repos with at least this health score (0-100)
+    #[arg(long, value_parser = clap::value_parser!(u8).range(0..=100))]
+    pub min_health: Option<u8>,
+    /// Only show repos tagged with this topic (repeatable; see --topic-match)
+    #[arg(long = "topic", value_name = "TOPIC")]
+    pub topics: Vec<String>,
+    /// With several --topic flags: any (at least one tagged) or all (every one)
+    #[arg(long, value_enum, default_value_t)]
+    pub topic_match: TopicMatch,
+    /// Add a Topics column to the table
+    #[arg(long)]
+    pub show_topics: bool,
+    /// Only show repos created on or after this date (YYYY-MM-DD)
+    #[arg(long)]
+    pub created_after: Option<String>,
+    /// Add a Created column to the table
+    #[arg(long)]
+    pub show_created: bool,
+    /// Add a Size column to the table
+    #[arg(long)]
+    pub show_size: bool,
+    /// Add a Description column to the table (long ones wrap)
+    #[arg(long)]
+    pub show_description: bool,
+    /// What keeps a repo "active" in the Status column: push (code pushes
+    /// only) or update (any change to the repo, including metadata edits).
+    /// The Last Push column becomes Last Update to match
+    #[arg(long, value_enum, default_value_t)]
+    pub activity_metric: ActivityMetric,
+    /// Leave out the Language and Forks columns, for narrow terminals
+    #[arg(long)]
+    pub compact: bool,
+    /// Show one table per language, org, or status; with --json, an object
+    /// mapping each group to its repos
+    #[arg(long, value_enum)]
+    pub group_by: Option<GroupBy>,
+    /// Browse the repos in a full-screen list: type to filter by name, Tab
+    /// to change the sort, Enter to open one (plain table when not a terminal)
+    #[arg(long, conflicts_with = "group_by")]
+    pub interactive: bool,
+}
+
+/// What `--group-by` splits the listing on.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Language,
+    Org,
+    Status,
+}
+
+impl GroupBy {
+    fn key(self, repo: &RepoSummary) -> String {
+        match self {
+            GroupBy::Language if repo.language == "-" => "(none)".to_string(),
+            GroupBy::Language => repo.language.clone(),
+            GroupBy::Org => repo.org.clone(),
+            GroupBy::Status => repo.status.clone(),
+        }
+    }
+}
+
+/// Splits `repos` into groups by key, keeping their order within each.
+fn group_repos(repos: &[RepoSummary], by: GroupBy) -> BTreeMap<String, Vec<&RepoSummary>> {
+    let mut groups: BTreeMap<String, Vec<&RepoSummary>> = BTreeMap::new();
+    for repo in repos {
+        groups.entry(by.key(repo)).or_default().push(repo);
+    }
+    groups
+}
+
+/// How several `--topic` filters combine.
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TopicMatch {
+    #[default]
+    Any,
+    All,
+}
 
 #[derive(Debug, Serialize)]
 pub struct RepoSummary {
     pub org: String,
     pub name: String,
+    /// The repo's description, "-" when it has none
+    pub description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
     pub language: String,
+    pub topics: Vec<String>,
     pub stars: u32,
     pub forks: u32,
     pub open_issues: u32,
-    pub last_push: String,
+    /// Disk size as GitHub reports it, in KB
+    pub size_kb: u32,
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub created_at: Option<DateTime<Utc>>,
     pub status: String,
+    pub has_discussions: bool,
+    pub has_license: bool,
+    /// Only known when READMEs were fetched (`--readme-badges`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub has_readme: Option<bool>,
+    pub health: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub badges: Option<Vec<Badge>>,
+    /// The repo's page, on whichever host serves it
+    #[serde(skip)]
+    pub html_url: Option<String>,
+    /// Whichever of push or update time `--activity-metric` goes by
+    #[serde(skip)]
+    pub last_activity: Option<DateTime<Utc>>,
+    /// Days since `last_activity` when the repo was listed
+    #[serde(skip)]
+    pub idle_days: Option<i64>,
 }
 
 impl RepoSummary {
-    pub fn from_repo(org: &str, repo: &Repository) -> Self {
+    pub fn from_repo(org: &str, repo: &OrgRepo, metric: ActivityMetric) -> Self {
         let language = repo
             .language
             .as_ref()
@@ -29,136 +180,502 @@ impl RepoSummary {
             .to_string();
 
         let pushed_at = repo.pushed_at;
-        let last_push = pushed_at
-            .map(|dt| dt.format("%Y-%m-%d").to_string())
-            .unwrap_or_else(|| "never".to_string());
-
+        let idle_days = days_since_activity(repo, metric, Utc::now());
         let status = if repo.archived.unwrap_or(false) {
             "archived".to_string()
         } else {
-            let days = pushed_at
-                .map(|dt| (Utc::now() - dt).num_days())
-                .unwrap_or(999);
-            if days > 365 {
-                "stale".to_string()
-            } else {
-                "active".to_string()
+            match idle_days {
+                Some(days) if days <= 365 => "active".to_string(),
+                _ => "stale".to_string(),
             }
         };
 
         Self {
             org: org.to_string(),
             name: repo.name.clone(),
+            description: repo
+                .description
+                .as_deref()
+                .map(str::trim)
+                .filter(|d| !d.is_empty())
+                .unwrap_or("-")
+                .to_string(),
+            homepage: repo.homepage.clone().filter(|h| !h.trim().is_empty()),
             language,
+            topics: repo.topics.clone().unwrap_or_default(),
             stars: repo.stargazers_count.unwrap_or(0),
             forks: repo.forks_count.unwrap_or(0),
             open_issues: repo.open_issues_count.unwrap_or(0),
-            last_push,
+            size_kb: repo.size.unwrap_or(0),
+            pushed_at,
+            created_at: repo.created_at,
             status,
+            has_discussions: repo.has_discussions.unwrap_or(false),
+            has_license: repo.license.is_some(),
+            has_readme: None,
+            health: 0,
+            badges: None,
+            html_url: repo.html_url.as_ref().map(|u| u.to_string()),
+            last_activity: metric.last_activity(repo),
+            idle_days,
+        }
+    }
+
+    fn health_inputs(&self) -> HealthInputs {
+        HealthInputs {
+            pushed_at: self.pushed_at,
+            open_issues: self.open_issues,
+            archived: self.status == "archived",
+            has_license: self.has_license,
+            has_readme: self.has_readme,
         }
     }
 }
 
-pub async fn run(org: &Option<String>, sort: &str, json: bool, verbose: bool) -> Result<()> {
-    let config = load_config()?;
-    let token = config.token()?;
-    let client = GithubClient::new(token, verbose)?;
+pub async fn run(args: &ReposArgs, global: &GlobalArgs) -> Result<()> {
+    let created_after = args
+        .created_after
+        .as_deref()
+        .map(|date| parse_date("created-after", date))
+        .transpose()?;
+    let config = global.load_config()?;
+    let mut client = github_client(&config, global)?;
+    if args.since_last_run {
+        // A cached listing predates `started_at`, so changes made in
+        // between would fall through the gap
+        client = client.with_fresh_listings();
+    }
+
+    let started_at = Utc::now();
+    let since = if args.since_last_run {
+        load_last_run()?.get("repos")
+    } else {
+        None
+    };
 
-    let orgs = resolve_orgs(org, &config, &client).await?;
+    let orgs = resolve_orgs(&args.org, &global.exclude_orgs, &config, &client).await?;
 
-    let mut summaries = Vec::new();
+    // Sorting, limiting, grouping, and README scores all need every repo
+    // first; without them `--ndjson` prints each repo as it's listed
+    let in_listing_order = args.sort.is_none()
+        && config.defaults().repo_sort.is_none()
+        && args.limit.is_none()
+        && args.group_by.is_none()
+        && !args.readme_badges
+        && args.min_health.is_none()
+        && !args.interactive;
+    let mut sink = RecordSink::new(global.json, in_listing_order);
+    let mut failures = OrgFailures::new(orgs.len());
     for org_name in &orgs {
-        match client.list_org_repos(org_name).await {
+        match client.list_org_repos(org_name, args.visibility).await {
             Ok(repos) => {
                 for repo in &repos {
-                    summaries.push(RepoSummary::from_repo(org_name, repo));
+                    // Pushes and metadata edits both count as "updated"
+                    if !changed_since(repo.updated_at.max(repo.pushed_at), since) {
+                        continue;
+                    }
+                    let mut summary = RepoSummary::from_repo(org_name, repo, args.activity_metric);
+                    if !matches_language(&summary.language, &args.languages)
+                        || !matches_topic(&summary.topics, &args.topics, args.topic_match)
+                        || !created_since(summary.created_at, created_after)
+                        || !args.stars.matches(summary.stars)
+                        || !args.archived.matches(repo.archived.unwrap_or(false))
+                        || (args.with_discussions && !summary.has_discussions)
+                    {
+                        continue;
+                    }
+                    if sink.is_streaming() {
+                        score_health(std::slice::from_mut(&mut summary));
+                    }
+                    sink.push(summary);
                 }
             }
             Err(e) => {
-                display::warn(&format!("Failed to fetch repos for {org_name}: {e}"));
+                failures.repos_failed(&args.org, org_name, e)?;
             }
         }
     }
 
-    sort_repos(&mut summaries, sort);
+    let complete = failures.finish()?;
+    let mut summaries = sink.into_records();
+
+    // The health filter needs README presence up front; otherwise only the
+    // repos that survive `--limit` are fetched.
+    let readmes_first = args.readme_badges && args.min_health.is_some();
+    if readmes_first {
+        fetch_badges(&client, &mut summaries).await;
+    }
+    score_health(&mut summaries);
+    if let Some(min_health) = args.min_health {
+        summaries.retain(|r| r.health >= min_health);
+    }
+
+    sort_repos(&mut summaries, resolve_sort(args.sort.as_deref(), &config));
+    let total = apply_limit(&mut summaries, args.limit);
+
+    if args.readme_badges && !readmes_first {
+        fetch_badges(&client, &mut summaries).await;
+        score_health(&mut summaries);
+    }
+
+    let interactive = args.interactive
+        && !global.json
+        && !global.markdown
+        && global.format.is_none()
+        && global.output.is_none()
+        && std::io::stdin().is_terminal()
+        && std::io::stdout().is_terminal();
+
+    match args.group_by {
+        _ if interactive => {
+            let sort = resolve_sort(args.sort.as_deref(), &config);
+            browser::browse(summaries, SORTS, sort_repos, sort)?;
+        }
+        Some(by) if global.json => {
+            display::output(true, &group_repos(&summaries, by), |_| {});
+        }
+        _ => display::output_records(global.json, &summaries, |data| {
+            match args.group_by {
+                Some(by) => render_grouped_repos(&group_repos(data, by), total, args),
+                None => render_repos_table(data, total, args),
+            }
+            if args.readme_badges {
+                render_badges_table(data);
+            }
+        })?,
+    }
 
-    display::output(json, &summaries, |data| {
-        render_repos_table(data);
-    });
+    // Orgs that failed would otherwise be skipped past for good
+    if args.since_last_run && complete {
+        record_last_run("repos", started_at)?;
+    }
 
+    client.warn_if_truncated();
+    client.report_retries(global.json);
     client.check_rate_limit_if_verbose().await;
 
     Ok(())
 }
 
+fn score_health(repos: &mut [RepoSummary]) {
+    let now = Utc::now();
+    for repo in repos {
+        repo.health = health_score(&repo.health_inputs(), now);
+    }
+}
+
+/// Fetches each repo's README (one request per repo, `--max-concurrency`
+/// at a time) and records the shields.io badges it advertises.
+async fn fetch_badges(client: &GithubClient, repos: &mut [RepoSummary]) {
+    let results: Vec<(usize, Result<Option<String>>)> = stream::iter(repos.iter().enumerate())
+        .map(|(i, r)| async move { (i, client.get_readme(&r.org, &r.name).await) })
+        .buffer_unordered(client.max_concurrency())
+        .collect()
+        .await;
+
+    for (i, result) in results {
+        let repo = &mut repos[i];
+        match result {
+            Ok(readme) => {
+                repo.has_readme = Some(readme.is_some());
+                repo.badges = Some(readme.map(|r| extract_badges(&r)).unwrap_or_default());
+            }
+            Err(e) => display::warn(&format!(
+                "Failed to fetch README for {}/{}: {e}",
+                repo.org, repo.name
+            )),
+        }
+    }
+}
+
+/// Case-insensitive match against any of `languages`; an empty set matches all.
+fn matches_language(language: &str, languages: &[String]) -> bool {
+    languages.is_empty() || languages.iter().any(|l| l.eq_ignore_ascii_case(language))
+}
+
+/// Case-insensitive: the repo is tagged with any (or, for
+/// [`TopicMatch::All`], every) topic in `wanted`; an empty set matches all.
+fn matches_topic(topics: &[String], wanted: &[String], mode: TopicMatch) -> bool {
+    let tagged = |w: &String| topics.iter().any(|t| w.eq_ignore_ascii_case(t));
+    wanted.is_empty()
+        || match mode {
+            TopicMatch::Any => wanted.iter().any(tagged),
+            TopicMatch::All => wanted.iter().all(tagged),
+        }
+}
+
+/// Whether a repo was created on or after `date`; with no date, all match.
+fn created_since(created_at: Option<DateTime<Utc>>, date: Option<NaiveDate>) -> bool {
+    date.map_or(true, |date| {
+        created_at.is_some_and(|created| created.date_naive() >= date)
+    })
+}
+
+/// `--sort` wins, then `defaults.repo_sort` from config, then "activity".
+fn resolve_sort<'a>(flag: Option<&'a str>, config: &'a Config) -> &'a str {
+    flag.or(config.defaults().repo_sort.as_deref())
+        .unwrap_or("activity")
+}
+
 fn sort_repos(repos: &mut [RepoSummary], sort: &str) {
     match sort {
-        "stars" => repos.sort_by(|a, b| b.stars.cmp(&a.stars)),
-        "name" => repos.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase())),
-        "staleness" => repos.sort_by(|a, b| a.last_push.cmp(&b.last_push)),
-        _ => repos.sort_by(|a, b| b.last_push.cmp(&a.last_push)), // activity (most recent first)
+        "stars" => repos.sort_by_key(|r| Reverse(r.stars)),
+        "name" => repos.sort_by_key(|r| r.name.to_lowercase()),
+        "size" => repos.sort_by_key(|r| Reverse(r.size_kb)),
+        // Never-pushed repos count as the stalest
+        "staleness" => repos.sort_by_key(|r| r.pushed_at),
+        _ => repos.sort_by_key(|r| Reverse(r.pushed_at)), // activity (most recent first)
+    }
+}
+
+impl Browsable for RepoSummary {
+    fn label(&self) -> String {
+        format!("{}/{}", self.org, self.name)
+    }
+
+    fn details(&self) -> String {
+        format!(
+            "{:>6} stars  {:<12}  {:<8}  {}",
+            self.stars,
+            self.language,
+            self.status,
+            format_last_activity(self.last_activity)
+        )
+    }
+
+    fn url(&self) -> String {
+        self.html_url
+            .clone()
+            .unwrap_or_else(|| format!("https://github.com/{}/{}", self.org, self.name))
     }
 }
 
-fn render_repos_table(repos: &[RepoSummary]) {
+fn format_date(date: Option<DateTime<Utc>>) -> String {
+    date.map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "-".to_string())
+}
+
+fn format_last_activity(at: Option<DateTime<Utc>>) -> String {
+    at.map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "never".to_string())
+}
+
+fn render_repos_table(repos: &[RepoSummary], total: usize, args: &ReposArgs) {
     if repos.is_empty() {
         display::warn("No repositories found.");
         return;
     }
 
     display::section_header("Repositories");
+    display::print_table(&repos_table(repos, args));
+    display::count_footer(repos.len(), total, "repository(ies)");
+}
+
+fn render_grouped_repos(
+    groups: &BTreeMap<String, Vec<&RepoSummary>>,
+    total: usize,
+    args: &ReposArgs,
+) {
+    if groups.is_empty() {
+        display::warn("No repositories found.");
+        return;
+    }
+
+    display::section_header("Repositories");
+    for (key, repos) in groups {
+        display::outln!("\n  {} ({})", display::bold(key), repos.len());
+        display::print_table(&repos_table(repos.iter().copied(), args));
+    }
+    let shown = groups.values().map(Vec::len).sum();
+    display::count_footer(shown, total, "repository(ies)");
+}
 
-    let mut table = display::new_table(&[
-        "Org",
-        "Name",
-        "Language",
-        "Stars",
-        "Forks",
+fn repos_table<'a>(repos: impl IntoIterator<Item = &'a RepoSummary>, args: &ReposArgs) -> Table {
+    let mut headers = vec!["Org", "Name"];
+    if args.show_description {
+        headers.push("Description");
+    }
+    if !args.compact {
+        headers.push("Language");
+    }
+    if args.show_topics {
+        headers.push("Topics");
+    }
+    headers.push("Stars");
+    if !args.compact {
+        headers.push("Forks");
+    }
+    if args.show_size {
+        headers.push("Size");
+    }
+    headers.extend([
         "Issues",
-        "Last Push",
-        "Status",
+        match args.activity_metric {
+            ActivityMetric::Push => "Last Push",
+            ActivityMetric::Update => "Last Update",
+        },
     ]);
+    if args.show_created {
+        headers.push("Created");
+    }
+    headers.extend(["Status", "Health", "Discussions"]);
+    let mut table = display::new_table(&headers);
 
     for r in repos {
+        let mut row = vec![Cell::new(&r.org), Cell::new(&r.name)];
+        if args.show_description {
+            row.push(Cell::new(&r.description));
+        }
+        if !args.compact {
+            row.push(Cell::new(&r.language));
+        }
+        if args.show_topics {
+            row.push(Cell::new(if r.topics.is_empty() {
+                "-".to_string()
+            } else {
+                r.topics.join(", ")
+            }));
+        }
+        row.push(Cell::new(r.stars));
+        if !args.compact {
+            row.push(Cell::new(r.forks));
+        }
+        if args.show_size {
+            row.push(Cell::new(display::human_size(u64::from(r.size_kb))));
+        }
+        row.extend([
+            Cell::new(r.open_issues),
+            display::staleness_cell(&format_last_activity(r.last_activity), r.idle_days),
+        ]);
+        if args.show_created {
+            row.push(Cell::new(format_date(r.created_at)));
+        }
+        row.extend([
+            display::status_cell(&r.status),
+            Cell::new(r.health),
+            Cell::new(if r.has_discussions { "yes" } else { "no" }),
+        ]);
+        table.add_row(row);
+    }
+    if args.show_description {
+        // Wrap long descriptions instead of letting them set the width
+        if let Some(column) = table.column_mut(2) {
+            column.set_constraint(ColumnConstraint::UpperBoundary(Width::Fixed(
+                DESCRIPTION_WIDTH,
+            )));
+        }
+    }
+    table
+}
+
+fn render_badges_table(repos: &[RepoSummary]) {
+    let repos: Vec<&RepoSummary> = repos.iter().filter(|r| r.badges.is_some()).collect();
+    if repos.is_empty() {
+        return;
+    }
+
+    display::section_header("README Badges");
+
+    let mut table = display::new_table(&["Org", "Name", "CI", "Coverage", "Version", "Other"]);
+
+    for r in repos {
+        let badges = r.badges.as_deref().unwrap_or_default();
+        let has = |kind: BadgeKind| {
+            let count = badges.iter().filter(|b| b.kind == kind).count();
+            if count == 0 {
+                "-".to_string()
+            } else {
+                count.to_string()
+            }
+        };
         table.add_row(vec![
-            &r.org,
-            &r.name,
-            &r.language,
-            &r.stars.to_string(),
-            &r.forks.to_string(),
-            &r.open_issues.to_string(),
-            &r.last_push,
-            &r.status,
+            r.org.clone(),
+            r.name.clone(),
+            has(BadgeKind::Ci),
+            has(BadgeKind::Coverage),
+            has(BadgeKind::Version),
+            has(BadgeKind::Other),
         ]);
     }
 
-    println!("{table}");
-    println!("\n{} repository(ies) found.", repos.len());
+    display::print_table(&table);
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    fn make_repo(name: &str, stars: u32, last_push: &str) -> RepoSummary {
+    fn make_repo(name: &str, stars: u32, pushed_at: &str) -> RepoSummary {
         RepoSummary {
             org: "test-org".to_string(),
             name: name.to_string(),
+            description: "-".to_string(),
+            homepage: None,
             language: "Rust".to_string(),
+            topics: Vec::new(),
             stars,
             forks: 0,
             open_issues: 0,
-            last_push: last_push.to_string(),
+            size_kb: 0,
+            pushed_at: Some(pushed_at.parse().unwrap()),
+            created_at: None,
             status: "active".to_string(),
+            has_discussions: false,
+            has_license: false,
+            has_readme: None,
+            health: 0,
+            badges: None,
+            html_url: None,
+            last_activity: Some(pushed_at.parse().unwrap()),
+            idle_days: None,
         }
     }
 
+    #[test]
+    fn missing_description_shows_dash() {
+        let repo: OrgRepo = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "tool",
+            "url": "https://api.github.com/repos/org/tool",
+            "description": "  ",
+            "homepage": "https://tool.example.com"
+        }))
+        .unwrap();
+        let summary = RepoSummary::from_repo("org", &repo, ActivityMetric::Push);
+        assert_eq!(summary.description, "-");
+        assert_eq!(
+            summary.homepage.as_deref(),
+            Some("https://tool.example.com")
+        );
+    }
+
+    #[test]
+    fn update_metric_keeps_recently_edited_repos_active() {
+        let repo: OrgRepo = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "triaged",
+            "url": "https://api.github.com/repos/org/triaged",
+            "pushed_at": "2015-01-01T00:00:00Z",
+            "updated_at": Utc::now().to_rfc3339(),
+        }))
+        .unwrap();
+        assert_eq!(
+            RepoSummary::from_repo("org", &repo, ActivityMetric::Push).status,
+            "stale"
+        );
+        let updated = RepoSummary::from_repo("org", &repo, ActivityMetric::Update);
+        assert_eq!(updated.status, "active");
+        // The Last Update column is dated and colored by the same timestamp
+        assert_eq!(updated.last_activity, repo.updated_at);
+        assert_eq!(updated.idle_days, Some(0));
+    }
+
     #[test]
     fn sort_by_stars_descending() {
         let mut repos = vec![
-            make_repo("low", 5, "2024-01-01"),
-            make_repo("high", 100, "2024-01-01"),
-            make_repo("mid", 50, "2024-01-01"),
+            make_repo("low", 5, "2024-01-01T00:00:00Z"),
+            make_repo("high", 100, "2024-01-01T00:00:00Z"),
+            make_repo("mid", 50, "2024-01-01T00:00:00Z"),
         ];
         sort_repos(&mut repos, "stars");
         assert_eq!(repos[0].name, "high");
@@ -169,9 +686,9 @@ mod tests {
     #[test]
     fn sort_by_name_case_insensitive() {
         let mut repos = vec![
-            make_repo("Zebra", 0, "2024-01-01"),
-            make_repo("alpha", 0, "2024-01-01"),
-            make_repo("Beta", 0, "2024-01-01"),
+            make_repo("Zebra", 0, "2024-01-01T00:00:00Z"),
+            make_repo("alpha", 0, "2024-01-01T00:00:00Z"),
+            make_repo("Beta", 0, "2024-01-01T00:00:00Z"),
         ];
         sort_repos(&mut repos, "name");
         assert_eq!(repos[0].name, "alpha");
@@ -179,12 +696,70 @@ mod tests {
         assert_eq!(repos[2].name, "Zebra");
     }
 
+    #[test]
+    fn grouping_keeps_sort_order_within_groups() {
+        let with = |name: &str, language: &str, status: &str| RepoSummary {
+            language: language.to_string(),
+            status: status.to_string(),
+            ..make_repo(name, 0, "2024-01-01T00:00:00Z")
+        };
+        let repos = vec![
+            with("b", "Rust", "active"),
+            with("a", "-", "stale"),
+            with("c", "Rust", "stale"),
+        ];
+
+        let by_language = group_repos(&repos, GroupBy::Language);
+        let keys: Vec<&str> = by_language.keys().map(String::as_str).collect();
+        assert_eq!(keys, vec!["(none)", "Rust"]);
+        let rust: Vec<&str> = by_language["Rust"]
+            .iter()
+            .map(|r| r.name.as_str())
+            .collect();
+        assert_eq!(rust, vec!["b", "c"]);
+
+        let by_status = group_repos(&repos, GroupBy::Status);
+        assert_eq!(by_status["stale"].len(), 2);
+
+        let json = serde_json::to_value(&by_status).unwrap();
+        assert_eq!(json["active"][0]["name"], "b");
+    }
+
+    #[test]
+    fn browser_opens_the_repos_own_page() {
+        let enterprise = RepoSummary {
+            html_url: Some("https://ghe.example.com/test-org/tool".to_string()),
+            ..make_repo("tool", 0, "2024-01-01T00:00:00Z")
+        };
+        assert_eq!(enterprise.url(), "https://ghe.example.com/test-org/tool");
+        assert!(serde_json::to_value(&enterprise)
+            .unwrap()
+            .get("html_url")
+            .is_none());
+    }
+
+    #[test]
+    fn sort_by_size_largest_first() {
+        let sized = |name: &str, size_kb: u32| RepoSummary {
+            size_kb,
+            ..make_repo(name, 0, "2024-01-01T00:00:00Z")
+        };
+        let mut repos = vec![
+            sized("small", 12),
+            sized("huge", 2_000_000),
+            sized("mid", 900),
+        ];
+        sort_repos(&mut repos, "size");
+        let names: Vec<&str> = repos.iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, vec!["huge", "mid", "small"]);
+    }
+
     #[test]
     fn sort_by_activity_most_recent_first() {
         let mut repos = vec![
-            make_repo("old", 0, "2023-01-01"),
-            make_repo("new", 0, "2024-06-01"),
-            make_repo("mid", 0, "2024-01-01"),
+            make_repo("old", 0, "2023-01-01T00:00:00Z"),
+            make_repo("new", 0, "2024-06-01T00:00:00Z"),
+            make_repo("mid", 0, "2024-01-01T00:00:00Z"),
         ];
         sort_repos(&mut repos, "activity");
         assert_eq!(repos[0].name, "new");
@@ -195,13 +770,105 @@ mod tests {
     #[test]
     fn sort_by_staleness_oldest_first() {
         let mut repos = vec![
-            make_repo("new", 0, "2024-06-01"),
-            make_repo("old", 0, "2023-01-01"),
-            make_repo("mid", 0, "2024-01-01"),
+            make_repo("new", 0, "2024-06-01T00:00:00Z"),
+            make_repo("old", 0, "2023-01-01T00:00:00Z"),
+            make_repo("mid", 0, "2024-01-01T00:00:00Z"),
         ];
         sort_repos(&mut repos, "staleness");
         assert_eq!(repos[0].name, "old");
         assert_eq!(repos[1].name, "mid");
         assert_eq!(repos[2].name, "new");
     }
+
+    #[test]
+    fn sort_falls_back_to_config_then_activity() {
+        let mut config = Config::default();
+        assert_eq!(resolve_sort(None, &config), "activity");
+
+        config.defaults.repo_sort = Some("stars".to_string());
+        assert_eq!(resolve_sort(None, &config), "stars");
+        assert_eq!(resolve_sort(Some("name"), &config), "name");
+    }
+
+    #[test]
+    fn created_after_is_inclusive_and_needs_a_date() {
+        let created = Some("2024-03-01T12:00:00Z".parse().unwrap());
+        let on = NaiveDate::from_ymd_opt(2024, 3, 1);
+        let later = NaiveDate::from_ymd_opt(2024, 3, 2);
+
+        assert!(created_since(created, None));
+        assert!(created_since(created, on));
+        assert!(!created_since(created, later));
+        assert!(!created_since(None, on));
+        assert!(created_since(None, None));
+    }
+
+    #[test]
+    fn topic_filter_matches_any_wanted_topic() {
+        let topics = vec!["cli".to_string(), "github".to_string()];
+        let any = TopicMatch::Any;
+        assert!(matches_topic(&topics, &[], any));
+        assert!(matches_topic(&topics, &["GitHub".to_string()], any));
+        assert!(matches_topic(
+            &topics,
+            &["web".to_string(), "cli".to_string()],
+            any
+        ));
+        assert!(!matches_topic(&topics, &["web".to_string()], any));
+        assert!(!matches_topic(&[], &["cli".to_string()], any));
+    }
+
+    #[test]
+    fn topic_filter_all_needs_every_topic() {
+        let topics = vec!["backend".to_string(), "critical".to_string()];
+        let all = TopicMatch::All;
+        assert!(matches_topic(&topics, &[], all));
+        assert!(matches_topic(
+            &topics,
+            &["Backend".to_string(), "critical".to_string()],
+            all
+        ));
+        assert!(!matches_topic(
+            &topics,
+            &["backend".to_string(), "web".to_string()],
+            all
+        ));
+    }
+
+    #[test]
+    fn language_filter_is_case_insensitive_or_set() {
+        let langs = vec!["rust".to_string(), "Go".to_string()];
+        assert!(matches_language("Rust", &langs));
+        assert!(matches_language("go", &langs));
+        assert!(!matches_language("Python", &langs));
+        assert!(!matches_language("-", &langs));
+        assert!(matches_language("Python", &[]));
+    }
+
+    #[test]
+    fn sort_by_activity_orders_same_day_pushes() {
+        let mut repos = vec![
+            make_repo("morning", 0, "2024-06-01T08:00:00Z"),
+            make_repo("evening", 0, "2024-06-01T20:30:00Z"),
+            make_repo("noon", 0, "2024-06-01T12:00:00Z"),
+        ];
+        sort_repos(&mut repos, "activity");
+        assert_eq!(repos[0].name, "evening");
+        assert_eq!(repos[1].name, "noon");
+        assert_eq!(repos[2].name, "morning");
+    }
+
+    #[test]
+    fn never_pushed_repos_sort_as_stalest() {
+        let mut repos = vec![
+            make_repo("pushed", 0, "2024-06-01T00:00:00Z"),
+            make_repo("empty", 0, "2024-06-01T00:00:00Z"),
+        ];
+        repos[1].pushed_at = None;
+
+        sort_repos(&mut repos, "activity");
+        assert_eq!(repos[1].name, "empty");
+        sort_repos(&mut repos, "staleness");
+        assert_eq!(repos[0].name, "empty");
+    }
 }
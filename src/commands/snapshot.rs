@@ -0,0 +1,189 @@
+use crate::commands::repos::RepoSummary;
+use crate::commands::{github_client, resolve_orgs, ActivityMetric, GlobalArgs, OrgFailures};
+use crate::display;
+use crate::error::{GitorgError, Result};
+use crate::github::{OrgRepo, RepoVisibility};
+use crate::snapshot::{
+    diff_snapshots, list_snapshots, load_snapshot, resolve_snapshot, save_snapshot, Snapshot,
+    SnapshotDiff, SnapshotRepo,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::path::Path;
+
+#[derive(Debug, Serialize)]
+pub struct SnapshotInfo {
+    pub name: String,
+    pub taken_at: DateTime<Utc>,
+    pub repos: usize,
+}
+
+pub async fn save(org: &Option<String>, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+    let orgs = resolve_orgs(org, &global.exclude_orgs, &config, &client).await?;
+
+    let mut repos = Vec::new();
+    let mut failures = OrgFailures::new(orgs.len());
+    for org_name in &orgs {
+        match client.list_org_repos(org_name, RepoVisibility::All).await {
+            Ok(org_repos) => {
+                repos.extend(org_repos.iter().map(|repo| snapshot_repo(org_name, repo)));
+            }
+            Err(e) => {
+                failures.repos_failed(org, org_name, e)?;
+            }
+        }
+    }
+    failures.finish()?;
+
+    let snapshot = Snapshot {
+        taken_at: Utc::now(),
+        repos,
+    };
+    let path = save_snapshot(&snapshot)?;
+    display::success(&format!(
+        "Saved {} repo(s) to {}",
+        snapshot.repos.len(),
+        path.display()
+    ));
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// A repo as snapshots record it.
+pub fn snapshot_repo(org: &str, repo: &OrgRepo) -> SnapshotRepo {
+    let summary = RepoSummary::from_repo(org, repo, ActivityMetric::Push);
+    SnapshotRepo {
+        org: summary.org,
+        name: summary.name,
+        stars: summary.stars,
+        forks: Some(summary.forks),
+        archived: repo.archived.unwrap_or(false),
+        status: summary.status,
+    }
+}
+
+pub fn list(global: &GlobalArgs) -> Result<()> {
+    let mut infos = Vec::new();
+    for path in list_snapshots()? {
+        let snapshot = load_snapshot(&path)?;
+        infos.push(SnapshotInfo {
+            name: snapshot_name(&path),
+            taken_at: snapshot.taken_at,
+            repos: snapshot.repos.len(),
+        });
+    }
+
+    display::output_records(global.json, &infos, |data| {
+        render_snapshot_list(data);
+    })?;
+
+    Ok(())
+}
+
+/// Compares `from` with `to`. Either may be a snapshot name or a file; by
+/// default the two newest snapshots are compared.
+pub fn diff(from: &Option<String>, to: &Option<String>, global: &GlobalArgs) -> Result<()> {
+    let saved = list_snapshots()?;
+    let newest = |back: usize| {
+        saved
+            .len()
+            .checked_sub(back + 1)
+            .map(|i| saved[i].clone())
+            .ok_or_else(|| {
+                GitorgError::Config(
+                    "Not enough snapshots to compare. Save one with `gitorg snapshot save`."
+                        .to_string(),
+                )
+            })
+    };
+
+    let (from, to) = match (from, to) {
+        (Some(from), Some(to)) => (resolve_snapshot(from)?, resolve_snapshot(to)?),
+        (Some(from), None) => (resolve_snapshot(from)?, newest(0)?),
+        (None, _) => (newest(1)?, newest(0)?),
+    };
+    let diff = diff_snapshots(&load_snapshot(&from)?, &load_snapshot(&to)?);
+
+    display::output(global.json, &diff, |data| {
+        render_diff(data);
+    });
+
+    Ok(())
+}
+
+fn snapshot_name(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn render_snapshot_list(infos: &[SnapshotInfo]) {
+    if infos.is_empty() {
+        display::outln!("No snapshots saved yet. Run `gitorg snapshot save`.");
+        return;
+    }
+
+    display::section_header("Snapshots");
+
+    let mut table = display::new_table(&["Name", "Taken", "Repos"]);
+    for info in infos {
+        table.add_row(vec![
+            info.name.clone(),
+            info.taken_at.format("%Y-%m-%d %H:%M UTC").to_string(),
+            info.repos.to_string(),
+        ]);
+    }
+
+    display::print_table(&table);
+}
+
+fn render_diff(diff: &SnapshotDiff) {
+    let span = format!(
+        "{} to {}",
+        diff.from.format("%Y-%m-%d %H:%M"),
+        diff.to.format("%Y-%m-%d %H:%M")
+    );
+    if diff.is_empty() {
+        display::success(&format!("No changes from {span}"));
+        return;
+    }
+
+    display::section_header(&format!("Changes from {span}"));
+
+    let sections = [
+        ("New", &diff.added),
+        ("Removed", &diff.removed),
+        ("Archived", &diff.archived),
+        ("Unarchived", &diff.unarchived),
+        ("Went stale", &diff.went_stale),
+    ];
+    for (label, repos) in sections {
+        if repos.is_empty() {
+            continue;
+        }
+        display::outln!("\n  {} ({})", display::bold(label), repos.len());
+        for repo in repos {
+            display::outln!("    {repo}");
+        }
+    }
+
+    if !diff.star_changes.is_empty() {
+        display::outln!("\n  {}", display::bold("Stars"));
+        let mut table = display::new_table(&["Repo", "Before", "After", "Change"]);
+        for change in &diff.star_changes {
+            table.add_row(vec![
+                change.repo.clone(),
+                change.before.to_string(),
+                change.after.to_string(),
+                format!("{:+}", change.delta),
+            ]);
+        }
+        display::print_table(&table);
+    }
+}
@@ -1,46 +1,165 @@
-use crate::commands::resolve_orgs;
-use crate::config::load_config;
+use crate::commands::{
+    apply_limit, days_since_activity, github_client, parse_date, resolve_orgs, stale_days,
+    ActivityMetric, GlobalArgs, OrgFailures,
+};
+use crate::config::Config;
 use crate::display;
 use crate::error::Result;
-use crate::github::GithubClient;
-use chrono::Utc;
+use crate::github::{GithubClient, RepoVisibility};
+use crate::webhook::{self, slack_message, WebhookFormat};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Args;
+use comfy_table::Cell;
 use serde::Serialize;
+use std::cmp::Reverse;
+use std::fmt;
+
+#[derive(Args, Debug)]
+pub struct StaleArgs {
+    /// Filter to specific organizations (comma-separated)
+    #[arg(long)]
+    pub org: Option<String>,
+    /// Number of days without a push to consider stale [default: 90, or defaults.stale_days]
+    #[arg(long)]
+    pub days: Option<u64>,
+    /// Treat repos not pushed since this date (YYYY-MM-DD) as stale; overrides --days
+    #[arg(long)]
+    pub since: Option<String>,
+    /// Sort by: days (most stale first), stars (fewest first), name
+    #[arg(long, default_value = "days")]
+    pub sort: String,
+    /// Show at most this many repos (after sorting)
+    #[arg(long)]
+    pub limit: Option<usize>,
+    /// Which repos to include: all, public, or private
+    #[arg(long, value_enum, default_value_t)]
+    pub visibility: RepoVisibility,
+    /// What counts as activity: push (code pushes only) or update (any change
+    /// to the repo, so settings or metadata edits keep it alive too)
+    #[arg(long, value_enum, default_value_t)]
+    pub activity_metric: ActivityMetric,
+    /// Exit with status 2 if any stale repos are found (for CI gating)
+    #[arg(long)]
+    pub fail_on_match: bool,
+    /// POST the stale list as JSON to this URL once the report is printed
+    #[arg(long)]
+    pub webhook: Option<String>,
+    /// Payload for --webhook: generic (the --json records) or slack
+    #[arg(long, value_enum, default_value_t, requires = "webhook")]
+    pub webhook_format: WebhookFormat,
+}
+
+/// What made a repo count as stale: a rolling `--days` window or a fixed
+/// `--since` date. Serialized as `{"days": 90}` or `{"since": "2023-01-01"}`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StaleCutoff {
+    Days(u64),
+    Since(NaiveDate),
+}
+
+impl StaleCutoff {
+    pub fn from_args(args: &StaleArgs, config: &Config) -> Result<Self> {
+        match &args.since {
+            Some(since) => parse_date("since", since).map(StaleCutoff::Since),
+            None => Ok(StaleCutoff::Days(stale_days(args.days, config))),
+        }
+    }
+
+    fn is_stale(&self, pushed_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        let Some(pushed_at) = pushed_at else {
+            return true;
+        };
+        match self {
+            StaleCutoff::Days(days) => (now - pushed_at).num_days() >= *days as i64,
+            StaleCutoff::Since(date) => pushed_at.date_naive() < *date,
+        }
+    }
+}
+
+impl fmt::Display for StaleCutoff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StaleCutoff::Days(days) => write!(f, "for more than {days} days"),
+            StaleCutoff::Since(date) => write!(f, "since {date}"),
+        }
+    }
+}
 
 #[derive(Debug, Serialize)]
 pub struct StaleRepo {
     pub org: String,
     pub name: String,
     pub last_push: String,
-    pub days_stale: i64,
+    /// `None` when the repo has never been pushed to (or updated, with
+    /// `--activity-metric update`)
+    pub days_stale: Option<i64>,
     pub stars: u32,
     pub language: String,
+    pub cutoff: StaleCutoff,
 }
 
-pub async fn run(org: &Option<String>, days: u64, json: bool, verbose: bool) -> Result<()> {
-    let config = load_config()?;
-    let token = config.token()?;
-    let client = GithubClient::new(token, verbose)?;
+/// Returns how many stale repos were found (before `--limit`).
+pub async fn run(args: &StaleArgs, global: &GlobalArgs) -> Result<usize> {
+    let config = global.load_config()?;
+    let cutoff = StaleCutoff::from_args(args, &config)?;
+    let client = github_client(&config, global)?;
 
-    let orgs = resolve_orgs(org, &config, &client).await?;
+    let mut stale_repos = collect(args, cutoff, global, &config, &client).await?;
+    let total = apply_limit(&mut stale_repos, args.limit);
+
+    display::output_records(global.json, &stale_repos, |data| {
+        render_stale_repos(data, cutoff, args.activity_metric, total);
+    })?;
+
+    // The report is already out, so a failed delivery only warns
+    if let Some(url) = &args.webhook {
+        let timeout = global.timeout(&config);
+        let proxy = global.proxy(&config)?;
+        let sent = match args.webhook_format {
+            WebhookFormat::Generic => webhook::post_json(url, &stale_repos, timeout, proxy).await,
+            WebhookFormat::Slack => {
+                let text = slack_text(&stale_repos, cutoff, total);
+                webhook::post_json(url, &slack_message(&text), timeout, proxy).await
+            }
+        };
+        if let Err(e) = sent {
+            display::warn(&e.to_string());
+        }
+    }
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(total)
+}
+
+/// The unarchived repos across the orgs stale by `cutoff`, in `--sort`
+/// order.
+pub async fn collect(
+    args: &StaleArgs,
+    cutoff: StaleCutoff,
+    global: &GlobalArgs,
+    config: &Config,
+    client: &GithubClient,
+) -> Result<Vec<StaleRepo>> {
+    let orgs = resolve_orgs(&args.org, &global.exclude_orgs, config, client).await?;
     let now = Utc::now();
-    let threshold = days as i64;
 
     let mut stale_repos = Vec::new();
 
+    let mut failures = OrgFailures::new(orgs.len());
     for org_name in &orgs {
-        match client.list_org_repos(org_name).await {
+        match client.list_org_repos(org_name, args.visibility).await {
             Ok(repos) => {
                 for repo in &repos {
                     if repo.archived.unwrap_or(false) {
                         continue;
                     }
 
-                    let days_since = repo
-                        .pushed_at
-                        .map(|dt| (now - dt).num_days())
-                        .unwrap_or(99999);
-
-                    if days_since >= threshold {
+                    let last_activity = args.activity_metric.last_activity(repo);
+                    if cutoff.is_stale(last_activity, now) {
                         let language = repo
                             .language
                             .as_ref()
@@ -51,46 +170,84 @@ pub async fn run(org: &Option<String>, days: u64, json: bool, verbose: bool) ->
                         stale_repos.push(StaleRepo {
                             org: org_name.clone(),
                             name: repo.name.clone(),
-                            last_push: repo
-                                .pushed_at
+                            last_push: last_activity
                                 .map(|dt| dt.format("%Y-%m-%d").to_string())
                                 .unwrap_or_else(|| "never".to_string()),
-                            days_stale: days_since,
+                            days_stale: days_since_activity(repo, args.activity_metric, now),
                             stars: repo.stargazers_count.unwrap_or(0),
                             language,
+                            cutoff,
                         });
                     }
                 }
             }
             Err(e) => {
-                display::warn(&format!("Failed to fetch repos for {org_name}: {e}"));
+                failures.repos_failed(&args.org, org_name, e)?;
             }
         }
     }
 
-    stale_repos.sort_by(|a, b| b.days_stale.cmp(&a.days_stale));
+    failures.finish()?;
 
-    display::output(json, &stale_repos, |data| {
-        render_stale_repos(data, days);
-    });
+    sort_stale(&mut stale_repos, &args.sort);
+    Ok(stale_repos)
+}
 
-    client.check_rate_limit_if_verbose().await;
+fn sort_stale(repos: &mut [StaleRepo], sort: &str) {
+    // Never-pushed repos are the stalest of all
+    let staleness = |r: &StaleRepo| Reverse(r.days_stale.unwrap_or(i64::MAX));
+    match sort {
+        "stars" => repos.sort_by_key(|r| (r.stars, staleness(r))),
+        "name" => repos.sort_by_key(|r| r.name.to_lowercase()),
+        _ => repos.sort_by_key(staleness), // days
+    }
+}
 
-    Ok(())
+/// A plain-text summary of the stale list for chat webhooks.
+fn slack_text(repos: &[StaleRepo], cutoff: StaleCutoff, total: usize) -> String {
+    if repos.is_empty() {
+        return format!("No repositories stale {cutoff}.");
+    }
+    let mut text = format!("{total} stale repository(ies) {cutoff}:");
+    for r in repos {
+        text.push_str(&format!(
+            "\n• {}/{} (last activity {})",
+            r.org, r.name, r.last_push
+        ));
+    }
+    if total > repos.len() {
+        text.push_str(&format!("\n…and {} more", total - repos.len()));
+    }
+    text
 }
 
-fn render_stale_repos(repos: &[StaleRepo], days: u64) {
+pub fn render_stale_repos(
+    repos: &[StaleRepo],
+    cutoff: StaleCutoff,
+    metric: ActivityMetric,
+    total: usize,
+) {
     if repos.is_empty() {
-        display::success(&format!("No repositories stale for more than {days} days."));
+        display::success(&format!("No repositories stale {cutoff}."));
         return;
     }
 
-    display::section_header(&format!("Stale Repositories (>{days} days)"));
+    let header = match cutoff {
+        StaleCutoff::Days(days) => format!("Stale Repositories (>{days} days)"),
+        StaleCutoff::Since(date) => {
+            format!("Stale Repositories (no {} since {date})", metric.noun())
+        }
+    };
+    let last_column = match metric {
+        ActivityMetric::Push => "Last Push",
+        ActivityMetric::Update => "Last Update",
+    };
+    display::section_header(&header);
 
     let mut table = display::new_table(&[
         "Org",
         "Name",
-        "Last Push",
+        last_column,
         "Days Stale",
         "Stars",
         "Language",
@@ -98,77 +255,215 @@ fn render_stale_repos(repos: &[StaleRepo], days: u64) {
 
     for r in repos {
         table.add_row(vec![
-            &r.org,
-            &r.name,
-            &r.last_push,
-            &r.days_stale.to_string(),
-            &r.stars.to_string(),
-            &r.language,
+            Cell::new(&r.org),
+            Cell::new(&r.name),
+            display::staleness_cell(&r.last_push, r.days_stale),
+            Cell::new(r.days_stale.map_or("-".to_string(), |d| d.to_string())),
+            Cell::new(r.stars),
+            Cell::new(&r.language),
         ]);
     }
 
-    println!("{table}");
-    println!("\n{} stale repository(ies) found.", repos.len());
+    display::print_table(&table);
+    display::count_footer(repos.len(), total, "stale repository(ies)");
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::GitorgError;
 
     #[test]
     fn stale_filtering_by_threshold() {
-        let repos = vec![
+        let repos = [
             StaleRepo {
                 org: "org".into(),
                 name: "very-stale".into(),
                 last_push: "2020-01-01".into(),
-                days_stale: 1500,
+                days_stale: Some(1500),
                 stars: 0,
                 language: "Rust".into(),
+                cutoff: StaleCutoff::Days(90),
             },
             StaleRepo {
                 org: "org".into(),
                 name: "barely-stale".into(),
                 last_push: "2024-01-01".into(),
-                days_stale: 100,
+                days_stale: Some(100),
                 stars: 5,
                 language: "Go".into(),
+                cutoff: StaleCutoff::Days(90),
             },
         ];
 
         // Both are stale at threshold 90
-        let filtered: Vec<&StaleRepo> = repos.iter().filter(|r| r.days_stale >= 90).collect();
+        let filtered: Vec<&StaleRepo> = repos.iter().filter(|r| r.days_stale >= Some(90)).collect();
         assert_eq!(filtered.len(), 2);
 
         // Only one at threshold 200
-        let filtered: Vec<&StaleRepo> = repos.iter().filter(|r| r.days_stale >= 200).collect();
+        let filtered: Vec<&StaleRepo> =
+            repos.iter().filter(|r| r.days_stale >= Some(200)).collect();
         assert_eq!(filtered.len(), 1);
         assert_eq!(filtered[0].name, "very-stale");
     }
 
+    fn stale_args(days: Option<u64>, since: Option<&str>) -> StaleArgs {
+        StaleArgs {
+            org: None,
+            days,
+            since: since.map(str::to_string),
+            sort: "days".to_string(),
+            limit: None,
+            visibility: RepoVisibility::All,
+            activity_metric: ActivityMetric::Push,
+            fail_on_match: false,
+            webhook: None,
+            webhook_format: WebhookFormat::Generic,
+        }
+    }
+
+    #[test]
+    fn since_overrides_days() {
+        let cutoff = StaleCutoff::from_args(
+            &stale_args(Some(30), Some("2023-01-01")),
+            &Config::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            cutoff,
+            StaleCutoff::Since(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap())
+        );
+        assert_eq!(
+            StaleCutoff::from_args(&stale_args(Some(30), None), &Config::default()).unwrap(),
+            StaleCutoff::Days(30)
+        );
+    }
+
+    #[test]
+    fn configured_days_apply_without_the_flag() {
+        let mut config = Config::default();
+        config.defaults.stale_days = Some(180);
+        assert_eq!(
+            StaleCutoff::from_args(&stale_args(None, None), &config).unwrap(),
+            StaleCutoff::Days(180)
+        );
+    }
+
+    #[test]
+    fn malformed_since_is_a_config_error() {
+        let err = StaleCutoff::from_args(&stale_args(None, Some("01/02/2023")), &Config::default())
+            .unwrap_err();
+        assert!(matches!(err, GitorgError::Config(msg) if msg.contains("YYYY-MM-DD")));
+    }
+
+    #[test]
+    fn since_cutoff_compares_push_date() {
+        let now = Utc::now();
+        let cutoff = StaleCutoff::Since(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+        assert!(cutoff.is_stale(Some("2022-12-31T23:00:00Z".parse().unwrap()), now));
+        assert!(!cutoff.is_stale(Some("2023-01-01T00:00:00Z".parse().unwrap()), now));
+        assert!(cutoff.is_stale(None, now));
+    }
+
+    #[test]
+    fn cutoff_serializes_with_mode() {
+        let json = serde_json::to_string(&StaleCutoff::Days(90)).unwrap();
+        assert_eq!(json, r#"{"days":90}"#);
+        let since = StaleCutoff::Since(NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
+        assert_eq!(
+            serde_json::to_string(&since).unwrap(),
+            r#"{"since":"2023-01-01"}"#
+        );
+    }
+
     #[test]
     fn stale_sorting_most_stale_first() {
-        let mut repos = vec![
+        let mut repos = [
             StaleRepo {
                 org: "org".into(),
                 name: "less-stale".into(),
                 last_push: "2024-01-01".into(),
-                days_stale: 100,
+                days_stale: Some(100),
                 stars: 0,
                 language: "-".into(),
+                cutoff: StaleCutoff::Days(90),
             },
             StaleRepo {
                 org: "org".into(),
                 name: "more-stale".into(),
                 last_push: "2020-01-01".into(),
-                days_stale: 1500,
+                days_stale: Some(1500),
                 stars: 0,
                 language: "-".into(),
+                cutoff: StaleCutoff::Days(90),
             },
         ];
 
-        repos.sort_by(|a, b| b.days_stale.cmp(&a.days_stale));
+        sort_stale(&mut repos, "days");
         assert_eq!(repos[0].name, "more-stale");
         assert_eq!(repos[1].name, "less-stale");
     }
+
+    fn make_stale(name: &str, days_stale: Option<i64>, stars: u32) -> StaleRepo {
+        StaleRepo {
+            org: "org".into(),
+            name: name.into(),
+            last_push: "-".into(),
+            days_stale,
+            stars,
+            language: "-".into(),
+            cutoff: StaleCutoff::Days(90),
+        }
+    }
+
+    fn names(repos: &[StaleRepo]) -> Vec<&str> {
+        repos.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[test]
+    fn never_pushed_sorts_as_most_stale() {
+        let mut repos = [
+            make_stale("old", Some(800), 0),
+            make_stale("never", None, 0),
+        ];
+        sort_stale(&mut repos, "days");
+        assert_eq!(names(&repos), vec!["never", "old"]);
+    }
+
+    #[test]
+    fn sort_by_stars_puts_fewest_first_then_stalest() {
+        let mut repos = [
+            make_stale("popular", Some(500), 90),
+            make_stale("newer", Some(100), 0),
+            make_stale("older", Some(400), 0),
+        ];
+        sort_stale(&mut repos, "stars");
+        assert_eq!(names(&repos), vec!["older", "newer", "popular"]);
+    }
+
+    #[test]
+    fn slack_text_lists_repos_and_what_was_cut() {
+        let mut repos = vec![make_stale("old", Some(800), 0)];
+        repos[0].last_push = "2022-01-01".into();
+        assert_eq!(
+            slack_text(&repos, StaleCutoff::Days(90), 3),
+            "3 stale repository(ies) for more than 90 days:\n\
+             • org/old (last activity 2022-01-01)\n\
+             …and 2 more"
+        );
+        assert_eq!(
+            slack_text(&[], StaleCutoff::Days(90), 0),
+            "No repositories stale for more than 90 days."
+        );
+    }
+
+    #[test]
+    fn sort_by_name_case_insensitive() {
+        let mut repos = [
+            make_stale("beta", Some(100), 0),
+            make_stale("Alpha", Some(100), 0),
+        ];
+        sort_stale(&mut repos, "name");
+        assert_eq!(names(&repos), vec!["Alpha", "beta"]);
+    }
 }
@@ -1,21 +1,124 @@
-use crate::commands::resolve_orgs;
-use crate::config::load_config;
+use crate::commands::snapshot::snapshot_repo;
+use crate::commands::{
+    github_client, open_pr_counts, resolve_orgs, ArchivedFilter, GlobalArgs, OrgFailures,
+    SeenRepos, StarFilter,
+};
+use crate::config::Config;
 use crate::display;
 use crate::error::Result;
-use crate::github::GithubClient;
-use owo_colors::OwoColorize;
+use crate::github::{GithubClient, OrgRepo, RepoVisibility};
+use crate::snapshot::{
+    compare_to_snapshot, load_snapshot, repo_key, resolve_snapshot, Snapshot, SnapshotComparison,
+    SnapshotRepo,
+};
+use clap::Args;
+use futures::stream::{self, StreamExt};
 use serde::Serialize;
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Repos listed under "Star Changes" with `--compare-to`.
+const TOP_CHANGES: usize = 10;
+
+#[derive(Args, Debug, Default)]
+pub struct StatsArgs {
+    /// Filter to specific organizations (comma-separated)
+    #[arg(long)]
+    pub org: Option<String>,
+    /// Break down why repos have an unknown language (empty, fork, archived)
+    #[arg(long)]
+    pub explain_unknown: bool,
+    #[command(flatten)]
+    pub stars: StarFilter,
+    #[command(flatten)]
+    pub archived: ArchivedFilter,
+    /// Which repos to include: all, public, or private
+    #[arg(long, value_enum, default_value_t)]
+    pub visibility: RepoVisibility,
+    /// Report each organization separately instead of one combined total
+    #[arg(long)]
+    pub by_org: bool,
+    /// Count open PRs separately from issues (one extra request per repo)
+    #[arg(long)]
+    pub accurate_issue_counts: bool,
+    /// Weigh languages by bytes of code instead of each repo's main language
+    /// (one extra request per repo)
+    #[arg(long)]
+    pub detailed_languages: bool,
+    /// Name orgs that have no repos at all, to tell them from failed ones
+    /// (also shown with --verbose)
+    #[arg(long)]
+    pub include_empty_orgs: bool,
+    /// Show what changed since a saved snapshot (a name from `snapshot
+    /// list`, or a file): deltas next to the totals and per-repo star changes
+    #[arg(long, value_name = "SNAPSHOT")]
+    pub compare_to: Option<String>,
+}
 
 #[derive(Debug, Serialize)]
 pub struct OrgStats {
     pub total_repos: usize,
     pub total_stars: u32,
     pub total_forks: u32,
+    /// Open issues, excluding PRs only when `total_open_prs` is present.
     pub total_open_issues: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_open_prs: Option<u32>,
+    pub avg_stars_per_repo: f64,
+    pub avg_issues_per_repo: f64,
+    pub archived_count: usize,
+    pub with_discussions: usize,
+    /// Sum of GitHub's reported repo sizes, in KB
+    pub total_size_kb: u64,
     pub languages: Vec<LanguageCount>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language_bytes: Option<Vec<LanguageBytes>>,
     pub most_starred: Option<RepoRef>,
     pub most_forked: Option<RepoRef>,
+    /// `count` is the repo's size in KB
+    pub largest_repo: Option<RepoRef>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unknown_language: Option<UnknownLanguageBreakdown>,
+    /// Changes since the `--compare-to` snapshot
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_snapshot: Option<SnapshotComparison>,
+}
+
+/// Why repos land in the "Unknown" language bucket. A repo can count
+/// towards several reasons; `unexplained` holds those matching none.
+#[derive(Debug, Serialize, Default)]
+pub struct UnknownLanguageBreakdown {
+    pub total: usize,
+    pub empty: usize,
+    pub fork: usize,
+    pub archived: usize,
+    pub unexplained: usize,
+}
+
+impl UnknownLanguageBreakdown {
+    fn record(&mut self, empty: bool, fork: bool, archived: bool) {
+        self.total += 1;
+        if empty {
+            self.empty += 1;
+        }
+        if fork {
+            self.fork += 1;
+        }
+        if archived {
+            self.archived += 1;
+        }
+        if !(empty || fork || archived) {
+            self.unexplained += 1;
+        }
+    }
+
+    fn merge(&mut self, other: UnknownLanguageBreakdown) {
+        self.total += other.total;
+        self.empty += other.empty;
+        self.fork += other.fork;
+        self.archived += other.archived;
+        self.unexplained += other.unexplained;
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -24,6 +127,14 @@ pub struct LanguageCount {
     pub count: usize,
 }
 
+/// A language's share of all code, from `--detailed-languages`.
+#[derive(Debug, Serialize)]
+pub struct LanguageBytes {
+    pub language: String,
+    pub bytes: u64,
+    pub percent: f64,
+}
+
 #[derive(Debug, Serialize, Clone)]
 pub struct RepoRef {
     pub org: String,
@@ -31,101 +142,456 @@ pub struct RepoRef {
     pub count: u32,
 }
 
-pub async fn run(org: &Option<String>, json: bool, verbose: bool) -> Result<()> {
-    let config = load_config()?;
-    let token = config.token()?;
-    let client = GithubClient::new(token, verbose)?;
+pub async fn run(args: &StatsArgs, global: &GlobalArgs) -> Result<()> {
+    let baseline = match &args.compare_to {
+        Some(name) => Some(load_snapshot(&resolve_snapshot(name)?)?),
+        None => None,
+    };
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
 
-    let orgs = resolve_orgs(org, &config, &client).await?;
+    let collected = collect(args, baseline.is_some(), global, &config, &client).await?;
 
-    let mut total_repos = 0usize;
-    let mut total_stars = 0u32;
-    let mut total_forks = 0u32;
-    let mut total_open_issues = 0u32;
-    let mut lang_map: HashMap<String, usize> = HashMap::new();
-    let mut most_starred: Option<RepoRef> = None;
-    let mut most_forked: Option<RepoRef> = None;
+    if args.by_org {
+        let stats = collected.by_org(args, baseline.as_ref());
+        display::output_single(global.json, global.wrap_array, &stats, |data| {
+            for (org, stats) in data {
+                render_stats(&format!("Organization Statistics: {org}"), stats);
+            }
+        });
+    } else {
+        let stats = collected.total(args, baseline.as_ref());
+        display::output_single(global.json, global.wrap_array, &stats, |data| {
+            render_stats("Organization Statistics", data);
+        });
+    }
 
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// Repo counts gathered per org, before they're totalled.
+#[derive(Default)]
+pub struct CollectedStats {
+    per_org: BTreeMap<String, StatsAccumulator>,
+    /// For `--compare-to`: every repo listed, and those counted
+    listed: HashSet<String>,
+    counted: Vec<SnapshotRepo>,
+}
+
+impl CollectedStats {
+    /// One set of stats over every org.
+    pub fn total(self, args: &StatsArgs, baseline: Option<&Snapshot>) -> OrgStats {
+        let loaded: Vec<String> = self.per_org.keys().cloned().collect();
+        let since_snapshot = self.compare(baseline, &loaded);
+        let mut stats = self
+            .per_org
+            .into_values()
+            .fold(StatsAccumulator::default(), StatsAccumulator::merge)
+            .finish(args);
+        stats.since_snapshot = since_snapshot;
+        stats
+    }
+
+    /// Stats for each org separately (`--by-org`).
+    pub fn by_org(
+        self,
+        args: &StatsArgs,
+        baseline: Option<&Snapshot>,
+    ) -> BTreeMap<String, OrgStats> {
+        let comparisons: Vec<Option<SnapshotComparison>> = self
+            .per_org
+            .keys()
+            .map(|org| self.compare(baseline, std::slice::from_ref(org)))
+            .collect();
+        self.per_org
+            .into_iter()
+            .zip(comparisons)
+            .map(|((org, acc), since_snapshot)| {
+                let mut stats = acc.finish(args);
+                stats.since_snapshot = since_snapshot;
+                (org, stats)
+            })
+            .collect()
+    }
+
+    fn compare(&self, baseline: Option<&Snapshot>, orgs: &[String]) -> Option<SnapshotComparison> {
+        baseline.map(|baseline| {
+            let now: Vec<SnapshotRepo> = self
+                .counted
+                .iter()
+                .filter(|r| orgs.contains(&r.org))
+                .cloned()
+                .collect();
+            compare_to_snapshot(baseline, &now, &self.listed, orgs)
+        })
+    }
+}
+
+/// Lists the repos of every org and counts those passing the filters in
+/// `args`. With `compare`, also keeps what a snapshot comparison needs.
+pub async fn collect(
+    args: &StatsArgs,
+    compare: bool,
+    global: &GlobalArgs,
+    config: &Config,
+    client: &GithubClient,
+) -> Result<CollectedStats> {
+    let orgs = resolve_orgs(&args.org, &global.exclude_orgs, config, client).await?;
+
+    let mut per_org: BTreeMap<String, StatsAccumulator> = BTreeMap::new();
+    let mut seen = SeenRepos::default();
+    let mut listed = HashSet::new();
+    let mut counted = Vec::new();
+
+    if args.detailed_languages {
+        display::warn(
+            "--detailed-languages makes one API request per repo and can use up a lot of your rate limit",
+        );
+    }
+
+    let mut failures =
+        OrgFailures::new(orgs.len()).with_empty_report(args.include_empty_orgs || global.verbose);
     for org_name in &orgs {
-        let repos = match client.list_org_repos(org_name).await {
+        let repos = match client.list_org_repos(org_name, args.visibility).await {
             Ok(r) => r,
             Err(e) => {
-                display::warn(&format!("Failed to fetch repos for {org_name}: {e}"));
+                failures.repos_failed(&args.org, org_name, e)?;
                 continue;
             }
         };
+        failures.listed(org_name, repos.len());
+        if compare {
+            listed.extend(repos.iter().map(|repo| repo_key(org_name, &repo.name)));
+        }
+
+        let repos = select_repos(args, &mut seen, &repos);
+        if compare {
+            counted.extend(repos.iter().map(|repo| snapshot_repo(org_name, repo)));
+        }
+
+        let pr_counts = if args.accurate_issue_counts {
+            open_pr_counts(client, org_name, repos.iter().copied()).await
+        } else {
+            HashMap::new()
+        };
 
-        for repo in &repos {
-            total_repos += 1;
-            let stars = repo.stargazers_count.unwrap_or(0);
-            let forks = repo.forks_count.unwrap_or(0);
-            total_stars += stars;
-            total_forks += forks;
-            total_open_issues += repo.open_issues_count.unwrap_or(0);
-
-            let language = repo
-                .language
-                .as_ref()
-                .and_then(|v| v.as_str())
-                .unwrap_or("Unknown")
-                .to_string();
-
-            *lang_map.entry(language).or_insert(0) += 1;
-
-            if most_starred.as_ref().is_none_or(|r| stars > r.count) && stars > 0 {
-                most_starred = Some(RepoRef {
-                    org: org_name.clone(),
-                    name: repo.name.clone(),
-                    count: stars,
-                });
-            }
-            if most_forked.as_ref().is_none_or(|r| forks > r.count) && forks > 0 {
-                most_forked = Some(RepoRef {
-                    org: org_name.clone(),
-                    name: repo.name.clone(),
-                    count: forks,
-                });
+        let acc = per_org.entry(org_name.clone()).or_default();
+        if args.detailed_languages {
+            acc.add_language_bytes(language_bytes(client, org_name, &repos).await);
+        }
+        for repo in repos {
+            let open_prs = pr_counts.get(&repo.name).copied().unwrap_or(0);
+            acc.record(org_name, repo, open_prs);
+        }
+    }
+
+    failures.finish()?;
+
+    Ok(CollectedStats {
+        per_org,
+        listed,
+        counted,
+    })
+}
+
+/// The repos that count towards the totals: those passing the star and
+/// archived filters and not already counted under another org.
+fn select_repos<'a>(
+    args: &StatsArgs,
+    seen: &mut SeenRepos,
+    repos: &'a [OrgRepo],
+) -> Vec<&'a OrgRepo> {
+    repos
+        .iter()
+        .filter(|repo| {
+            args.stars.matches(repo.stargazers_count.unwrap_or(0))
+                && args.archived.matches(repo.archived.unwrap_or(false))
+        })
+        .filter(|repo| seen.insert(repo))
+        .collect()
+}
+
+/// Bytes per language summed over `repos`, skipping empty ones. Repos that
+/// fail are warned about and left out.
+async fn language_bytes(
+    client: &GithubClient,
+    owner: &str,
+    repos: &[&OrgRepo],
+) -> HashMap<String, u64> {
+    let candidates = repos.iter().filter(|repo| repo.size.unwrap_or(0) > 0);
+
+    let results: Vec<_> = stream::iter(candidates)
+        .map(|repo| async move {
+            let languages = client.get_repo_languages(owner, &repo.name).await;
+            (repo.name.clone(), languages)
+        })
+        .buffer_unordered(client.max_concurrency())
+        .collect()
+        .await;
+
+    let mut totals = HashMap::new();
+    for (repo, result) in results {
+        match result {
+            Ok(languages) => {
+                for (language, bytes) in languages {
+                    *totals.entry(language).or_insert(0) += bytes;
+                }
             }
+            Err(e) => display::warn(&format!(
+                "Failed to fetch languages for {owner}/{repo}: {e}"
+            )),
+        }
+    }
+    totals
+}
+
+/// Running totals for one org (or, once merged, for all of them).
+#[derive(Debug, Default)]
+struct StatsAccumulator {
+    total_repos: usize,
+    total_stars: u32,
+    total_forks: u32,
+    total_open_issues: u32,
+    total_open_prs: u32,
+    archived_count: usize,
+    with_discussions: usize,
+    total_size_kb: u64,
+    lang_map: HashMap<String, usize>,
+    lang_bytes: HashMap<String, u64>,
+    most_starred: Option<RepoRef>,
+    most_forked: Option<RepoRef>,
+    largest_repo: Option<RepoRef>,
+    unknown_language: UnknownLanguageBreakdown,
+}
+
+impl StatsAccumulator {
+    /// `open_prs` is split out of the repo's `open_issues_count`, which
+    /// GitHub reports with PRs included.
+    fn record(&mut self, org: &str, repo: &OrgRepo, open_prs: u32) {
+        let stars = repo.stargazers_count.unwrap_or(0);
+        let forks = repo.forks_count.unwrap_or(0);
+        let archived = repo.archived.unwrap_or(false);
+        let size_kb = repo.size.unwrap_or(0);
+
+        self.total_repos += 1;
+        self.total_size_kb += u64::from(size_kb);
+        self.total_stars += stars;
+        self.total_forks += forks;
+        self.total_open_issues += repo.open_issues_count.unwrap_or(0).saturating_sub(open_prs);
+        self.total_open_prs += open_prs;
+        if archived {
+            self.archived_count += 1;
+        }
+        if repo.has_discussions.unwrap_or(false) {
+            self.with_discussions += 1;
+        }
+
+        let language = repo
+            .language
+            .as_ref()
+            .and_then(|v| v.as_str())
+            .unwrap_or("Unknown")
+            .to_string();
+
+        if language == "Unknown" {
+            self.unknown_language.record(
+                repo.size.unwrap_or(0) == 0,
+                repo.fork.unwrap_or(false),
+                archived,
+            );
+        }
+
+        *self.lang_map.entry(language).or_insert(0) += 1;
+
+        let repo_ref = |count| RepoRef {
+            org: org.to_string(),
+            name: repo.name.clone(),
+            count,
+        };
+        keep_max(&mut self.most_starred, Some(repo_ref(stars)));
+        keep_max(&mut self.most_forked, Some(repo_ref(forks)));
+        keep_max(&mut self.largest_repo, Some(repo_ref(size_kb)));
+    }
+
+    fn add_language_bytes(&mut self, bytes: HashMap<String, u64>) {
+        for (language, count) in bytes {
+            *self.lang_bytes.entry(language).or_insert(0) += count;
+        }
+    }
+
+    fn merge(mut self, other: StatsAccumulator) -> StatsAccumulator {
+        self.total_repos += other.total_repos;
+        self.total_stars += other.total_stars;
+        self.total_forks += other.total_forks;
+        self.total_open_issues += other.total_open_issues;
+        self.total_open_prs += other.total_open_prs;
+        self.archived_count += other.archived_count;
+        self.with_discussions += other.with_discussions;
+        self.total_size_kb += other.total_size_kb;
+        for (language, count) in other.lang_map {
+            *self.lang_map.entry(language).or_insert(0) += count;
+        }
+        self.add_language_bytes(other.lang_bytes);
+        keep_max(&mut self.most_starred, other.most_starred);
+        keep_max(&mut self.most_forked, other.most_forked);
+        keep_max(&mut self.largest_repo, other.largest_repo);
+        self.unknown_language.merge(other.unknown_language);
+        self
+    }
+
+    fn finish(self, args: &StatsArgs) -> OrgStats {
+        let mut languages: Vec<LanguageCount> = self
+            .lang_map
+            .into_iter()
+            .map(|(language, count)| LanguageCount { language, count })
+            .collect();
+        languages.sort_by_key(|l| Reverse(l.count));
+
+        let language_bytes = args
+            .detailed_languages
+            .then(|| language_shares(self.lang_bytes));
+
+        OrgStats {
+            total_repos: self.total_repos,
+            total_stars: self.total_stars,
+            total_forks: self.total_forks,
+            total_open_issues: self.total_open_issues,
+            total_open_prs: args.accurate_issue_counts.then_some(self.total_open_prs),
+            avg_stars_per_repo: average(self.total_stars, self.total_repos),
+            avg_issues_per_repo: average(self.total_open_issues, self.total_repos),
+            archived_count: self.archived_count,
+            with_discussions: self.with_discussions,
+            total_size_kb: self.total_size_kb,
+            languages,
+            language_bytes,
+            most_starred: self.most_starred,
+            most_forked: self.most_forked,
+            largest_repo: self.largest_repo,
+            unknown_language: args.explain_unknown.then_some(self.unknown_language),
+            since_snapshot: None,
         }
     }
+}
+
+/// Replaces `best` with `candidate` if it has a strictly higher, non-zero
+/// count, so the first repo seen wins ties.
+fn keep_max(best: &mut Option<RepoRef>, candidate: Option<RepoRef>) {
+    if let Some(candidate) = candidate {
+        if candidate.count > 0 && best.as_ref().map_or(true, |b| candidate.count > b.count) {
+            *best = Some(candidate);
+        }
+    }
+}
 
-    let mut languages: Vec<LanguageCount> = lang_map
+/// Languages by total bytes, largest first, with their share of all code.
+fn language_shares(bytes: HashMap<String, u64>) -> Vec<LanguageBytes> {
+    let total: u64 = bytes.values().sum();
+    let mut shares: Vec<LanguageBytes> = bytes
         .into_iter()
-        .map(|(language, count)| LanguageCount { language, count })
+        .map(|(language, bytes)| LanguageBytes {
+            language,
+            bytes,
+            percent: if total == 0 {
+                0.0
+            } else {
+                bytes as f64 * 100.0 / total as f64
+            },
+        })
         .collect();
-    languages.sort_by(|a, b| b.count.cmp(&a.count));
-
-    let stats = OrgStats {
-        total_repos,
-        total_stars,
-        total_forks,
-        total_open_issues,
-        languages,
-        most_starred,
-        most_forked,
-    };
-
-    display::output(json, &stats, |data| {
-        render_stats(data);
+    shares.sort_by(|a, b| {
+        b.bytes
+            .cmp(&a.bytes)
+            .then_with(|| a.language.cmp(&b.language))
     });
+    shares
+}
 
-    client.check_rate_limit_if_verbose().await;
+/// Per-repo average, or 0 for an empty org.
+fn average(total: u32, repos: usize) -> f64 {
+    if repos == 0 {
+        0.0
+    } else {
+        f64::from(total) / repos as f64
+    }
+}
 
-    Ok(())
+/// " (+120 since 2024-06-01)" after a total, when comparing to a snapshot.
+fn change_note(delta: Option<i64>, since: Option<&SnapshotComparison>) -> String {
+    match (delta, since) {
+        (Some(delta), Some(since)) => {
+            format!(" ({delta:+} since {})", since.since.format("%Y-%m-%d"))
+        }
+        _ => String::new(),
+    }
 }
 
-fn render_stats(stats: &OrgStats) {
-    display::section_header("Organization Statistics");
+pub fn render_stats(title: &str, stats: &OrgStats) {
+    display::section_header(title);
 
-    println!("  {} {}", "Repositories:".bold(), stats.total_repos);
-    println!("  {} {}", "Total Stars:".bold(), stats.total_stars);
-    println!("  {} {}", "Total Forks:".bold(), stats.total_forks);
-    println!("  {} {}", "Open Issues:".bold(), stats.total_open_issues);
+    let since = stats.since_snapshot.as_ref();
+    display::outln!(
+        "  {} {}{}",
+        display::bold("Repositories:"),
+        stats.total_repos,
+        change_note(since.map(|c| c.repos), since)
+    );
+    display::outln!(
+        "  {} {}{}",
+        display::bold("Total Stars:"),
+        stats.total_stars,
+        change_note(since.map(|c| c.stars), since)
+    );
+    display::outln!(
+        "  {} {}{}",
+        display::bold("Total Forks:"),
+        stats.total_forks,
+        change_note(since.and_then(|c| c.forks), since)
+    );
+    match stats.total_open_prs {
+        Some(prs) => {
+            display::outln!(
+                "  {} {}",
+                display::bold("Open Issues:"),
+                stats.total_open_issues
+            );
+            display::outln!("  {} {}", display::bold("Open PRs:"), prs);
+        }
+        None => display::outln!(
+            "  {} {}",
+            display::bold("Open Issues + PRs:"),
+            stats.total_open_issues
+        ),
+    }
+    display::outln!(
+        "  {} {:.1}",
+        display::bold("Avg Stars/Repo:"),
+        stats.avg_stars_per_repo
+    );
+    display::outln!(
+        "  {} {:.1}",
+        display::bold("Avg Issues/Repo:"),
+        stats.avg_issues_per_repo
+    );
+    display::outln!("  {} {}", display::bold("Archived:"), stats.archived_count);
+    display::outln!(
+        "  {} {}",
+        display::bold("With Discussions:"),
+        stats.with_discussions
+    );
+    display::outln!(
+        "  {} {}",
+        display::bold("Disk Size:"),
+        display::human_size(stats.total_size_kb)
+    );
 
     if let Some(ref r) = stats.most_starred {
-        println!(
+        display::outln!(
             "  {} {}/{} ({})",
-            "Most Starred:".bold(),
+            display::bold("Most Starred:"),
             r.org,
             r.name,
             r.count
@@ -133,21 +599,86 @@ fn render_stats(stats: &OrgStats) {
     }
 
     if let Some(ref r) = stats.most_forked {
-        println!(
+        display::outln!(
             "  {} {}/{} ({})",
-            "Most Forked:".bold(),
+            display::bold("Most Forked:"),
             r.org,
             r.name,
             r.count
         );
     }
 
+    if let Some(ref r) = stats.largest_repo {
+        display::outln!(
+            "  {} {}/{} ({})",
+            display::bold("Largest Repo:"),
+            r.org,
+            r.name,
+            display::human_size(u64::from(r.count))
+        );
+    }
+
     if !stats.languages.is_empty() {
-        println!("\n  {}", "Top Languages:".bold());
+        display::outln!("\n  {}", display::bold("Top Languages:"));
         for (i, lang) in stats.languages.iter().take(10).enumerate() {
-            println!("    {}. {} ({})", i + 1, lang.language, lang.count);
+            display::outln!("    {}. {} ({})", i + 1, lang.language, lang.count);
+        }
+    }
+
+    if let Some(ref shares) = stats.language_bytes {
+        display::outln!("\n  {}", display::bold("Languages by Code Size:"));
+        for (i, lang) in shares.iter().take(10).enumerate() {
+            display::outln!("    {}. {} ({:.1}%)", i + 1, lang.language, lang.percent);
         }
     }
+
+    if let Some(ref unknown) = stats.unknown_language {
+        display::outln!("\n  {}", display::bold("Unknown Language Breakdown:"));
+        if unknown.total == 0 {
+            display::outln!("    No repositories with an unknown language.");
+        } else {
+            display::outln!("    Total:       {}", unknown.total);
+            display::outln!("    Empty:       {}", unknown.empty);
+            display::outln!("    Forks:       {}", unknown.fork);
+            display::outln!("    Archived:    {}", unknown.archived);
+            display::outln!("    Unexplained: {}", unknown.unexplained);
+        }
+    }
+
+    if let Some(since) = since {
+        render_star_changes(since);
+    }
+}
+
+fn render_star_changes(since: &SnapshotComparison) {
+    let date = since.since.format("%Y-%m-%d");
+    if since.repo_changes.is_empty() {
+        display::outln!("\n  No repo's stars or forks changed since {date}.");
+        return;
+    }
+    display::outln!(
+        "\n  {}",
+        display::bold(&format!("Star Changes Since {date}:"))
+    );
+    let mut table = display::new_table(&["Repo", "Before", "After", "Change", "Forks"]);
+    for change in since.repo_changes.iter().take(TOP_CHANGES) {
+        table.add_row(vec![
+            format!("{}/{}", change.org, change.name),
+            change.stars_before.to_string(),
+            change.stars.to_string(),
+            format!("{:+}", change.star_delta),
+            change
+                .fork_delta
+                .map_or("-".to_string(), |delta| format!("{delta:+}")),
+        ]);
+    }
+    display::print_table(&table);
+    if since.repo_changes.len() > TOP_CHANGES {
+        display::outln!(
+            "  ...and {} more (see --json)",
+            since.repo_changes.len() - TOP_CHANGES
+        );
+    }
 }
 
 #[cfg(test)]
@@ -156,7 +687,7 @@ mod tests {
 
     #[test]
     fn language_sorting_by_count_descending() {
-        let mut langs = vec![
+        let mut langs = [
             LanguageCount {
                 language: "Go".into(),
                 count: 3,
@@ -170,7 +701,7 @@ mod tests {
                 count: 7,
             },
         ];
-        langs.sort_by(|a, b| b.count.cmp(&a.count));
+        langs.sort_by_key(|l| Reverse(l.count));
         assert_eq!(langs[0].language, "Rust");
         assert_eq!(langs[1].language, "Python");
         assert_eq!(langs[2].language, "Go");
@@ -195,20 +726,215 @@ mod tests {
             total_stars: 100,
             total_forks: 20,
             total_open_issues: 10,
+            total_open_prs: Some(4),
+            avg_stars_per_repo: average(100, 5),
+            avg_issues_per_repo: average(10, 5),
+            archived_count: 1,
+            with_discussions: 2,
+            total_size_kb: 2048,
             languages: vec![LanguageCount {
                 language: "Rust".into(),
                 count: 3,
             }],
+            language_bytes: None,
             most_starred: Some(RepoRef {
                 org: "myorg".into(),
                 name: "best-repo".into(),
                 count: 50,
             }),
             most_forked: None,
+            largest_repo: Some(RepoRef {
+                org: "myorg".into(),
+                name: "big-repo".into(),
+                count: 1500,
+            }),
+            unknown_language: None,
+            since_snapshot: None,
         };
 
         let json = serde_json::to_string(&stats).unwrap();
         assert!(json.contains("\"total_repos\":5"));
         assert!(json.contains("\"best-repo\""));
+        assert!(json.contains("\"avg_stars_per_repo\":20.0"));
+        assert!(json.contains("\"avg_issues_per_repo\":2.0"));
+        assert!(json.contains("\"archived_count\":1"));
+        assert!(json.contains("\"total_open_prs\":4"));
+        assert!(json.contains("\"total_size_kb\":2048"));
+        assert!(json
+            .contains("\"largest_repo\":{\"org\":\"myorg\",\"name\":\"big-repo\",\"count\":1500}"));
+        assert!(!json.contains("unknown_language"));
+        assert!(!json.contains("language_bytes"));
+        assert!(!json.contains("since_snapshot"));
+    }
+
+    #[test]
+    fn change_note_only_with_a_snapshot() {
+        let since = SnapshotComparison {
+            since: "2024-06-01T00:00:00Z".parse().unwrap(),
+            repos: 0,
+            stars: 120,
+            forks: None,
+            repo_changes: Vec::new(),
+        };
+        assert_eq!(
+            change_note(Some(since.stars), Some(&since)),
+            " (+120 since 2024-06-01)"
+        );
+        assert_eq!(change_note(Some(0), Some(&since)), " (+0 since 2024-06-01)");
+        assert_eq!(change_note(since.forks, Some(&since)), "");
+        assert_eq!(change_note(Some(5), None), "");
+    }
+
+    #[test]
+    fn language_bytes_merge_into_shares() {
+        let mut a = StatsAccumulator::default();
+        a.add_language_bytes(HashMap::from([
+            ("Rust".to_string(), 600),
+            ("Shell".to_string(), 100),
+        ]));
+        let mut b = StatsAccumulator::default();
+        b.add_language_bytes(HashMap::from([("Rust".to_string(), 300)]));
+        b.add_language_bytes(HashMap::from([("Go".to_string(), 0)]));
+
+        let args = StatsArgs {
+            detailed_languages: true,
+            ..StatsArgs::default()
+        };
+        let shares = a.merge(b).finish(&args).language_bytes.unwrap();
+        let summary: Vec<(&str, u64)> = shares
+            .iter()
+            .map(|l| (l.language.as_str(), l.bytes))
+            .collect();
+        assert_eq!(summary, vec![("Rust", 900), ("Shell", 100), ("Go", 0)]);
+        assert_eq!(shares[0].percent, 90.0);
+
+        assert!(StatsAccumulator::default()
+            .finish(&StatsArgs::default())
+            .language_bytes
+            .is_none());
+    }
+
+    fn repo_ref(name: &str, count: u32) -> Option<RepoRef> {
+        Some(RepoRef {
+            org: "org".into(),
+            name: name.into(),
+            count,
+        })
+    }
+
+    #[test]
+    fn keep_max_prefers_first_of_equal_counts_and_skips_zero() {
+        let mut best = None;
+        keep_max(&mut best, repo_ref("zero", 0));
+        assert!(best.is_none());
+        keep_max(&mut best, repo_ref("first", 5));
+        keep_max(&mut best, repo_ref("tie", 5));
+        keep_max(&mut best, repo_ref("lower", 3));
+        assert_eq!(best.as_ref().unwrap().name, "first");
+        keep_max(&mut best, repo_ref("higher", 9));
+        assert_eq!(best.unwrap().name, "higher");
+    }
+
+    #[test]
+    fn merged_accumulators_sum_per_org_totals() {
+        let a = StatsAccumulator {
+            total_repos: 2,
+            total_stars: 30,
+            total_open_issues: 4,
+            lang_map: HashMap::from([("Rust".to_string(), 2)]),
+            most_starred: repo_ref("a", 20),
+            ..StatsAccumulator::default()
+        };
+        let b = StatsAccumulator {
+            total_repos: 3,
+            total_stars: 60,
+            archived_count: 1,
+            lang_map: HashMap::from([("Rust".to_string(), 1), ("Go".to_string(), 2)]),
+            most_starred: repo_ref("b", 50),
+            ..StatsAccumulator::default()
+        };
+
+        let stats = StatsAccumulator::default()
+            .merge(a)
+            .merge(b)
+            .finish(&StatsArgs::default());
+        assert_eq!(stats.total_repos, 5);
+        assert_eq!(stats.total_stars, 90);
+        assert_eq!(stats.archived_count, 1);
+        assert_eq!(stats.avg_stars_per_repo, 18.0);
+        assert_eq!(stats.languages[0].language, "Rust");
+        assert_eq!(stats.languages[0].count, 3);
+        assert_eq!(stats.most_starred.unwrap().name, "b");
+        assert!(stats.unknown_language.is_none());
+        assert!(stats.total_open_prs.is_none());
+    }
+
+    fn repo(id: u64, name: &str, stars: u32) -> OrgRepo {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "name": name,
+            "url": format!("https://api.github.com/repos/org/{name}"),
+            "stargazers_count": stars,
+            "open_issues_count": 7
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn duplicate_repos_are_counted_once() {
+        let args = StatsArgs::default();
+        let listing = vec![repo(1, "app", 10), repo(2, "lib", 5)];
+        let mut seen = SeenRepos::default();
+        let mut acc = StatsAccumulator::default();
+
+        // The same org listed twice, plus a repo reachable from a second org
+        for repos in [&listing, &listing, &vec![repo(2, "lib", 5)]] {
+            for repo in select_repos(&args, &mut seen, repos) {
+                acc.record("org", repo, 0);
+            }
+        }
+
+        let stats = acc.finish(&args);
+        assert_eq!(stats.total_repos, 2);
+        assert_eq!(stats.total_stars, 15);
+        assert_eq!(stats.total_open_issues, 14);
+    }
+
+    #[test]
+    fn open_prs_are_split_out_of_issue_counts() {
+        let repo = repo(1, "app", 0);
+
+        let mut acc = StatsAccumulator::default();
+        acc.record("org", &repo, 3);
+        // A failed PR count falls back to GitHub's combined figure
+        acc.record("org", &repo, 0);
+
+        let stats = acc.finish(&StatsArgs {
+            accurate_issue_counts: true,
+            ..StatsArgs::default()
+        });
+        assert_eq!(stats.total_open_issues, 11);
+        assert_eq!(stats.total_open_prs, Some(3));
+    }
+
+    #[test]
+    fn average_of_empty_org_is_zero() {
+        assert_eq!(average(0, 0), 0.0);
+        assert_eq!(average(7, 2), 3.5);
+    }
+
+    #[test]
+    fn unknown_language_breakdown_counts_reasons() {
+        let mut breakdown = UnknownLanguageBreakdown::default();
+        breakdown.record(true, false, false);
+        breakdown.record(true, true, false);
+        breakdown.record(false, false, true);
+        breakdown.record(false, false, false);
+
+        assert_eq!(breakdown.total, 4);
+        assert_eq!(breakdown.empty, 2);
+        assert_eq!(breakdown.fork, 1);
+        assert_eq!(breakdown.archived, 1);
+        assert_eq!(breakdown.unexplained, 1);
     }
 }
@@ -0,0 +1,144 @@
+use crate::commands::{github_client, resolve_orgs, single_org_flag, GlobalArgs};
+use crate::display;
+use crate::error::{GitorgError, Result};
+use crate::github::Team;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct TeamSummary {
+    pub org: String,
+    pub name: String,
+    pub slug: String,
+    pub privacy: String,
+    /// `None` if the team's details couldn't be fetched.
+    pub members: Option<u32>,
+}
+
+pub async fn run(org: &Option<String>, global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let orgs = resolve_orgs(org, &global.exclude_orgs, &config, &client).await?;
+
+    let mut summaries = Vec::new();
+    for org_name in &orgs {
+        let teams = match client.list_org_teams(org_name).await {
+            Ok(t) => t,
+            Err(e @ GitorgError::OrgNotFound(_)) if single_org_flag(org) => return Err(e),
+            Err(e) => {
+                display::warn(&format!("Failed to fetch teams for {org_name}: {e}"));
+                continue;
+            }
+        };
+
+        // Listings don't carry member counts, so fetch each team's details
+        let client = &client;
+        let details: Vec<(Team, Result<Team>)> = stream::iter(teams)
+            .map(|team| async move {
+                let detail = client.get_team(org_name, &team.slug).await;
+                (team, detail)
+            })
+            .buffer_unordered(client.max_concurrency())
+            .collect()
+            .await;
+
+        for (team, detail) in details {
+            let members = match detail {
+                Ok(detail) => detail.members_count,
+                Err(e) => {
+                    display::warn(&format!(
+                        "Failed to fetch members for {org_name}/{}: {e}",
+                        team.slug
+                    ));
+                    None
+                }
+            };
+            summaries.push(TeamSummary {
+                org: org_name.clone(),
+                name: team.name,
+                slug: team.slug,
+                privacy: team.privacy.unwrap_or_default(),
+                members,
+            });
+        }
+    }
+
+    sort_teams(&mut summaries);
+
+    display::output_records(global.json, &summaries, |data| {
+        render_teams_table(data);
+    })?;
+
+    client.warn_if_truncated();
+    client.report_retries(global.json);
+    client.check_rate_limit_if_verbose().await;
+
+    Ok(())
+}
+
+/// Groups teams by org, alphabetically by name within each.
+fn sort_teams(teams: &mut [TeamSummary]) {
+    teams.sort_by(|a, b| {
+        a.org
+            .cmp(&b.org)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+}
+
+fn render_teams_table(teams: &[TeamSummary]) {
+    if teams.is_empty() {
+        display::warn("No teams found.");
+        return;
+    }
+
+    display::section_header("Teams");
+
+    let mut table = display::new_table(&["Org", "Team", "Slug", "Privacy", "Members"]);
+
+    for t in teams {
+        table.add_row(vec![
+            t.org.clone(),
+            t.name.clone(),
+            t.slug.clone(),
+            t.privacy.clone(),
+            t.members.map_or_else(|| "-".to_string(), |m| m.to_string()),
+        ]);
+    }
+
+    display::print_table(&table);
+    display::count_footer(teams.len(), teams.len(), "team(s)");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn team(org: &str, name: &str) -> TeamSummary {
+        TeamSummary {
+            org: org.to_string(),
+            name: name.to_string(),
+            slug: name.to_lowercase(),
+            privacy: "closed".to_string(),
+            members: Some(1),
+        }
+    }
+
+    #[test]
+    fn teams_sort_by_org_then_name() {
+        let mut teams = vec![
+            team("zeta", "Admins"),
+            team("acme", "platform"),
+            team("acme", "Design"),
+        ];
+        sort_teams(&mut teams);
+        let order: Vec<(&str, &str)> = teams
+            .iter()
+            .map(|t| (t.org.as_str(), t.name.as_str()))
+            .collect();
+        assert_eq!(
+            order,
+            vec![("acme", "Design"), ("acme", "platform"), ("zeta", "Admins")]
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use crate::commands::auth::missing_scopes;
+use crate::commands::{github_client, GlobalArgs};
+use crate::display;
+use crate::error::Result;
+use crate::github::RateLimitResource;
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct Whoami {
+    pub login: String,
+    pub name: Option<String>,
+    /// `None` when GitHub doesn't report scopes for the token
+    pub scopes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rate_limit: Option<RateLimitResource>,
+}
+
+pub async fn run(global: &GlobalArgs) -> Result<()> {
+    let config = global.load_config()?;
+    let client = github_client(&config, global)?;
+
+    let user = client.validate_token().await?;
+    let rate_limit = match client.get_rate_limit().await {
+        Ok(rate_limit) => Some(rate_limit.resources.core),
+        Err(e) => {
+            display::warn(&format!("Could not fetch rate limit: {e}"));
+            None
+        }
+    };
+
+    let whoami = Whoami {
+        login: user.login,
+        name: user.name,
+        scopes: user.scopes,
+        rate_limit,
+    };
+
+    display::output(global.json, &whoami, |data| {
+        render_whoami(data);
+    });
+
+    Ok(())
+}
+
+fn render_whoami(whoami: &Whoami) {
+    display::section_header("Authenticated User");
+
+    display::outln!("  {} {}", display::bold("Login:"), whoami.login);
+    display::outln!(
+        "  {} {}",
+        display::bold("Name:"),
+        whoami.name.as_deref().unwrap_or("-")
+    );
+    match &whoami.scopes {
+        Some(scopes) => {
+            display::outln!(
+                "  {} {}",
+                display::bold("Scopes:"),
+                if scopes.is_empty() {
+                    "none".to_string()
+                } else {
+                    scopes.join(", ")
+                }
+            );
+            let missing = missing_scopes(scopes);
+            if !missing.is_empty() {
+                display::warn(&format!(
+                    "Token is missing the {} scope(s)",
+                    missing.join(", ")
+                ));
+            }
+        }
+        None => display::outln!(
+            "  {} not reported (fine-grained or app token)",
+            display::bold("Scopes:")
+        ),
+    }
+    if let Some(ref core) = whoami.rate_limit {
+        display::outln!(
+            "  {} {}/{} (resets {})",
+            display::bold("Rate Limit:"),
+            core.remaining,
+            core.limit,
+            core.reset_time()
+        );
+    }
+}
@@ -1,10 +1,30 @@
 use crate::error::{GitorgError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Name of the profile stored in the top-level `[auth]`/`[defaults]` tables.
+/// Configs written before profiles existed are read as this profile.
+pub const DEFAULT_PROFILE: &str = "default";
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct Config {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_profile: Option<String>,
+    #[serde(default)]
+    pub auth: AuthConfig,
+    #[serde(default)]
+    pub defaults: DefaultsConfig,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
+    /// Profile chosen with `--profile` for this invocation; never persisted.
+    #[serde(skip)]
+    selected_profile: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct Profile {
     #[serde(default)]
     pub auth: AuthConfig,
     #[serde(default)]
@@ -14,56 +34,187 @@ pub struct Config {
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct AuthConfig {
     pub token: Option<String>,
+    /// Where the token lives; with `keyring`, `token` is left unset
+    #[serde(default, skip_serializing_if = "TokenStorage::is_file")]
+    pub storage: TokenStorage,
+    /// API base URL for GitHub Enterprise Server; github.com when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStorage {
+    #[default]
+    File,
+    Keyring,
+}
+
+impl TokenStorage {
+    fn is_file(&self) -> bool {
+        *self == TokenStorage::File
+    }
+}
+
+impl AuthConfig {
+    /// Whether a token has been saved, in either backend.
+    pub fn has_token(&self) -> bool {
+        self.token.is_some() || self.storage == TokenStorage::Keyring
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct DefaultsConfig {
     pub orgs: Option<Vec<String>>,
+    /// Sort order for `repos` when `--sort` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo_sort: Option<String>,
+    /// Request timeout in seconds when `--timeout` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+    /// HTTP proxy for API requests when `--proxy` isn't given; overrides
+    /// the proxy environment variables
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Days without a push before `stale` and `overview` call a repo stale,
+    /// when `--days` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stale_days: Option<u64>,
+    /// Days without activity before `repos` and `stale` color a repo
+    /// yellow (default 90)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staleness_yellow_days: Option<u64>,
+    /// Days without activity before a repo turns red (default 365)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staleness_red_days: Option<u64>,
+    /// Output format when no format flag (or `--output` extension) picks one.
+    /// Only the formats gitorg can print are accepted: `table`, `json`,
+    /// `ndjson`, and `markdown`. There is no CSV or YAML writer, and any
+    /// other value fails to load the config
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output_format: Option<OutputFormat>,
+    /// Requests in flight at once when `--max-concurrency` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_concurrency: Option<usize>,
+    /// Core rate-limit requests to leave unspent when
+    /// `--rate-limit-headroom` isn't given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate_limit_headroom: Option<u32>,
+}
+
+/// Formats `defaults.output_format` can select, one per output flag
+/// (`--table`, `--json`, `--ndjson`, `--markdown`).
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    Table,
+    Json,
+    Ndjson,
+    Markdown,
 }
 
 impl Config {
-    pub fn token(&self) -> Result<&str> {
-        self.auth
-            .token
+    /// The profile in effect: `--profile`, then `active_profile`, then "default".
+    pub fn profile_name(&self) -> &str {
+        self.selected_profile
             .as_deref()
-            .ok_or(GitorgError::NotAuthenticated)
+            .or(self.active_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE)
+    }
+
+    /// Selects the profile for this invocation. A profile that doesn't exist
+    /// yet starts out empty, so `auth --profile <name>` can create it.
+    pub fn select_profile(&mut self, name: Option<&str>) {
+        if let Some(name) = name {
+            self.selected_profile = Some(name.to_string());
+        }
+        let name = self.profile_name().to_string();
+        if name != DEFAULT_PROFILE {
+            self.profiles.entry(name).or_default();
+        }
+    }
+
+    pub fn has_profile(&self, name: &str) -> bool {
+        name == DEFAULT_PROFILE || self.profiles.contains_key(name)
+    }
+
+    fn profile(&self) -> Option<&Profile> {
+        match self.profile_name() {
+            DEFAULT_PROFILE => None,
+            name => self.profiles.get(name),
+        }
+    }
+
+    pub fn auth(&self) -> &AuthConfig {
+        self.profile().map_or(&self.auth, |p| &p.auth)
+    }
+
+    pub fn auth_mut(&mut self) -> &mut AuthConfig {
+        let name = self.profile_name().to_string();
+        if name == DEFAULT_PROFILE {
+            return &mut self.auth;
+        }
+        &mut self.profiles.entry(name).or_default().auth
+    }
+
+    pub fn defaults(&self) -> &DefaultsConfig {
+        self.profile().map_or(&self.defaults, |p| &p.defaults)
+    }
+
+    pub fn defaults_mut(&mut self) -> &mut DefaultsConfig {
+        let name = self.profile_name().to_string();
+        if name == DEFAULT_PROFILE {
+            return &mut self.defaults;
+        }
+        &mut self.profiles.entry(name).or_default().defaults
+    }
+
+    pub fn token(&self) -> Result<String> {
+        let auth = self.auth();
+        let token = match auth.storage {
+            TokenStorage::File => auth.token.clone(),
+            TokenStorage::Keyring => crate::keyring::load(self.profile_name())?,
+        };
+        token.ok_or(GitorgError::NotAuthenticated)
     }
 }
 
-pub fn config_path() -> Result<PathBuf> {
+pub fn config_dir() -> Result<PathBuf> {
     if let Ok(xdg) = std::env::var("XDG_CONFIG_HOME") {
-        let path = PathBuf::from(xdg).join("gitorg").join("config.toml");
-        return Ok(path);
+        return Ok(PathBuf::from(xdg).join("gitorg"));
     }
 
     let home =
         dirs::home_dir().ok_or_else(|| GitorgError::Config("Cannot find home directory".into()))?;
-    Ok(home.join(".config").join("gitorg").join("config.toml"))
+    Ok(home.join(".config").join("gitorg"))
 }
 
-pub fn load_config() -> Result<Config> {
-    let path = config_path()?;
+/// The default config file location; `--config` overrides it.
+pub fn config_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("config.toml"))
+}
+
+pub fn load_config(path: &Path) -> Result<Config> {
     if !path.exists() {
         return Ok(Config::default());
     }
-    let contents = fs::read_to_string(&path)?;
+    let contents = fs::read_to_string(path)?;
     let config: Config = toml::from_str(&contents)?;
     Ok(config)
 }
 
-pub fn save_config(config: &Config) -> Result<()> {
-    let path = config_path()?;
-    if let Some(parent) = path.parent() {
+pub fn save_config(path: &Path, config: &Config) -> Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
         fs::create_dir_all(parent)?;
     }
     let contents = toml::to_string_pretty(config)?;
-    fs::write(&path, &contents)?;
+    fs::write(path, &contents)?;
 
     #[cfg(unix)]
     {
         use std::os::unix::fs::PermissionsExt;
         let perms = fs::Permissions::from_mode(0o600);
-        fs::set_permissions(&path, perms)?;
+        fs::set_permissions(path, perms)?;
     }
 
     Ok(())
@@ -78,22 +229,59 @@ mod tests {
         let config = Config {
             auth: AuthConfig {
                 token: Some("ghp_test123".to_string()),
+                storage: TokenStorage::File,
+                base_url: Some("https://ghe.example.com/api/v3".to_string()),
             },
             defaults: DefaultsConfig {
                 orgs: Some(vec!["myorg".to_string(), "other".to_string()]),
+                repo_sort: None,
+                timeout: None,
+                proxy: None,
+                stale_days: None,
+                staleness_yellow_days: None,
+                staleness_red_days: None,
+                output_format: None,
+                max_concurrency: None,
+                rate_limit_headroom: None,
             },
+            ..Config::default()
         };
 
         let serialized = toml::to_string_pretty(&config).unwrap();
         let deserialized: Config = toml::from_str(&serialized).unwrap();
 
         assert_eq!(deserialized.auth.token.as_deref(), Some("ghp_test123"));
+        assert_eq!(
+            deserialized.auth.base_url.as_deref(),
+            Some("https://ghe.example.com/api/v3")
+        );
         assert_eq!(
             deserialized.defaults.orgs,
             Some(vec!["myorg".to_string(), "other".to_string()])
         );
     }
 
+    #[test]
+    fn token_storage_defaults_to_file_and_roundtrips() {
+        let config: Config = toml::from_str("[auth]\nstorage = \"keyring\"\n").unwrap();
+        assert_eq!(config.auth.storage, TokenStorage::Keyring);
+        assert!(config.auth.has_token());
+        assert!(toml::to_string(&config)
+            .unwrap()
+            .contains("storage = \"keyring\""));
+
+        let config = Config::default();
+        assert!(!config.auth.has_token());
+        assert!(!toml::to_string(&config).unwrap().contains("storage"));
+    }
+
+    #[test]
+    fn output_format_parses_lowercase_names() {
+        let config: Config = toml::from_str("[defaults]\noutput_format = \"ndjson\"\n").unwrap();
+        assert_eq!(config.defaults().output_format, Some(OutputFormat::Ndjson));
+        assert!(toml::from_str::<Config>("[defaults]\noutput_format = \"yaml\"\n").is_err());
+    }
+
     #[test]
     fn config_default_has_no_token() {
         let config = Config::default();
@@ -105,8 +293,10 @@ mod tests {
         let config = Config {
             auth: AuthConfig {
                 token: Some("ghp_abc".to_string()),
+                storage: TokenStorage::File,
+                base_url: None,
             },
-            defaults: DefaultsConfig::default(),
+            ..Config::default()
         };
         assert_eq!(config.token().unwrap(), "ghp_abc");
     }
@@ -118,6 +308,78 @@ mod tests {
         assert!(config.defaults.orgs.is_none());
     }
 
+    #[test]
+    fn flat_config_is_default_profile() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [auth]
+            token = "ghp_legacy"
+
+            [defaults]
+            orgs = ["legacy-org"]
+            "#,
+        )
+        .unwrap();
+        config.select_profile(None);
+
+        assert_eq!(config.profile_name(), DEFAULT_PROFILE);
+        assert_eq!(config.token().unwrap(), "ghp_legacy");
+        assert_eq!(config.defaults().orgs, Some(vec!["legacy-org".to_string()]));
+    }
+
+    #[test]
+    fn named_profile_selection() {
+        let toml = r#"
+            active_profile = "work"
+
+            [auth]
+            token = "ghp_personal"
+
+            [profiles.work.auth]
+            token = "ghp_work"
+
+            [profiles.work.defaults]
+            orgs = ["work-org"]
+        "#;
+
+        let mut config: Config = toml::from_str(toml).unwrap();
+        config.select_profile(None);
+        assert_eq!(config.profile_name(), "work");
+        assert_eq!(config.token().unwrap(), "ghp_work");
+        assert_eq!(config.defaults().orgs, Some(vec!["work-org".to_string()]));
+
+        let mut config: Config = toml::from_str(toml).unwrap();
+        config.select_profile(Some(DEFAULT_PROFILE));
+        assert_eq!(config.token().unwrap(), "ghp_personal");
+        assert!(config.defaults().orgs.is_none());
+    }
+
+    #[test]
+    fn auth_writes_into_selected_profile() {
+        let mut config = Config::default();
+        config.auth.token = Some("ghp_personal".to_string());
+        config.select_profile(Some("work"));
+        config.auth_mut().token = Some("ghp_work".to_string());
+
+        let serialized = toml::to_string_pretty(&config).unwrap();
+        let deserialized: Config = toml::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.auth.token.as_deref(), Some("ghp_personal"));
+        assert_eq!(
+            deserialized.profiles["work"].auth.token.as_deref(),
+            Some("ghp_work")
+        );
+        // --profile is per-invocation and must not switch the active profile
+        assert!(deserialized.active_profile.is_none());
+    }
+
+    #[test]
+    fn unknown_profile_is_unauthenticated() {
+        let mut config = Config::default();
+        config.select_profile(Some("missing"));
+        assert!(matches!(config.token(), Err(GitorgError::NotAuthenticated)));
+    }
+
     #[test]
     fn config_path_uses_xdg() {
         std::env::set_var("XDG_CONFIG_HOME", "/tmp/test_xdg");
@@ -125,4 +387,19 @@ mod tests {
         assert_eq!(path, PathBuf::from("/tmp/test_xdg/gitorg/config.toml"));
         std::env::remove_var("XDG_CONFIG_HOME");
     }
+
+    #[test]
+    fn save_and_load_at_explicit_path() {
+        let dir = std::env::temp_dir().join(format!("gitorg_config_{}", std::process::id()));
+        let path = dir.join("nested").join("gitorg.toml");
+        let mut config = Config::default();
+        config.defaults.orgs = Some(vec!["project-org".to_string()]);
+
+        save_config(&path, &config).unwrap();
+        let loaded = load_config(&path).unwrap();
+        assert_eq!(loaded.defaults.orgs, Some(vec!["project-org".to_string()]));
+
+        fs::remove_dir_all(&dir).unwrap();
+        assert!(load_config(&path).unwrap().defaults.orgs.is_none());
+    }
 }
@@ -1,40 +1,707 @@
-use comfy_table::{presets::UTF8_FULL_CONDENSED, ContentArrangement, Table};
-use owo_colors::OwoColorize;
+use crate::error::GitorgError;
+use comfy_table::presets::{
+    ASCII_FULL, ASCII_FULL_CONDENSED, NOTHING, UTF8_FULL, UTF8_FULL_CONDENSED,
+};
+use comfy_table::{Attribute, Cell, Color, ContentArrangement, Table};
+use owo_colors::{OwoColorize, Style};
 use serde::Serialize;
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{BufRead, IsTerminal, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 
-pub fn output<T: Serialize>(json_mode: bool, data: &T, render_table: impl FnOnce(&T)) {
+static COLOR: AtomicBool = AtomicBool::new(true);
+static MARKDOWN: AtomicBool = AtomicBool::new(false);
+static NDJSON: AtomicBool = AtomicBool::new(false);
+static COMPACT_JSON: AtomicBool = AtomicBool::new(false);
+static QUIET: AtomicBool = AtomicBool::new(false);
+static OUTPUT: Mutex<Option<File>> = Mutex::new(None);
+static TABLE_STYLE: Mutex<TableStyle> = Mutex::new(TableStyle::Condensed);
+static FORMAT: Mutex<Option<String>> = Mutex::new(None);
+static STALENESS: Mutex<StalenessThresholds> = Mutex::new(StalenessThresholds::DEFAULT);
+
+/// Table border presets for `--table-style`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TableStyle {
+    /// Borders and a rule between every row
+    Full,
+    /// Borders with a rule under the header only
+    #[default]
+    Condensed,
+    /// Condensed, drawn with plain ASCII even in a terminal
+    Ascii,
+    /// No borders, columns separated by spaces
+    Borderless,
+}
+
+impl TableStyle {
+    /// The comfy_table preset; box-drawing characters only when color is on.
+    fn preset(self, color: bool) -> &'static str {
+        match (self, color) {
+            (TableStyle::Full, true) => UTF8_FULL,
+            (TableStyle::Full, false) => ASCII_FULL,
+            (TableStyle::Condensed, true) => UTF8_FULL_CONDENSED,
+            (TableStyle::Condensed, false) | (TableStyle::Ascii, _) => ASCII_FULL_CONDENSED,
+            (TableStyle::Borderless, _) => NOTHING,
+        }
+    }
+}
+
+/// `print!` for command results, honoring `--output`.
+macro_rules! out {
+    ($($arg:tt)*) => {
+        $crate::display::write_out(format_args!($($arg)*))
+    };
+}
+
+/// `println!` for command results, honoring `--output`. Warnings and
+/// errors keep going to stderr.
+macro_rules! outln {
+    () => {
+        $crate::display::write_out(format_args!("\n"))
+    };
+    ($($arg:tt)*) => {
+        $crate::display::write_out(format_args!("{}\n", format_args!($($arg)*)))
+    };
+}
+
+pub(crate) use {out, outln};
+
+/// Decides once per run whether to emit ANSI styling and box-drawing
+/// borders: off for `--no-color`, a non-empty `NO_COLOR`, or when stdout
+/// isn't a terminal.
+pub fn init_color(no_color_flag: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let enabled = !no_color_flag && !no_color_env && std::io::stdout().is_terminal();
+    COLOR.store(enabled, Ordering::Relaxed);
+}
+
+/// Sends command results to `path` instead of stdout (`--output`),
+/// creating parent directories as needed.
+pub fn set_output_file(path: &Path) -> crate::error::Result<()> {
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+    let file = File::create(path)?;
+    *OUTPUT.lock().unwrap_or_else(|e| e.into_inner()) = Some(file);
+    Ok(())
+}
+
+/// Backs [`out!`] and [`outln!`].
+pub fn write_out(args: fmt::Arguments) {
+    let mut output = OUTPUT.lock().unwrap_or_else(|e| e.into_inner());
+    match output.as_mut() {
+        Some(file) => {
+            if let Err(e) = file.write_fmt(args) {
+                error(&format!("Failed to write output: {e}"));
+                std::process::exit(1);
+            }
+        }
+        None => print!("{args}"),
+    }
+}
+
+fn color_enabled() -> bool {
+    COLOR.load(Ordering::Relaxed)
+}
+
+/// Switches tables and section headers to GitHub-flavored Markdown (`--markdown`).
+pub fn set_markdown(enabled: bool) {
+    MARKDOWN.store(enabled, Ordering::Relaxed);
+}
+
+fn markdown_enabled() -> bool {
+    MARKDOWN.load(Ordering::Relaxed)
+}
+
+/// Switches JSON output to compact JSON Lines (`--ndjson`).
+pub fn set_ndjson(enabled: bool) {
+    NDJSON.store(enabled, Ordering::Relaxed);
+}
+
+fn ndjson_enabled() -> bool {
+    NDJSON.load(Ordering::Relaxed)
+}
+
+/// Prints `--json` output on a single line instead of pretty-printed
+/// (`--json-compact`).
+pub fn set_compact_json(enabled: bool) {
+    COMPACT_JSON.store(enabled, Ordering::Relaxed);
+}
+
+fn compact_json_enabled() -> bool {
+    COMPACT_JSON.load(Ordering::Relaxed)
+}
+
+/// Leaves out section headers, count footers, and success messages, so
+/// only the table or JSON is printed (`--quiet`). Warnings still go to
+/// stderr.
+pub fn set_quiet(enabled: bool) {
+    QUIET.store(enabled, Ordering::Relaxed);
+}
+
+fn quiet_enabled() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Picks the border preset for every table (`--table-style`).
+pub fn set_table_style(style: TableStyle) {
+    *TABLE_STYLE.lock().unwrap_or_else(|e| e.into_inner()) = style;
+}
+
+fn table_style() -> TableStyle {
+    *TABLE_STYLE.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Prints list results one template line per record (`--format`).
+pub fn set_format(template: Option<String>) {
+    *FORMAT.lock().unwrap_or_else(|e| e.into_inner()) = template;
+}
+
+fn format_template() -> Option<String> {
+    FORMAT.lock().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Fills `{field}` tokens in `template` from a serialized record. `{{` and
+/// `}}` are literal braces; null prints as "-" and arrays comma-joined.
+pub fn render_template(template: &str, record: &serde_json::Value) -> crate::error::Result<String> {
+    let mut line = String::new();
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                line.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                line.push('}');
+            }
+            '{' => {
+                let mut field = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => field.push(c),
+                        None => {
+                            return Err(GitorgError::Config(format!(
+                                "Unclosed '{{' in --format '{template}'"
+                            )))
+                        }
+                    }
+                }
+                let field = field.trim();
+                let value = record.get(field).ok_or_else(|| {
+                    let known: Vec<&str> = record
+                        .as_object()
+                        .map(|o| o.keys().map(String::as_str).collect())
+                        .unwrap_or_default();
+                    GitorgError::Config(format!(
+                        "Unknown field '{field}' in --format. Available: {}",
+                        known.join(", ")
+                    ))
+                })?;
+                line.push_str(&template_value(value));
+            }
+            c => line.push(c),
+        }
+    }
+    Ok(line)
+}
+
+fn template_value(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => "-".to_string(),
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .map(template_value)
+            .collect::<Vec<_>>()
+            .join(","),
+        other => other.to_string(),
+    }
+}
+
+fn paint(text: &str, style: Style) -> String {
+    if color_enabled() {
+        text.style(style).to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// Renders `values` as Unicode block characters scaled to the largest one;
+/// all zeros give a flat baseline.
+pub fn sparkline(values: &[u32]) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&v| {
+            if max == 0 {
+                BLOCKS[0]
+            } else {
+                BLOCKS[(v as usize * (BLOCKS.len() - 1) + max as usize / 2) / max as usize]
+            }
+        })
+        .collect()
+}
+
+/// A KB count as "512 KB", "1.2 MB", "3.4 GB", ...
+pub fn human_size(kb: u64) -> String {
+    const UNITS: [&str; 4] = ["MB", "GB", "TB", "PB"];
+    if kb < 1024 {
+        return format!("{kb} KB");
+    }
+    let mut size = kb as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Asks a yes/no question on stderr; anything but "y" or "yes" is a no.
+pub fn confirm(question: &str) -> bool {
+    eprint!("{question} [y/N] ");
+    std::io::stderr().flush().ok();
+    let mut line = String::new();
+    if std::io::stdin().lock().read_line(&mut line).is_err() {
+        return false;
+    }
+    is_yes(&line)
+}
+
+fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_ascii_lowercase().as_str(), "y" | "yes")
+}
+
+pub fn bold(text: &str) -> String {
+    paint(text, Style::new().bold())
+}
+
+/// Prints `data` as JSON in `json_mode` (one compact line under `--ndjson`
+/// or `--json-compact`), otherwise hands it to `render_table`.
+pub fn output<T: Serialize + ?Sized>(json_mode: bool, data: &T, render_table: impl FnOnce(&T)) {
     if json_mode {
-        match serde_json::to_string_pretty(data) {
-            Ok(json) => println!("{json}"),
-            Err(e) => eprintln!("{} Failed to serialize JSON: {e}", "error:".red().bold()),
+        let json = if ndjson_enabled() || compact_json_enabled() {
+            serde_json::to_string(data)
+        } else {
+            serde_json::to_string_pretty(data)
+        };
+        match json {
+            Ok(json) => outln!("{json}"),
+            Err(e) => error(&format!("Failed to serialize JSON: {e}")),
         }
     } else {
         render_table(data);
     }
 }
 
+/// Like [`output`] for list commands; under `--ndjson` each record is
+/// written, and flushed, as its own line, and with `--format` each record
+/// is one template line instead of a table row. Commands that can emit
+/// records as they go use a [`RecordSink`] instead.
+pub fn output_records<T: Serialize>(
+    json_mode: bool,
+    records: &[T],
+    render_table: impl FnOnce(&[T]),
+) -> crate::error::Result<()> {
+    if let Some(template) = format_template().filter(|_| !json_mode) {
+        for record in records {
+            match serde_json::to_value(record) {
+                Ok(value) => outln!("{}", render_template(&template, &value)?),
+                Err(e) => error(&format!("Failed to serialize record: {e}")),
+            }
+        }
+        return Ok(());
+    }
+    if !(json_mode && ndjson_enabled()) {
+        output(json_mode, records, render_table);
+        return Ok(());
+    }
+    for record in records {
+        write_json_line(record);
+    }
+    Ok(())
+}
+
+/// One compact JSON line, flushed so a reader downstream sees it at once.
+fn write_json_line<T: Serialize>(record: &T) {
+    match serde_json::to_string(record) {
+        Ok(json) => outln!("{json}"),
+        Err(e) => error(&format!("Failed to serialize JSON: {e}")),
+    }
+    flush_out();
+}
+
+fn flush_out() {
+    let mut output = OUTPUT.lock().unwrap_or_else(|e| e.into_inner());
+    let flushed = match output.as_mut() {
+        Some(file) => file.flush(),
+        None => std::io::stdout().flush(),
+    };
+    if let Err(e) = flushed {
+        error(&format!("Failed to write output: {e}"));
+        std::process::exit(1);
+    }
+}
+
+/// Where a list command puts each record once it's final. Under `--ndjson`
+/// with `stream` set, the record is written and flushed straight away and
+/// never held; otherwise records are kept for sorting and
+/// [`output_records`].
+pub struct RecordSink<T> {
+    stream: bool,
+    pushed: usize,
+    records: Vec<T>,
+}
+
+impl<T: Serialize> RecordSink<T> {
+    /// `stream` is whether the command can print records in the order it
+    /// finds them; it only takes effect under `--ndjson`.
+    pub fn new(json_mode: bool, stream: bool) -> Self {
+        Self {
+            stream: stream && json_mode && ndjson_enabled(),
+            pushed: 0,
+            records: Vec::new(),
+        }
+    }
+
+    /// A sink that always keeps its records, for reports built from them.
+    pub fn collecting() -> Self {
+        Self::new(false, false)
+    }
+
+    pub fn push(&mut self, record: T) {
+        self.pushed += 1;
+        if self.stream {
+            write_json_line(&record);
+        } else {
+            self.records.push(record);
+        }
+    }
+
+    /// Whether records are written as they're pushed.
+    pub fn is_streaming(&self) -> bool {
+        self.stream
+    }
+
+    /// How many records were pushed, written or kept.
+    pub fn count(&self) -> usize {
+        self.pushed
+    }
+
+    /// The kept records; empty when streaming.
+    pub fn into_records(self) -> Vec<T> {
+        self.records
+    }
+
+    /// Like [`Self::into_records`], leaving the sink to keep counting.
+    pub fn take_records(&mut self) -> Vec<T> {
+        std::mem::take(&mut self.records)
+    }
+}
+
+/// Like [`output`] for commands that produce one object; `wrap_array`
+/// (`--wrap-array`) emits it as a one-element JSON array instead.
+pub fn output_single<T: Serialize>(
+    json_mode: bool,
+    wrap_array: bool,
+    data: &T,
+    render_table: impl FnOnce(&T),
+) {
+    if json_mode && wrap_array {
+        output(true, &[data], |_| {});
+    } else {
+        output(json_mode, data, render_table);
+    }
+}
+
 pub fn new_table(headers: &[&str]) -> Table {
     let mut table = Table::new();
     table
-        .load_preset(UTF8_FULL_CONDENSED)
+        .load_preset(table_style().preset(color_enabled()))
         .set_content_arrangement(ContentArrangement::Dynamic)
         .set_header(headers);
+    if color_enabled() {
+        // Stdout is already known to be a terminal; keep cell colors even
+        // if comfy_table's own check disagrees
+        table.enforce_styling();
+    } else {
+        table.force_no_tty();
+    }
     table
 }
 
+/// A repo status cell: green "active", yellow "stale", dimmed "archived".
+/// Styled through comfy_table rather than ANSI strings so column widths
+/// stay right; plain under `--no-color`.
+pub fn status_cell(status: &str) -> Cell {
+    let cell = Cell::new(status);
+    match status {
+        "active" => cell.fg(Color::Green),
+        "stale" => cell.fg(Color::Yellow),
+        "archived" => cell.add_attribute(Attribute::Dim),
+        _ => cell,
+    }
+}
+
+/// Days since the last activity where a repo turns from green to yellow,
+/// and from yellow to red.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StalenessThresholds {
+    pub yellow_days: u64,
+    pub red_days: u64,
+}
+
+impl StalenessThresholds {
+    pub const DEFAULT: Self = Self {
+        yellow_days: 90,
+        red_days: 365,
+    };
+
+    /// `defaults.staleness_yellow_days`/`staleness_red_days`, falling back
+    /// to 90 and 365; red never comes before yellow.
+    pub fn from_config(yellow_days: Option<u64>, red_days: Option<u64>) -> Self {
+        let yellow_days = yellow_days.unwrap_or(Self::DEFAULT.yellow_days);
+        Self {
+            yellow_days,
+            red_days: red_days.unwrap_or(Self::DEFAULT.red_days).max(yellow_days),
+        }
+    }
+}
+
+/// How long a repo has gone without activity, as a color.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StalenessTier {
+    /// Green: under `yellow_days`
+    Fresh,
+    /// Yellow: from `yellow_days` up to `red_days`
+    Aging,
+    /// Red: past `red_days`, or no activity at all
+    Stale,
+}
+
+impl StalenessTier {
+    fn color(self) -> Color {
+        match self {
+            StalenessTier::Fresh => Color::Green,
+            StalenessTier::Aging => Color::Yellow,
+            StalenessTier::Stale => Color::Red,
+        }
+    }
+}
+
+/// Sets the thresholds [`staleness_cell`] colors by.
+pub fn set_staleness_thresholds(thresholds: StalenessThresholds) {
+    *STALENESS.lock().unwrap_or_else(|e| e.into_inner()) = thresholds;
+}
+
+fn staleness_thresholds() -> StalenessThresholds {
+    *STALENESS.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// The tier of a repo last active `days` ago (`None` for never).
+pub fn staleness_tier(days: Option<i64>, thresholds: StalenessThresholds) -> StalenessTier {
+    match days.map(|d| d.max(0) as u64) {
+        Some(d) if d < thresholds.yellow_days => StalenessTier::Fresh,
+        Some(d) if d <= thresholds.red_days => StalenessTier::Aging,
+        _ => StalenessTier::Stale,
+    }
+}
+
+/// A cell colored by the staleness tier of `days`; plain under
+/// `--no-color`, like [`status_cell`].
+pub fn staleness_cell(text: &str, days: Option<i64>) -> Cell {
+    Cell::new(text).fg(staleness_tier(days, staleness_thresholds()).color())
+}
+
+/// Prints a table built with [`new_table`], as Markdown under `--markdown`.
+pub fn print_table(table: &Table) {
+    if markdown_enabled() {
+        let cells = |row: &comfy_table::Row| row.cell_iter().map(|c| c.content()).collect();
+        let headers: Vec<String> = table.header().map(cells).unwrap_or_default();
+        let rows: Vec<Vec<String>> = table.row_iter().map(cells).collect();
+        out!("{}", markdown_table(&headers, &rows));
+    } else {
+        outln!("{table}");
+    }
+}
+
+/// Renders a GitHub-flavored Markdown table: a header row, a `---`
+/// separator row, then one line per row.
+pub fn markdown_table(headers: &[String], rows: &[Vec<String>]) -> String {
+    let line = |cells: &[String]| {
+        let cells: Vec<String> = cells
+            .iter()
+            .map(|c| c.replace('|', "\\|").replace('\n', " "))
+            .collect();
+        format!("| {} |\n", cells.join(" | "))
+    };
+
+    let mut out = line(headers);
+    out.push_str(&line(&vec!["---".to_string(); headers.len()]));
+    for row in rows {
+        out.push_str(&line(row));
+    }
+    out
+}
+
 pub fn section_header(title: &str) {
-    println!("\n{}", title.cyan().bold());
-    println!("{}", "─".repeat(title.len()).cyan());
+    if quiet_enabled() {
+        return;
+    }
+    if markdown_enabled() {
+        outln!("\n## {title}\n");
+        return;
+    }
+    let rule = if color_enabled() { "─" } else { "-" };
+    let style = Style::new().cyan();
+    outln!("\n{}", paint(title, style.bold()));
+    outln!("{}", paint(&rule.repeat(title.len()), style));
+}
+
+/// Prints the trailing count line, noting when `--limit` cut the list short.
+pub fn count_footer(shown: usize, total: usize, noun: &str) {
+    if quiet_enabled() {
+        return;
+    }
+    if shown < total {
+        outln!("\nShowing {shown} of {total} {noun}.");
+    } else {
+        outln!("\n{total} {noun} found.");
+    }
 }
 
 pub fn success(msg: &str) {
-    println!("{} {msg}", "✓".green().bold());
+    if quiet_enabled() {
+        return;
+    }
+    outln!("{} {msg}", paint("✓", Style::new().green().bold()));
 }
 
 pub fn warn(msg: &str) {
-    eprintln!("{} {msg}", "warning:".yellow().bold());
+    eprintln!("{} {msg}", paint("warning:", Style::new().yellow().bold()));
 }
 
 pub fn error(msg: &str) {
-    eprintln!("{} {msg}", "error:".red().bold());
+    eprintln!("{} {msg}", paint("error:", Style::new().red().bold()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sparkline_scales_to_the_busiest_week() {
+        assert_eq!(sparkline(&[0, 1, 4, 8]), "▁▂▅█");
+        assert_eq!(sparkline(&[0, 0, 0]), "▁▁▁");
+        assert_eq!(sparkline(&[]), "");
+    }
+
+    #[test]
+    fn sizes_render_in_readable_units() {
+        assert_eq!(human_size(0), "0 KB");
+        assert_eq!(human_size(512), "512 KB");
+        assert_eq!(human_size(1536), "1.5 MB");
+        assert_eq!(human_size(1_258_291), "1.2 GB");
+    }
+
+    #[test]
+    fn only_explicit_yes_confirms() {
+        for answer in ["y\n", "YES", " yes "] {
+            assert!(is_yes(answer), "{answer:?}");
+        }
+        for answer in ["", "\n", "n", "yep", "no"] {
+            assert!(!is_yes(answer), "{answer:?}");
+        }
+    }
+
+    #[test]
+    fn template_fills_fields_and_escapes_braces() {
+        let record = serde_json::json!({
+            "org": "acme",
+            "name": "tool",
+            "stars": 42,
+            "topics": ["cli", "rust"],
+            "pushed_at": null,
+        });
+        assert_eq!(
+            render_template(
+                "{org}/{name} {stars}★ [{topics}] {pushed_at} {{x}}",
+                &record
+            )
+            .unwrap(),
+            "acme/tool 42★ [cli,rust] - {x}"
+        );
+    }
+
+    #[test]
+    fn template_rejects_unknown_and_unclosed_fields() {
+        let record = serde_json::json!({ "name": "tool" });
+        let err = render_template("{nmae}", &record).unwrap_err();
+        assert!(err.to_string().contains("Available: name"), "{err}");
+        assert!(render_template("{name", &record).is_err());
+    }
+
+    #[test]
+    fn table_styles_map_to_presets() {
+        assert_eq!(TableStyle::default().preset(true), UTF8_FULL_CONDENSED);
+        assert_eq!(TableStyle::default().preset(false), ASCII_FULL_CONDENSED);
+        assert_eq!(TableStyle::Full.preset(false), ASCII_FULL);
+        assert_eq!(TableStyle::Ascii.preset(true), ASCII_FULL_CONDENSED);
+        assert_eq!(TableStyle::Borderless.preset(true), NOTHING);
+    }
+
+    #[test]
+    fn markdown_table_has_separator_row() {
+        let headers = vec!["Name".to_string(), "Stars".to_string()];
+        let rows = vec![
+            vec!["gitorg".to_string(), "42".to_string()],
+            vec!["a|b".to_string(), "0".to_string()],
+        ];
+        assert_eq!(
+            markdown_table(&headers, &rows),
+            "| Name | Stars |\n| --- | --- |\n| gitorg | 42 |\n| a\\|b | 0 |\n"
+        );
+    }
+
+    #[test]
+    fn staleness_tiers_follow_the_thresholds() {
+        let defaults = StalenessThresholds::DEFAULT;
+        assert_eq!(staleness_tier(Some(0), defaults), StalenessTier::Fresh);
+        assert_eq!(staleness_tier(Some(89), defaults), StalenessTier::Fresh);
+        assert_eq!(staleness_tier(Some(90), defaults), StalenessTier::Aging);
+        assert_eq!(staleness_tier(Some(365), defaults), StalenessTier::Aging);
+        assert_eq!(staleness_tier(Some(366), defaults), StalenessTier::Stale);
+        assert_eq!(staleness_tier(None, defaults), StalenessTier::Stale);
+
+        let custom = StalenessThresholds::from_config(Some(30), Some(10));
+        assert_eq!(custom.red_days, 30);
+        assert_eq!(staleness_tier(Some(31), custom), StalenessTier::Stale);
+        assert_eq!(
+            StalenessThresholds::from_config(None, Some(180)),
+            StalenessThresholds {
+                yellow_days: 90,
+                red_days: 180
+            }
+        );
+    }
+
+    #[test]
+    fn status_cells_are_plain_without_color() {
+        COLOR.store(false, Ordering::Relaxed);
+        let mut table = new_table(&["Status"]);
+        for status in ["active", "stale", "archived"] {
+            table.add_row(vec![status_cell(status)]);
+        }
+        table.add_row(vec![staleness_cell("2020-01-01", Some(2000))]);
+        let rendered = table.to_string();
+        assert!(rendered.contains("stale"));
+        assert!(!rendered.contains('\x1b'));
+    }
 }
@@ -21,11 +21,20 @@ pub enum GitorgError {
     #[error("TOML serialization error: {0}")]
     TomlSerialize(#[from] toml::ser::Error),
 
-    #[error("Rate limited. Resets at {0}. Please wait and retry.")]
+    #[error("Rate limited. Resets at {0}. Wait and retry, or pass --wait.")]
     RateLimited(String),
 
     #[error("Organization not found: {0}")]
     OrgNotFound(String),
+
+    #[error(
+        "Token lacks access to org {0}. A fine-grained token must be granted that org; \
+         for an SSO org, authorize the token for it."
+    )]
+    OrgAccessDenied(String),
+
+    #[error("Webhook error: {0}")]
+    Webhook(String),
 }
 
 impl From<octocrab::Error> for GitorgError {
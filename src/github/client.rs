@@ -1,12 +1,202 @@
+use super::proxy::{build_octocrab, ProxySettings};
+use super::scheduler::{Scheduler, DEFAULT_MAX_CONCURRENCY, DEFAULT_RATE_LIMIT_HEADROOM};
+use crate::cache::{page_repos, CachedPage, RepoCache};
 use crate::error::{GitorgError, Result};
 use octocrab::models::issues::Issue;
-use octocrab::models::Repository;
-use octocrab::Octocrab;
-use serde::Deserialize;
+use octocrab::models::pulls::PullRequest;
+use octocrab::models::repos::{Content, Release};
+use octocrab::models::{Milestone, Repository};
+use octocrab::{FromResponse, Octocrab, Page};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, AtomicI64, AtomicU32, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const DEFAULT_MAX_RETRIES: u32 = 3;
+pub const DEFAULT_BASE_DELAY: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: Duration = Duration::from_secs(120);
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+pub const DEFAULT_MAX_REPOS: usize = 2000;
+/// The most results the search API returns for one query.
+const SEARCH_RESULT_CAP: u64 = 1000;
+/// Extra tries for statistics GitHub is still computing (202 Accepted).
+const STATS_RETRIES: u32 = 2;
 
 pub struct GithubClient {
     octocrab: Octocrab,
     verbose: bool,
+    /// Whether the token is fine-grained, so empty org listings may mean
+    /// it wasn't granted the org rather than that the org is empty.
+    fine_grained: bool,
+    max_retries: u32,
+    base_delay: Duration,
+    timeout: Duration,
+    cache: Option<RepoCache>,
+    /// Prefixes cache keys with the API host and token the listing came from.
+    cache_scope: String,
+    max_pages: Option<u32>,
+    /// Set once any listing stops early because of `max_pages`.
+    truncated: AtomicBool,
+    /// Sleep through an exhausted primary rate limit instead of failing.
+    wait_on_rate_limit: bool,
+    /// Reset epoch of the last exhausted primary rate limit (0 if none),
+    /// picked up by `api_error` to report `RateLimited`.
+    rate_limit_reset: AtomicI64,
+    /// Requests retried because of a rate limit, and the time spent waiting.
+    retries: AtomicU32,
+    retry_wait_ms: AtomicU64,
+    /// Repos crawled from the API so far, checked against `max_repos`.
+    max_repos: Option<usize>,
+    over_repo_cap: OverCap,
+    repos_listed: AtomicUsize,
+    repo_cap: Mutex<RepoCap>,
+    /// Every request waits its turn here.
+    scheduler: Scheduler,
+}
+
+/// What to do once a run has crawled more than `max_repos` repos.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverCap {
+    /// Keep going (`--yes`)
+    Continue,
+    /// Ask on the terminal
+    Ask,
+    /// Stop listing repos
+    Stop,
+}
+
+/// Where a run stands against the repo cap; decided at most once.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RepoCap {
+    Armed,
+    Lifted,
+    Hit,
+}
+
+/// How much a run was throttled, reported by [`GithubClient::report_retries`].
+#[derive(Debug, Serialize, PartialEq)]
+pub struct RetryStats {
+    pub retries: u32,
+    pub retry_wait_secs: f64,
+}
+
+/// What a paginated listing should do after fetching a page.
+#[derive(Debug, PartialEq, Eq)]
+enum NextPage {
+    Fetch,
+    Done,
+    /// More pages exist but the `--max-pages` cap has been reached.
+    Capped,
+}
+
+fn next_page(page: u32, has_next: bool, max_pages: Option<u32>) -> NextPage {
+    if !has_next {
+        NextPage::Done
+    } else if max_pages.is_some_and(|max| page >= max) {
+        NextPage::Capped
+    } else {
+        NextPage::Fetch
+    }
+}
+
+/// The `type` filter for org repo listings.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum RepoVisibility {
+    #[default]
+    All,
+    Public,
+    Private,
+}
+
+impl RepoVisibility {
+    fn as_param(self) -> &'static str {
+        match self {
+            RepoVisibility::All => "all",
+            RepoVisibility::Public => "public",
+            RepoVisibility::Private => "private",
+        }
+    }
+}
+
+/// A repo from an org listing, plus fields octocrab's model doesn't expose.
+/// Derefs to [`Repository`] so callers can use it like the octocrab type.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrgRepo {
+    #[serde(flatten)]
+    pub repo: Repository,
+    #[serde(default)]
+    pub has_discussions: Option<bool>,
+}
+
+impl Deref for OrgRepo {
+    type Target = Repository;
+
+    fn deref(&self) -> &Repository {
+        &self.repo
+    }
+}
+
+/// Weekly commit counts for the last 52 weeks, oldest first. While GitHub is
+/// still computing them it answers 202 with an empty body, which leaves
+/// `all` empty.
+#[derive(Debug, Deserialize)]
+struct Participation {
+    #[serde(default)]
+    all: Vec<u32>,
+}
+
+/// The parts of a branch protection rule that `protection` reports.
+#[derive(Debug, Deserialize)]
+pub struct BranchProtection {
+    #[serde(default)]
+    pub required_pull_request_reviews: Option<RequiredReviews>,
+    #[serde(default)]
+    pub required_status_checks: Option<RequiredStatusChecks>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequiredReviews {
+    #[serde(default)]
+    pub required_approving_review_count: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RequiredStatusChecks {
+    #[serde(default)]
+    pub contexts: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Contributor {
+    pub login: String,
+    pub contributions: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Team {
+    pub name: String,
+    pub slug: String,
+    /// `closed` (visible to the org) or `secret`
+    pub privacy: Option<String>,
+    /// Only present on single-team responses, not in org listings.
+    #[serde(default)]
+    pub members_count: Option<u32>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Member {
+    login: String,
+}
+
+/// An org member and their role: `admin` (an owner) or `member`.
+#[derive(Debug, Clone)]
+pub struct OrgMember {
+    pub login: String,
+    pub role: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -15,49 +205,436 @@ pub struct OrgInfo {
     pub description: Option<String>,
 }
 
+/// Profile fields from `/orgs/{org}` that the org listing leaves out.
+#[derive(Debug, Deserialize)]
+pub struct OrgDetail {
+    pub name: Option<String>,
+    /// The website set on the org profile; may be empty
+    pub blog: Option<String>,
+    pub avatar_url: Option<String>,
+    pub public_repos: Option<u32>,
+}
+
 #[derive(Debug, Deserialize)]
 pub struct AuthenticatedUser {
     pub login: String,
     pub name: Option<String>,
+    /// OAuth scopes from `X-OAuth-Scopes`; `None` for tokens that don't
+    /// report any (fine-grained and app tokens).
+    #[serde(skip)]
+    pub scopes: Option<Vec<String>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RateLimit {
     pub resources: RateLimitResources,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RateLimitResources {
     pub core: RateLimitResource,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub search: Option<RateLimitResource>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub graphql: Option<RateLimitResource>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct RateLimitResource {
     pub limit: u64,
     pub remaining: u64,
     pub reset: i64,
 }
 
+impl RateLimitResource {
+    /// The reset time as `HH:MM:SS UTC`, or the raw epoch if it's out of range.
+    pub fn reset_time(&self) -> String {
+        format_reset(self.reset)
+    }
+}
+
+pub(super) fn format_reset(epoch: i64) -> String {
+    chrono::DateTime::from_timestamp(epoch, 0)
+        .map(|dt| dt.format("%H:%M:%S UTC").to_string())
+        .unwrap_or_else(|| epoch.to_string())
+}
+
 impl GithubClient {
-    pub fn new(token: &str, verbose: bool) -> Result<Self> {
-        let octocrab = Octocrab::builder()
-            .personal_token(token.to_string())
-            .build()
-            .map_err(|e| GitorgError::GitHub(e.to_string()))?;
-        Ok(Self { octocrab, verbose })
+    /// `timeout` bounds connecting to GitHub and each wait for response data.
+    /// Requests go through the proxies in `proxy`, if any.
+    pub fn new(
+        token: &str,
+        base_url: Option<&str>,
+        verbose: bool,
+        timeout: Duration,
+        proxy: ProxySettings,
+    ) -> Result<Self> {
+        let builder = Octocrab::builder()
+            .set_connect_timeout(Some(timeout))
+            .set_read_timeout(Some(timeout))
+            .set_write_timeout(Some(timeout));
+        let octocrab = if proxy.is_empty() {
+            let mut builder = builder.personal_token(token.to_string());
+            if let Some(base_url) = base_url {
+                builder = builder.base_uri(base_url).map_err(|e| {
+                    GitorgError::Config(format!("Invalid API URL '{base_url}': {e}"))
+                })?;
+            }
+            builder
+                .build()
+                .map_err(|e| GitorgError::GitHub(e.to_string()))?
+        } else {
+            if verbose {
+                let api = base_url.unwrap_or("https://api.github.com").parse().ok();
+                match api.as_ref().and_then(|uri| proxy.proxy_for(uri)) {
+                    Some(through) => eprintln!("Using proxy {through}"),
+                    None => eprintln!("Not using a proxy: the API host is in NO_PROXY"),
+                }
+            }
+            build_octocrab(builder, Some(token), base_url, proxy)?
+        };
+        Ok(Self {
+            octocrab,
+            verbose,
+            fine_grained: is_fine_grained(token),
+            max_retries: DEFAULT_MAX_RETRIES,
+            base_delay: DEFAULT_BASE_DELAY,
+            timeout,
+            cache: None,
+            cache_scope: cache_scope(token, base_url),
+            max_pages: None,
+            truncated: AtomicBool::new(false),
+            wait_on_rate_limit: false,
+            rate_limit_reset: AtomicI64::new(0),
+            retries: AtomicU32::new(0),
+            retry_wait_ms: AtomicU64::new(0),
+            max_repos: None,
+            over_repo_cap: OverCap::Stop,
+            repos_listed: AtomicUsize::new(0),
+            repo_cap: Mutex::new(RepoCap::Armed),
+            scheduler: Scheduler::new(DEFAULT_MAX_CONCURRENCY, DEFAULT_RATE_LIMIT_HEADROOM),
+        })
+    }
+
+    /// Caps requests in flight at `max_concurrency`, fewer as the core rate
+    /// limit runs down towards `headroom` requests.
+    pub fn with_concurrency(mut self, max_concurrency: usize, headroom: u32) -> Self {
+        self.scheduler = Scheduler::new(max_concurrency, headroom);
+        self
+    }
+
+    /// How many per-repo requests commands should keep queued at once; the
+    /// scheduler decides how many actually run.
+    pub fn max_concurrency(&self) -> usize {
+        self.scheduler.max_concurrency()
+    }
+
+    /// Guards against runaway crawls: once org repo listings pass
+    /// `max_repos` repos in total, `over_cap` decides whether to carry on.
+    /// Cached listings don't count.
+    pub fn with_max_repos(mut self, max_repos: Option<usize>, over_cap: OverCap) -> Self {
+        self.max_repos = max_repos;
+        self.over_repo_cap = over_cap;
+        self
+    }
+
+    /// Counts `listed` more crawled repos, and returns whether listing may
+    /// continue. The first time the cap is passed decides for the whole run.
+    fn within_repo_cap(&self, listed: usize) -> bool {
+        let Some(max) = self.max_repos else {
+            return true;
+        };
+        let total = self.repos_listed.fetch_add(listed, Ordering::Relaxed) + listed;
+        if total <= max {
+            return true;
+        }
+        let mut cap = self.repo_cap.lock().unwrap_or_else(|e| e.into_inner());
+        if *cap == RepoCap::Armed {
+            let go_on = match self.over_repo_cap {
+                OverCap::Continue => true,
+                OverCap::Stop => false,
+                OverCap::Ask => crate::display::confirm(&format!(
+                    "Listed {total} repos, past the --max-repos cap of {max}. Keep going?"
+                )),
+            };
+            *cap = if go_on { RepoCap::Lifted } else { RepoCap::Hit };
+        }
+        *cap == RepoCap::Lifted
+    }
+
+    fn repo_cap_hit(&self) -> bool {
+        *self.repo_cap.lock().unwrap_or_else(|e| e.into_inner()) == RepoCap::Hit
+    }
+
+    /// When the primary rate limit runs out, sleep until it resets and
+    /// carry on rather than failing with `RateLimited`.
+    pub fn with_wait_on_rate_limit(mut self, wait: bool) -> Self {
+        self.wait_on_rate_limit = wait;
+        self
+    }
+
+    /// Stops repo, issue, contributor, and org listings after `max_pages`
+    /// pages of 100.
+    pub fn with_max_pages(mut self, max_pages: Option<u32>) -> Self {
+        self.max_pages = max_pages;
+        self
+    }
+
+    /// Decides whether to fetch the page after `page`, recording when the
+    /// `max_pages` cap cuts a listing short.
+    fn next_page(&self, page: u32, has_next: bool) -> NextPage {
+        let next = next_page(page, has_next, self.max_pages);
+        if next == NextPage::Capped {
+            self.truncated.store(true, Ordering::Relaxed);
+        }
+        next
+    }
+
+    /// In verbose mode, notes each page of a long crawl so it doesn't look
+    /// hung.
+    fn page_progress(&self, label: &str, page: u32, so_far: usize, noun: &str) {
+        if self.verbose {
+            eprintln!("{}", progress_line(label, page, so_far, noun));
+        }
+    }
+
+    pub fn is_verbose(&self) -> bool {
+        self.verbose
+    }
+
+    /// Warns if any listing was cut short by `--max-pages` or `--max-repos`.
+    pub fn warn_if_truncated(&self) {
+        if let Some(max) = self.max_pages {
+            if self.truncated.load(Ordering::Relaxed) {
+                crate::display::warn(&format!("Results truncated at {max} page(s)"));
+            }
+        }
+        if let Some(max) = self.max_repos.filter(|_| self.repo_cap_hit()) {
+            crate::display::warn(&format!(
+                "Stopped listing repos after passing {max} (--max-repos); \
+                 raise it or pass --yes to list them all"
+            ));
+        }
+    }
+
+    /// Serves `list_org_repos` from `cache` while entries are fresh.
+    pub fn with_cache(mut self, cache: RepoCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Fetches every org listing afresh, as `--no-cache` does, still
+    /// refreshing the cache for later runs.
+    pub fn with_fresh_listings(mut self) -> Self {
+        self.cache = self.cache.map(RepoCache::write_only);
+        self
+    }
+
+    /// Overrides how many times a request is retried after a secondary rate
+    /// limit response (`--retry-on-abuse`).
+    pub fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Overrides the wait before the first retry of a rate-limited request
+    /// that gave no `Retry-After`; each later retry waits twice as long
+    /// (`--retry-delay`).
+    pub fn with_retry_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    /// Sleeps before retrying a rate-limited request, keeping count.
+    async fn wait_to_retry(&self, delay: Duration) {
+        self.retries.fetch_add(1, Ordering::Relaxed);
+        self.retry_wait_ms
+            .fetch_add(delay.as_millis() as u64, Ordering::Relaxed);
+        tokio::time::sleep(delay).await;
+    }
+
+    pub fn retry_stats(&self) -> RetryStats {
+        RetryStats {
+            retries: self.retries.load(Ordering::Relaxed),
+            retry_wait_secs: self.retry_wait_ms.load(Ordering::Relaxed) as f64 / 1000.0,
+        }
+    }
+
+    /// Reports rate-limit retries once a command is done: under `--json` as
+    /// a one-line `{"api": {...}}` object on stderr, so stdout stays the
+    /// data; otherwise only with `--verbose`. Silent when nothing was
+    /// retried.
+    pub fn report_retries(&self, json: bool) {
+        let stats = self.retry_stats();
+        if stats.retries == 0 {
+            return;
+        }
+        if json {
+            eprintln!("{}", serde_json::json!({ "api": stats }));
+        } else if self.verbose {
+            eprintln!(
+                "Retried {} request(s) after rate limits, waiting {:.1}s in total",
+                stats.retries, stats.retry_wait_secs
+            );
+        }
+    }
+
+    /// Sends a GET request, backing off and retrying when GitHub answers with
+    /// a secondary rate limit (403/429). A `Retry-After` header takes
+    /// precedence over the exponential delay.
+    async fn get_with_retry<T: FromResponse>(&self, route: &str) -> Result<T> {
+        self.fetch(route).await.map_err(|e| self.api_error(e))
+    }
+
+    /// Converts an octocrab error, naming the timeout or the exhausted rate
+    /// limit when that's what hit.
+    fn api_error(&self, err: octocrab::Error) -> GitorgError {
+        if matches!(status_of(&err), Some(403 | 429)) {
+            let reset = self.rate_limit_reset.swap(0, Ordering::Relaxed);
+            if reset != 0 {
+                return GitorgError::RateLimited(format_reset(reset));
+            }
+        }
+        if is_timeout(&err) {
+            GitorgError::GitHub(format!(
+                "request timed out after {}s",
+                self.timeout.as_secs()
+            ))
+        } else {
+            err.into()
+        }
+    }
+
+    /// Like `get_with_retry`, but a 404 yields `None` instead of an error.
+    async fn get_optional<T: FromResponse>(&self, route: &str) -> Result<Option<T>> {
+        match self.fetch(route).await {
+            Ok(value) => Ok(Some(value)),
+            Err(err) if status_of(&err) == Some(404) => Ok(None),
+            Err(err) => Err(self.api_error(err)),
+        }
+    }
+
+    async fn fetch<T: FromResponse>(&self, route: &str) -> octocrab::Result<T> {
+        match self.fetch_if_changed(route, None).await? {
+            Some((value, _)) => Ok(value),
+            None => unreachable!("304 without If-None-Match"),
+        }
+    }
+
+    /// Sends a conditional GET when `etag` is given. Returns `None` if GitHub
+    /// answers 304 Not Modified, otherwise the body and its new ETag.
+    async fn fetch_if_changed<T: FromResponse>(
+        &self,
+        route: &str,
+        etag: Option<&str>,
+    ) -> octocrab::Result<Option<(T, Option<String>)>> {
+        let headers = etag
+            .and_then(|etag| http::HeaderValue::from_str(etag).ok())
+            .map(|value| {
+                let mut headers = http::HeaderMap::new();
+                headers.insert(http::header::IF_NONE_MATCH, value);
+                headers
+            });
+        let mut attempt = 0u32;
+        loop {
+            let _slot = self.scheduler.acquire(self.wait_on_rate_limit).await;
+            let response = self
+                .octocrab
+                ._get_with_headers(route, headers.clone())
+                .await?;
+            self.scheduler.record(response.headers());
+            if headers.is_some() && response.status() == http::StatusCode::NOT_MODIFIED {
+                return Ok(None);
+            }
+            let retry_after = retry_after(response.headers());
+            let rate_limit_reset =
+                primary_rate_limit_reset(response.status().as_u16(), response.headers());
+
+            let err = match octocrab::map_github_error(response).await {
+                Ok(response) => {
+                    let etag = response
+                        .headers()
+                        .get(http::header::ETAG)
+                        .and_then(|v| v.to_str().ok())
+                        .map(str::to_string);
+                    return Ok(Some((T::from_response(response).await?, etag)));
+                }
+                Err(err) => err,
+            };
+
+            if let Some(reset) = rate_limit_reset {
+                if !self.wait_on_rate_limit {
+                    self.rate_limit_reset.store(reset, Ordering::Relaxed);
+                    return Err(err);
+                }
+                crate::display::warn(&format!(
+                    "API rate limit exhausted; waiting until {} to resume",
+                    format_reset(reset)
+                ));
+                self.wait_to_retry(wait_until(reset, chrono::Utc::now().timestamp()))
+                    .await;
+                continue;
+            }
+
+            let retryable = match &err {
+                octocrab::Error::GitHub { source, .. } => is_secondary_rate_limit(
+                    source.status_code.as_u16(),
+                    &source.message,
+                    retry_after.is_some(),
+                ),
+                _ => false,
+            };
+            if !retryable || attempt >= self.max_retries {
+                return Err(err);
+            }
+
+            let delay = retry_after.unwrap_or_else(|| backoff_delay(self.base_delay, attempt));
+            attempt += 1;
+            if self.verbose {
+                eprintln!(
+                    "Secondary rate limit on {route}; retrying in {}s (attempt {attempt}/{})",
+                    delay.as_secs(),
+                    self.max_retries
+                );
+            }
+            self.wait_to_retry(delay).await;
+        }
     }
 
+    /// Fetches the token's user, along with the scopes GitHub reports for it.
     pub async fn validate_token(&self) -> Result<AuthenticatedUser> {
-        let user: AuthenticatedUser = self
-            .octocrab
-            .get("/user", None::<&()>)
-            .await
-            .map_err(|e| GitorgError::GitHub(format!("Token validation failed: {e}")))?;
-        Ok(user)
+        let fetch = async {
+            let _slot = self.scheduler.acquire(self.wait_on_rate_limit).await;
+            let response = self.octocrab._get("/user").await?;
+            self.scheduler.record(response.headers());
+            let response = octocrab::map_github_error(response).await?;
+            let scopes = response
+                .headers()
+                .get("x-oauth-scopes")
+                .and_then(|v| v.to_str().ok())
+                .map(parse_scopes);
+            let mut user = AuthenticatedUser::from_response(response).await?;
+            user.scopes = scopes;
+            Ok::<_, octocrab::Error>(user)
+        };
+        fetch.await.map_err(|e| match self.api_error(e) {
+            GitorgError::GitHub(msg) => {
+                GitorgError::GitHub(format!("Token validation failed: {msg}"))
+            }
+            other => other,
+        })
     }
 
     pub async fn get_rate_limit(&self) -> Result<RateLimit> {
-        let rate_limit: RateLimit = self.octocrab.get("/rate_limit", None::<&()>).await?;
+        let rate_limit: RateLimit = self
+            .octocrab
+            .get("/rate_limit", None::<&()>)
+            .await
+            .map_err(|e| self.api_error(e))?;
+        // Free to ask, and gives the scheduler a budget before any listing
+        let core = &rate_limit.resources.core;
+        self.scheduler
+            .update(core.remaining.min(u32::MAX as u64) as u32, core.reset);
         Ok(rate_limit)
     }
 
@@ -72,9 +649,7 @@ impl GithubClient {
                     "Rate limit: {}/{} remaining (resets at {})",
                     core.remaining,
                     core.limit,
-                    chrono::DateTime::from_timestamp(core.reset, 0)
-                        .map(|dt| dt.format("%H:%M:%S UTC").to_string())
-                        .unwrap_or_else(|| core.reset.to_string())
+                    core.reset_time()
                 );
             }
             Err(e) => eprintln!("Could not check rate limit: {e}"),
@@ -87,85 +662,1882 @@ impl GithubClient {
             crate::display::warn(&format!(
                 "Only {} API calls remaining (resets at {})",
                 rl.resources.core.remaining,
-                chrono::DateTime::from_timestamp(rl.resources.core.reset, 0)
-                    .map(|dt| dt.format("%H:%M:%S UTC").to_string())
-                    .unwrap_or_else(|| rl.resources.core.reset.to_string())
+                rl.resources.core.reset_time()
             ));
         }
         Ok(())
     }
 
-    pub async fn list_org_repos(&self, org: &str) -> Result<Vec<Repository>> {
-        let mut all_repos = Vec::new();
+    pub async fn list_org_repos(
+        &self,
+        org: &str,
+        visibility: RepoVisibility,
+    ) -> Result<Vec<OrgRepo>> {
+        let repo_type = visibility.as_param();
+        let cache_key = format!("{}.{org}.{repo_type}", self.cache_scope);
+        if let Some((repos, age)) = self.cache.as_ref().and_then(|c| c.load(&cache_key)) {
+            if self.verbose {
+                eprintln!("Using cached repos for {org} ({}m old)", age.num_minutes());
+            }
+            return Ok(repos);
+        }
+        if self.repo_cap_hit() {
+            return Ok(Vec::new());
+        }
+
+        // An expired entry still carries ETags; unchanged pages come back as
+        // 304s, which don't count against the rate limit.
+        let previous = self
+            .cache
+            .as_ref()
+            .and_then(|c| c.load_pages(&cache_key))
+            .unwrap_or_default();
+        let mut pages: Vec<CachedPage> = Vec::new();
+        let mut not_modified = 0usize;
+        let mut complete = true;
         let mut page = 1u32;
         loop {
-            let page_result = self
-                .octocrab
-                .orgs(org)
-                .list_repos()
-                .repo_type(octocrab::params::repos::Type::All)
-                .per_page(100)
-                .page(page)
-                .send()
+            let cached = previous.get(page as usize - 1);
+            let fetched: Option<(Page<OrgRepo>, Option<String>)> = self
+                .fetch_if_changed(
+                    &format!("/orgs/{org}/repos?type={repo_type}&per_page=100&page={page}"),
+                    cached.and_then(|p| p.etag.as_deref()),
+                )
+                .await
+                .map_err(|e| org_error(org, status_of(&e), self.api_error(e)))?;
+
+            let current = match (fetched, cached) {
+                (Some((page_result, etag)), _) => CachedPage {
+                    etag,
+                    has_next: page_result.next.is_some(),
+                    repos: page_result.items,
+                },
+                (None, Some(cached)) => {
+                    not_modified += 1;
+                    cached.clone()
+                }
+                (None, None) => unreachable!("304 without If-None-Match"),
+            };
+            if current.repos.is_empty() {
+                break;
+            }
+            let has_next = current.has_next;
+            let listed = current.repos.len();
+            pages.push(current);
+            let so_far = pages.iter().map(|p| p.repos.len()).sum();
+            self.page_progress(&format!("org {org}"), page, so_far, "repos");
+            if !self.within_repo_cap(listed) {
+                complete = !has_next;
+                break;
+            }
+            match self.next_page(page, has_next) {
+                NextPage::Fetch => page += 1,
+                NextPage::Done => break,
+                NextPage::Capped => {
+                    complete = false;
+                    break;
+                }
+            }
+        }
+
+        if self.verbose && not_modified > 0 {
+            eprintln!(
+                "{not_modified} of {} repo page(s) for {org} unchanged since last fetch",
+                pages.len()
+            );
+        }
+
+        // A capped listing would later be served as if it were the whole org
+        if let Some(cache) = self.cache.as_ref().filter(|_| complete) {
+            if let Err(e) = cache.store(&cache_key, &pages) {
+                if self.verbose {
+                    eprintln!("Could not write repo cache for {org}: {e}");
+                }
+            }
+        }
+
+        let repos = page_repos(&pages);
+        if repos.is_empty() && self.fine_grained {
+            crate::display::warn(&format!(
+                "No repos visible in {org}; the fine-grained token may not have been granted it"
+            ));
+        }
+        Ok(repos)
+    }
+
+    /// Whether the token can see any repo in `org`, from a single-repo
+    /// listing. An org with no repos looks the same as one the token can't
+    /// reach.
+    pub async fn can_see_org_repos(&self, org: &str) -> Result<bool> {
+        match self
+            .fetch::<Page<serde::de::IgnoredAny>>(&format!("/orgs/{org}/repos?type=all&per_page=1"))
+            .await
+        {
+            Ok(page) => Ok(!page.items.is_empty()),
+            Err(e) => match org_error(org, status_of(&e), self.api_error(e)) {
+                GitorgError::OrgAccessDenied(_) => Ok(false),
+                err => Err(err),
+            },
+        }
+    }
+
+    /// Lists open issues. `milestone` is passed through to the API's
+    /// `milestone` parameter (a milestone number, `none`, or `*`).
+    pub async fn list_repo_issues(
+        &self,
+        owner: &str,
+        repo: &str,
+        milestone: Option<&str>,
+    ) -> Result<Vec<Issue>> {
+        let milestone_param = milestone
+            .map(|m| format!("&milestone={m}"))
+            .unwrap_or_default();
+        let mut all_issues = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let page_result: Page<Issue> = self
+                .get_with_retry(&format!(
+                    "/repos/{owner}/{repo}/issues?state=open{milestone_param}&per_page=100&page={page}"
+                ))
                 .await?;
 
             let items = page_result.items;
             if items.is_empty() {
                 break;
             }
-            all_repos.extend(items);
-            if page_result.next.is_none() {
+            all_issues.extend(items);
+            self.page_progress(&format!("{owner}/{repo}"), page, all_issues.len(), "issues");
+            if self.next_page(page, page_result.next.is_some()) != NextPage::Fetch {
                 break;
             }
             page += 1;
         }
-        Ok(all_repos)
+        Ok(all_issues)
     }
 
-    pub async fn list_repo_issues(&self, owner: &str, repo: &str) -> Result<Vec<Issue>> {
-        let mut all_issues = Vec::new();
+    /// Lists an org's teams. Listings omit `members_count`; see [`Self::get_team`].
+    pub async fn list_org_teams(&self, org: &str) -> Result<Vec<Team>> {
+        let mut all_teams = Vec::new();
         let mut page = 1u32;
         loop {
-            let page_result = self
-                .octocrab
-                .issues(owner, repo)
-                .list()
-                .state(octocrab::params::State::Open)
-                .per_page(100)
-                .page(page)
-                .send()
+            let page_result: Page<Team> = self
+                .fetch(&format!("/orgs/{org}/teams?per_page=100&page={page}"))
+                .await
+                .map_err(|e| org_error(org, status_of(&e), self.api_error(e)))?;
+
+            let items = page_result.items;
+            if items.is_empty() {
+                break;
+            }
+            all_teams.extend(items);
+            self.page_progress(&format!("org {org}"), page, all_teams.len(), "teams");
+            if self.next_page(page, page_result.next.is_some()) != NextPage::Fetch {
+                break;
+            }
+            page += 1;
+        }
+        Ok(all_teams)
+    }
+
+    pub async fn get_team(&self, org: &str, slug: &str) -> Result<Team> {
+        self.get_with_retry(&format!("/orgs/{org}/teams/{slug}"))
+            .await
+    }
+
+    /// Every member of `org` with their role, owners first. Tokens outside
+    /// the org only see its public members.
+    pub async fn list_org_members(&self, org: &str) -> Result<Vec<OrgMember>> {
+        let mut members = Vec::new();
+        for role in ["admin", "member"] {
+            let logins = self
+                .list_members(org, &format!("role={role}"))
+                .await
+                .map_err(|(status, err)| match (status, err) {
+                    (_, err @ GitorgError::RateLimited(_)) => err,
+                    (Some(403), _) => GitorgError::GitHub(format!(
+                        "Not allowed to list members of {org}; the token needs the read:org scope"
+                    )),
+                    (status, err) => org_error(org, status, err),
+                })?;
+            members.extend(logins.into_iter().map(|login| OrgMember {
+                login,
+                role: role.to_string(),
+            }));
+        }
+        Ok(members)
+    }
+
+    /// Logins of members without two-factor authentication, or `None` when
+    /// the token can't tell (only org owners may ask).
+    pub async fn list_members_without_2fa(&self, org: &str) -> Result<Option<Vec<String>>> {
+        match self.list_members(org, "filter=2fa_disabled").await {
+            Ok(logins) => Ok(Some(logins)),
+            Err((_, err @ GitorgError::RateLimited(_))) => Err(err),
+            Err((Some(403 | 422), _)) => Ok(None),
+            Err((status, err)) => Err(org_error(org, status, err)),
+        }
+    }
+
+    /// The org's profile; an unknown org is `OrgNotFound`.
+    pub async fn get_org(&self, org: &str) -> Result<OrgDetail> {
+        self.fetch(&format!("/orgs/{org}"))
+            .await
+            .map_err(|e| org_error(org, status_of(&e), self.api_error(e)))
+    }
+
+    /// How many members `org` has, from a one-per-page listing's last page
+    /// number. `None` if the token may not list them.
+    pub async fn count_org_members(&self, org: &str) -> Result<Option<u32>> {
+        let page: Page<Member> = match self.fetch(&format!("/orgs/{org}/members?per_page=1")).await
+        {
+            Ok(page) => page,
+            Err(e) => {
+                let status = status_of(&e);
+                return match self.api_error(e) {
+                    err @ GitorgError::RateLimited(_) => Err(err),
+                    _ if status == Some(403) => Ok(None),
+                    err => Err(org_error(org, status, err)),
+                };
+            }
+        };
+        Ok(Some(
+            page.number_of_pages().unwrap_or(page.items.len() as u32),
+        ))
+    }
+
+    /// Pages through `/orgs/{org}/members?{query}`, keeping the status of a
+    /// failed request so callers can explain permission errors.
+    async fn list_members(
+        &self,
+        org: &str,
+        query: &str,
+    ) -> std::result::Result<Vec<String>, (Option<u16>, GitorgError)> {
+        let mut logins = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let page_result: Page<Member> = self
+                .fetch(&format!(
+                    "/orgs/{org}/members?{query}&per_page=100&page={page}"
+                ))
+                .await
+                .map_err(|e| (status_of(&e), self.api_error(e)))?;
+
+            let items = page_result.items;
+            if items.is_empty() {
+                break;
+            }
+            logins.extend(items.into_iter().map(|m| m.login));
+            self.page_progress(&format!("org {org}"), page, logins.len(), "members");
+            if self.next_page(page, page_result.next.is_some()) != NextPage::Fetch {
+                break;
+            }
+            page += 1;
+        }
+        Ok(logins)
+    }
+
+    /// Lists open pull requests.
+    pub async fn list_repo_pulls(&self, owner: &str, repo: &str) -> Result<Vec<PullRequest>> {
+        let mut all_pulls = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let page_result: Page<PullRequest> = self
+                .get_with_retry(&format!(
+                    "/repos/{owner}/{repo}/pulls?state=open&per_page=100&page={page}"
+                ))
                 .await?;
 
             let items = page_result.items;
             if items.is_empty() {
                 break;
             }
-            all_issues.extend(items);
-            if page_result.next.is_none() {
+            all_pulls.extend(items);
+            self.page_progress(
+                &format!("{owner}/{repo}"),
+                page,
+                all_pulls.len(),
+                "pull requests",
+            );
+            if self.next_page(page, page_result.next.is_some()) != NextPage::Fetch {
                 break;
             }
             page += 1;
         }
-        Ok(all_issues)
+        Ok(all_pulls)
     }
 
-    pub async fn list_user_orgs(&self) -> Result<Vec<OrgInfo>> {
-        let mut all_orgs = Vec::new();
+    /// Lists contributors with their commit counts on the default branch.
+    /// Empty repos have none (the API answers 204), so skip them upstream.
+    pub async fn list_repo_contributors(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<Vec<Contributor>> {
+        let mut all_contributors = Vec::new();
         let mut page = 1u32;
         loop {
-            let orgs: Vec<OrgInfo> = self
-                .octocrab
-                .get(
-                    "/user/orgs",
-                    Some(&[("per_page", "100"), ("page", &page.to_string())]),
-                )
+            let page_result: Page<Contributor> = self
+                .get_with_retry(&format!(
+                    "/repos/{owner}/{repo}/contributors?per_page=100&page={page}"
+                ))
                 .await?;
-            if orgs.is_empty() {
+
+            let items = page_result.items;
+            if items.is_empty() {
+                break;
+            }
+            all_contributors.extend(items);
+            self.page_progress(
+                &format!("{owner}/{repo}"),
+                page,
+                all_contributors.len(),
+                "contributors",
+            );
+            if self.next_page(page, page_result.next.is_some()) != NextPage::Fetch {
                 break;
             }
-            all_orgs.extend(orgs);
             page += 1;
         }
-        Ok(all_orgs)
+        Ok(all_contributors)
+    }
+
+    /// Runs an issue search scoped to `org`, following pages. `None` when
+    /// more than the API's 1,000-result cap match, since the rest can't be
+    /// reached. GitHub answers 422 for an org it can't search, which is
+    /// nearly always a misspelt one, so that's `OrgNotFound`.
+    pub async fn search_org_issues(&self, org: &str, query: &str) -> Result<Option<Vec<Issue>>> {
+        let query = encode_query(query);
+        let mut all_issues = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let page_result: Page<Issue> = self
+                .fetch(&format!(
+                    "/search/issues?q={query}&sort=updated&order=desc&per_page=100&page={page}"
+                ))
+                .await
+                .map_err(|e| match status_of(&e) {
+                    Some(422) => GitorgError::OrgNotFound(org.to_string()),
+                    status => org_error(org, status, self.api_error(e)),
+                })?;
+            if page_result.total_count.unwrap_or(0) > SEARCH_RESULT_CAP {
+                return Ok(None);
+            }
+
+            let items = page_result.items;
+            if items.is_empty() {
+                break;
+            }
+            all_issues.extend(items);
+            self.page_progress("issue search", page, all_issues.len(), "issues");
+            if self.next_page(page, page_result.next.is_some()) != NextPage::Fetch {
+                break;
+            }
+            page += 1;
+        }
+        Ok(Some(all_issues))
+    }
+
+    pub async fn list_repo_milestones(&self, owner: &str, repo: &str) -> Result<Vec<Milestone>> {
+        let mut all_milestones = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let page_result: Page<Milestone> = self
+                .get_with_retry(&format!(
+                    "/repos/{owner}/{repo}/milestones?state=all&per_page=100&page={page}"
+                ))
+                .await?;
+
+            let items = page_result.items;
+            if items.is_empty() {
+                break;
+            }
+            all_milestones.extend(items);
+            self.page_progress(
+                &format!("{owner}/{repo}"),
+                page,
+                all_milestones.len(),
+                "milestones",
+            );
+            if self.next_page(page, page_result.next.is_some()) != NextPage::Fetch {
+                break;
+            }
+            page += 1;
+        }
+        Ok(all_milestones)
+    }
+
+    pub async fn get_repo(&self, owner: &str, repo: &str) -> Result<Repository> {
+        self.get_optional(&format!("/repos/{owner}/{repo}"))
+            .await?
+            .ok_or_else(|| GitorgError::GitHub(format!("Repository not found: {owner}/{repo}")))
+    }
+
+    /// Archives or unarchives a repo, returning it as updated. Needs admin
+    /// rights on the repo.
+    pub async fn set_repo_archived(
+        &self,
+        owner: &str,
+        repo: &str,
+        archived: bool,
+    ) -> Result<Repository> {
+        let body = serde_json::json!({ "archived": archived });
+        let patch = async {
+            let _slot = self.scheduler.acquire(self.wait_on_rate_limit).await;
+            let response = self
+                .octocrab
+                ._patch(format!("/repos/{owner}/{repo}"), Some(&body))
+                .await?;
+            self.scheduler.record(response.headers());
+            let response = octocrab::map_github_error(response).await?;
+            Repository::from_response(response).await
+        };
+        let updated = patch.await.map_err(|e| match status_of(&e) {
+            Some(404) => GitorgError::GitHub(format!("Repository not found: {owner}/{repo}")),
+            Some(403) => GitorgError::GitHub(format!(
+                "Not allowed to change {owner}/{repo}; archiving needs admin access"
+            )),
+            _ => self.api_error(e),
+        })?;
+        // Cached listings would keep showing the old state until they expire
+        self.forget_org_repos(owner);
+        Ok(updated)
+    }
+
+    /// Drops every cached listing of `org` for this host and token.
+    fn forget_org_repos(&self, org: &str) {
+        let Some(cache) = &self.cache else {
+            return;
+        };
+        for visibility in <RepoVisibility as clap::ValueEnum>::value_variants() {
+            let key = format!("{}.{org}.{}", self.cache_scope, visibility.as_param());
+            if let Err(e) = cache.remove(&key) {
+                if self.verbose {
+                    eprintln!("Could not clear repo cache for {org}: {e}");
+                }
+            }
+        }
+    }
+
+    /// Counts open Dependabot alerts. `None` when alerts are disabled on the
+    /// repo or the token can't see them (GitHub answers 403 or 404).
+    pub async fn count_dependabot_alerts(&self, owner: &str, repo: &str) -> Result<Option<u32>> {
+        // Alerts paginate by cursor, so follow the `next` links
+        let mut route = format!("/repos/{owner}/{repo}/dependabot/alerts?state=open&per_page=100");
+        let mut count = 0u32;
+        let mut page = 1u32;
+        loop {
+            let page_result: Page<serde::de::IgnoredAny> = match self.fetch(&route).await {
+                Ok(page_result) => page_result,
+                Err(e) => {
+                    let status = status_of(&e);
+                    return match self.api_error(e) {
+                        err @ GitorgError::RateLimited(_) => Err(err),
+                        _ if matches!(status, Some(403 | 404)) => Ok(None),
+                        err => Err(err),
+                    };
+                }
+            };
+
+            count += page_result.items.len() as u32;
+            self.page_progress(
+                &format!("{owner}/{repo}"),
+                page,
+                count as usize,
+                "Dependabot alerts",
+            );
+            let Some(next) = page_result.next else {
+                break;
+            };
+            if page_result.items.is_empty() || self.next_page(page, true) != NextPage::Fetch {
+                break;
+            }
+            route = next.to_string();
+            page += 1;
+        }
+        Ok(Some(count))
+    }
+
+    /// The protection rule on `branch`, or `None` when it isn't protected.
+    /// Reading it needs admin access to the repo.
+    pub async fn get_branch_protection(
+        &self,
+        owner: &str,
+        repo: &str,
+        branch: &str,
+    ) -> Result<Option<BranchProtection>> {
+        let route = format!("/repos/{owner}/{repo}/branches/{branch}/protection");
+        match self.fetch(&route).await {
+            Ok(protection) => Ok(Some(protection)),
+            Err(e) => {
+                let status = status_of(&e);
+                match self.api_error(e) {
+                    err @ GitorgError::RateLimited(_) => Err(err),
+                    _ if status == Some(404) => Ok(None),
+                    _ if status == Some(403) => Err(GitorgError::GitHub(format!(
+                        "Reading branch protection on {owner}/{repo} needs admin access"
+                    ))),
+                    err => Err(err),
+                }
+            }
+        }
+    }
+
+    /// Weekly commit counts for the last year, oldest first. GitHub computes
+    /// these lazily, so a cold repo is retried a couple of times; `None` if
+    /// they still aren't ready or the repo is gone.
+    pub async fn get_commit_activity(&self, owner: &str, repo: &str) -> Result<Option<Vec<u32>>> {
+        let route = format!("/repos/{owner}/{repo}/stats/participation");
+        for attempt in 0..=STATS_RETRIES {
+            let Some(participation) = self.get_optional::<Participation>(&route).await? else {
+                return Ok(None);
+            };
+            if !participation.all.is_empty() {
+                return Ok(Some(participation.all));
+            }
+            if attempt < STATS_RETRIES {
+                if self.verbose {
+                    eprintln!("Commit stats for {owner}/{repo} are still being computed; retrying");
+                }
+                tokio::time::sleep(backoff_delay(self.base_delay, attempt)).await;
+            }
+        }
+        Ok(None)
+    }
+
+    /// Bytes of code per language, as GitHub's linguist counts them.
+    pub async fn get_repo_languages(
+        &self,
+        owner: &str,
+        repo: &str,
+    ) -> Result<HashMap<String, u64>> {
+        self.get_with_retry(&format!("/repos/{owner}/{repo}/languages"))
+            .await
+    }
+
+    /// The latest published release, or `None` when the repo has none.
+    pub async fn get_latest_release(&self, owner: &str, repo: &str) -> Result<Option<Release>> {
+        self.get_optional(&format!("/repos/{owner}/{repo}/releases/latest"))
+            .await
+    }
+
+    /// Returns the decoded README, or `None` when the repo has none.
+    pub async fn get_readme(&self, owner: &str, repo: &str) -> Result<Option<String>> {
+        let content: Option<Content> = self
+            .get_optional(&format!("/repos/{owner}/{repo}/readme"))
+            .await?;
+        Ok(content.and_then(|c| c.decoded_content()))
+    }
+
+    pub async fn list_user_orgs(&self) -> Result<Vec<OrgInfo>> {
+        let mut all_orgs = Vec::new();
+        let mut page = 1u32;
+        loop {
+            let page_result: Page<OrgInfo> = self
+                .get_with_retry(&format!("/user/orgs?per_page=100&page={page}"))
+                .await?;
+
+            let items = page_result.items;
+            if items.is_empty() {
+                break;
+            }
+            all_orgs.extend(items);
+            self.page_progress("your orgs", page, all_orgs.len(), "orgs");
+            if self.next_page(page, page_result.next.is_some()) != NextPage::Fetch {
+                break;
+            }
+            page += 1;
+        }
+        Ok(all_orgs)
+    }
+}
+
+/// Form-encodes a search query for the `q=` parameter (spaces become `+`).
+fn encode_query(query: &str) -> String {
+    let mut encoded = String::with_capacity(query.len());
+    for byte in query.bytes() {
+        match byte {
+            b' ' => encoded.push('+'),
+            b if b.is_ascii_alphanumeric() || b"-_.~:".contains(&b) => encoded.push(b as char),
+            b => encoded.push_str(&format!("%{b:02X}")),
+        }
+    }
+    encoded
+}
+
+/// The `--verbose` note for one fetched page of a listing.
+fn progress_line(label: &str, page: u32, so_far: usize, noun: &str) -> String {
+    format!("{label}: fetched page {page}, {so_far} {noun} so far")
+}
+
+/// Splits an `X-OAuth-Scopes` header value (`repo, read:org`).
+fn parse_scopes(header: &str) -> Vec<String> {
+    header
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether the error, or anything that caused it, is an I/O timeout.
+fn is_timeout(err: &(dyn std::error::Error + 'static)) -> bool {
+    let mut current = Some(err);
+    while let Some(err) = current {
+        if err
+            .downcast_ref::<std::io::Error>()
+            .is_some_and(|e| e.kind() == std::io::ErrorKind::TimedOut)
+        {
+            return true;
+        }
+        current = err.source();
+    }
+    false
+}
+
+/// Names what went wrong with an org request: a 404 is an unknown org and
+/// a 403 (other than a rate limit) a token that can't reach it.
+fn org_error(org: &str, status: Option<u16>, err: GitorgError) -> GitorgError {
+    match (status, err) {
+        (_, err @ GitorgError::RateLimited(_)) => err,
+        (Some(404), _) => GitorgError::OrgNotFound(org.to_string()),
+        (Some(403), _) => GitorgError::OrgAccessDenied(org.to_string()),
+        (_, err) => err,
+    }
+}
+
+/// `<host>.<token fingerprint>`: an org of the same name on github.com and
+/// an Enterprise Server, or tokens that see different repos of it, never
+/// share cached listings. The fingerprint is a hash, never the token.
+fn cache_scope(token: &str, base_url: Option<&str>) -> String {
+    let host = base_url
+        .and_then(|url| url.parse::<http::Uri>().ok())
+        .and_then(|uri| uri.authority().map(|a| a.to_string()))
+        .unwrap_or_else(|| "api.github.com".to_string());
+    let host: String = host
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+    let mut hasher = DefaultHasher::new();
+    token.hash(&mut hasher);
+    format!("{host}.{:016x}", hasher.finish())
+}
+
+/// Fine-grained personal access tokens start with `github_pat_`. They
+/// report no OAuth scopes and only reach the orgs they were granted.
+pub fn is_fine_grained(token: &str) -> bool {
+    token.starts_with("github_pat_")
+}
+
+fn status_of(err: &octocrab::Error) -> Option<u16> {
+    match err {
+        octocrab::Error::GitHub { source, .. } => Some(source.status_code.as_u16()),
+        _ => None,
+    }
+}
+
+/// GitHub signals secondary ("abuse") rate limits with a 403 or 429 whose
+/// message mentions it, or with a `Retry-After` header.
+fn is_secondary_rate_limit(status: u16, message: &str, has_retry_after: bool) -> bool {
+    if status != 403 && status != 429 {
+        return false;
+    }
+    let message = message.to_lowercase();
+    has_retry_after || message.contains("secondary rate limit") || message.contains("abuse")
+}
+
+/// The reset epoch when a 403/429 means the primary rate limit is used up
+/// (`X-RateLimit-Remaining: 0`), as opposed to a permission or secondary
+/// limit problem.
+fn primary_rate_limit_reset(status: u16, headers: &http::HeaderMap) -> Option<i64> {
+    if status != 403 && status != 429 {
+        return None;
+    }
+    let header = |name: &str| headers.get(name)?.to_str().ok().map(str::trim);
+    if header("x-ratelimit-remaining")? != "0" {
+        return None;
+    }
+    header("x-ratelimit-reset")?.parse().ok()
+}
+
+/// How long to sleep until `reset`, with a second's margin for clock skew.
+pub(super) fn wait_until(reset: i64, now: i64) -> Duration {
+    Duration::from_secs(reset.saturating_sub(now).max(0) as u64 + 1)
+}
+
+fn retry_after(headers: &http::HeaderMap) -> Option<Duration> {
+    headers
+        .get(http::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn backoff_delay(base: Duration, attempt: u32) -> Duration {
+    base.saturating_mul(2u32.saturating_pow(attempt))
+        .min(MAX_BACKOFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Wrapped(std::io::Error);
+
+    impl std::fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "request failed")
+        }
+    }
+
+    impl std::error::Error for Wrapped {
+        fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn progress_line_counts_pages_and_items() {
+        assert_eq!(
+            progress_line("org acme", 3, 300, "repos"),
+            "org acme: fetched page 3, 300 repos so far"
+        );
+    }
+
+    #[test]
+    fn scopes_header_splits_on_commas() {
+        assert_eq!(parse_scopes("repo, read:org"), vec!["repo", "read:org"]);
+        assert!(parse_scopes("").is_empty());
+    }
+
+    #[test]
+    fn timeouts_are_found_in_the_source_chain() {
+        let timed_out = Wrapped(std::io::Error::from(std::io::ErrorKind::TimedOut));
+        assert!(is_timeout(&timed_out));
+        let refused = Wrapped(std::io::Error::from(std::io::ErrorKind::ConnectionRefused));
+        assert!(!is_timeout(&refused));
+    }
+
+    #[test]
+    fn next_page_follows_links_until_the_cap() {
+        assert_eq!(next_page(1, true, None), NextPage::Fetch);
+        assert_eq!(next_page(50, true, None), NextPage::Fetch);
+        assert_eq!(next_page(1, false, None), NextPage::Done);
+        assert_eq!(next_page(1, true, Some(2)), NextPage::Fetch);
+        assert_eq!(next_page(2, true, Some(2)), NextPage::Capped);
+        // Running out of pages exactly at the cap isn't a truncation
+        assert_eq!(next_page(2, false, Some(2)), NextPage::Done);
+    }
+
+    #[tokio::test]
+    async fn capped_listing_marks_client_truncated() {
+        let client = GithubClient::new(
+            "ghp_test",
+            None,
+            false,
+            DEFAULT_TIMEOUT,
+            ProxySettings::default(),
+        )
+        .unwrap()
+        .with_max_pages(Some(1));
+        assert_eq!(client.next_page(1, false), NextPage::Done);
+        assert!(!client.truncated.load(Ordering::Relaxed));
+        assert_eq!(client.next_page(1, true), NextPage::Capped);
+        assert!(client.truncated.load(Ordering::Relaxed));
+    }
+
+    #[test]
+    fn encode_query_escapes_reserved_characters() {
+        assert_eq!(
+            encode_query("org:acme milestone:\"v1 & 2\""),
+            "org:acme+milestone:%22v1+%26+2%22"
+        );
+    }
+
+    #[test]
+    fn rate_limit_reads_all_resources() {
+        let rl: RateLimit = serde_json::from_value(serde_json::json!({
+            "resources": {
+                "core": { "limit": 5000, "remaining": 4990, "reset": 1700000000 },
+                "search": { "limit": 30, "remaining": 30, "reset": 1700000060 },
+                "graphql": { "limit": 5000, "remaining": 5000, "reset": 1700003600 },
+                "integration_manifest": { "limit": 5000, "remaining": 5000, "reset": 1700003600 }
+            },
+            "rate": { "limit": 5000, "remaining": 4990, "reset": 1700000000 }
+        }))
+        .unwrap();
+        assert_eq!(rl.resources.core.remaining, 4990);
+        assert_eq!(rl.resources.search.as_ref().unwrap().limit, 30);
+        assert_eq!(rl.resources.graphql.as_ref().unwrap().reset, 1700003600);
+        assert_eq!(rl.resources.core.reset_time(), "22:13:20 UTC");
+    }
+
+    #[test]
+    fn not_found_maps_to_org_not_found() {
+        let err = org_error(
+            "typo-org",
+            Some(404),
+            GitorgError::GitHub("Not Found".into()),
+        );
+        assert!(matches!(err, GitorgError::OrgNotFound(org) if org == "typo-org"));
+
+        let err = org_error("myorg", Some(500), GitorgError::GitHub("oops".into()));
+        assert!(matches!(err, GitorgError::GitHub(_)));
+        let err = org_error("myorg", None, GitorgError::GitHub("offline".into()));
+        assert!(matches!(err, GitorgError::GitHub(_)));
+    }
+
+    #[test]
+    fn forbidden_org_names_the_missing_access() {
+        let err = org_error("locked", Some(403), GitorgError::GitHub("Forbidden".into()));
+        assert!(matches!(err, GitorgError::OrgAccessDenied(org) if org == "locked"));
+        // An exhausted rate limit is also a 403, but says so
+        let err = org_error(
+            "locked",
+            Some(403),
+            GitorgError::RateLimited("12:00".into()),
+        );
+        assert!(matches!(err, GitorgError::RateLimited(_)));
+
+        assert!(is_fine_grained("github_pat_11ABC"));
+        assert!(!is_fine_grained("ghp_classic"));
+    }
+
+    #[test]
+    fn team_members_count_is_optional() {
+        let listed: Team = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "Platform",
+            "slug": "platform",
+            "privacy": "closed"
+        }))
+        .unwrap();
+        assert_eq!(listed.slug, "platform");
+        assert_eq!(listed.members_count, None);
+
+        let detail: Team = serde_json::from_value(serde_json::json!({
+            "name": "Platform",
+            "slug": "platform",
+            "privacy": "secret",
+            "members_count": 12
+        }))
+        .unwrap();
+        assert_eq!(detail.members_count, Some(12));
+    }
+
+    #[test]
+    fn org_repo_reads_has_discussions() {
+        let repo: OrgRepo = serde_json::from_value(serde_json::json!({
+            "id": 1,
+            "name": "widgets",
+            "url": "https://api.github.com/repos/org/widgets",
+            "stargazers_count": 3,
+            "has_discussions": true,
+        }))
+        .unwrap();
+        assert_eq!(repo.has_discussions, Some(true));
+        assert_eq!(repo.name, "widgets");
+        assert_eq!(repo.stargazers_count, Some(3));
+    }
+
+    #[test]
+    fn secondary_rate_limit_detection() {
+        assert!(is_secondary_rate_limit(
+            403,
+            "You have exceeded a secondary rate limit.",
+            false
+        ));
+        assert!(is_secondary_rate_limit(403, "abuse detection", false));
+        assert!(is_secondary_rate_limit(429, "Too many requests", true));
+        assert!(!is_secondary_rate_limit(
+            403,
+            "Resource not accessible",
+            false
+        ));
+        assert!(!is_secondary_rate_limit(404, "Not Found", true));
+    }
+
+    #[test]
+    fn retry_after_header_parsing() {
+        let mut headers = http::HeaderMap::new();
+        assert_eq!(retry_after(&headers), None);
+
+        headers.insert(http::header::RETRY_AFTER, "30".parse().unwrap());
+        assert_eq!(retry_after(&headers), Some(Duration::from_secs(30)));
+
+        headers.insert(http::header::RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(retry_after(&headers), None);
+    }
+
+    #[test]
+    fn primary_rate_limit_needs_zero_remaining() {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-ratelimit-reset", "1700000000".parse().unwrap());
+        assert_eq!(primary_rate_limit_reset(403, &headers), None);
+
+        headers.insert("x-ratelimit-remaining", "12".parse().unwrap());
+        assert_eq!(primary_rate_limit_reset(403, &headers), None);
+
+        headers.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        assert_eq!(primary_rate_limit_reset(403, &headers), Some(1_700_000_000));
+        assert_eq!(primary_rate_limit_reset(429, &headers), Some(1_700_000_000));
+        assert_eq!(primary_rate_limit_reset(404, &headers), None);
+    }
+
+    #[test]
+    fn wait_until_reset_has_a_margin() {
+        assert_eq!(wait_until(1_000, 940), Duration::from_secs(61));
+        // Already past the reset
+        assert_eq!(wait_until(1_000, 1_005), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps() {
+        let base = Duration::from_secs(2);
+        assert_eq!(backoff_delay(base, 0), Duration::from_secs(2));
+        assert_eq!(backoff_delay(base, 1), Duration::from_secs(4));
+        assert_eq!(backoff_delay(base, 3), Duration::from_secs(16));
+        assert_eq!(backoff_delay(base, 20), MAX_BACKOFF);
+    }
+
+    /// A stand-in for the GitHub API: each path (with query) answers with
+    /// its queued responses in order, repeating the last one; anything
+    /// else is a 404.
+    mod fake_github {
+        use wiremock::matchers::any;
+        use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+        pub fn json(body: serde_json::Value) -> ResponseTemplate {
+            ResponseTemplate::new(200).set_body_json(body)
+        }
+
+        /// A GitHub-style error body.
+        pub fn status(status: u16, message: &str) -> ResponseTemplate {
+            ResponseTemplate::new(status).set_body_json(serde_json::json!({
+                "message": message,
+                "documentation_url": "https://docs.github.com/rest"
+            }))
+        }
+
+        fn target(request: &Request) -> String {
+            match request.url.query() {
+                Some(query) => format!("{}?{query}", request.url.path()),
+                None => request.url.path().to_string(),
+            }
+        }
+
+        pub struct Server {
+            pub url: String,
+            server: MockServer,
+        }
+
+        impl Server {
+            pub async fn start() -> Self {
+                let server = MockServer::start().await;
+                Mock::given(any())
+                    .respond_with(status(404, "Not Found"))
+                    .with_priority(u8::MAX)
+                    .mount(&server)
+                    .await;
+                Self {
+                    url: server.uri(),
+                    server,
+                }
+            }
+
+            pub async fn route(&self, path: &str, replies: Vec<ResponseTemplate>) {
+                let last = replies.len().saturating_sub(1);
+                for (i, reply) in replies.into_iter().enumerate() {
+                    let path = path.to_string();
+                    let mock = Mock::given(move |request: &Request| target(request) == path)
+                        .respond_with(reply);
+                    let mock = if i < last {
+                        mock.up_to_n_times(1)
+                    } else {
+                        mock
+                    };
+                    mock.mount(&self.server).await;
+                }
+            }
+
+            /// A `Link` header pointing at `path` as the next page.
+            pub fn next_link(&self, path: &str) -> String {
+                format!("<{}{path}>; rel=\"next\"", self.url)
+            }
+
+            pub async fn hits(&self) -> Vec<String> {
+                let requests = self.server.received_requests().await.unwrap_or_default();
+                requests.iter().map(target).collect()
+            }
+        }
+    }
+
+    /// An HTTP proxy that tunnels CONNECT requests and records their targets.
+    mod fake_proxy {
+        use std::sync::{Arc, Mutex};
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+        use tokio::net::{TcpListener, TcpStream};
+
+        pub async fn start() -> (String, Arc<Mutex<Vec<String>>>) {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let url = format!("http://{}", listener.local_addr().unwrap());
+            let targets: Arc<Mutex<Vec<String>>> = Arc::default();
+            let seen = targets.clone();
+            tokio::spawn(async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(tunnel(stream, seen.clone()));
+                }
+            });
+            (url, targets)
+        }
+
+        async fn tunnel(mut client: TcpStream, seen: Arc<Mutex<Vec<String>>>) {
+            let mut head = Vec::new();
+            while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                let mut chunk = [0u8; 1024];
+                match client.read(&mut chunk).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => head.extend_from_slice(&chunk[..n]),
+                }
+            }
+            let head = String::from_utf8_lossy(&head).to_string();
+            let mut words = head.split_whitespace();
+            let (method, target) = (words.next(), words.next().unwrap_or("").to_string());
+            seen.lock().unwrap().push(target.clone());
+            let Ok(mut upstream) = TcpStream::connect(&target).await else {
+                return;
+            };
+            if method != Some("CONNECT") {
+                return;
+            }
+            let established = b"HTTP/1.1 200 Connection established\r\n\r\n";
+            if client.write_all(established).await.is_ok() {
+                let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+            }
+        }
+    }
+
+    use fake_github::{json, status, Server};
+    use wiremock::ResponseTemplate;
+
+    fn repos_json(names: &[&str]) -> serde_json::Value {
+        serde_json::Value::Array(
+            names
+                .iter()
+                .enumerate()
+                .map(|(id, name)| {
+                    serde_json::json!({
+                        "id": id,
+                        "name": name,
+                        "url": format!("https://api.github.com/repos/acme/{name}"),
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn repos_page(page: u32) -> String {
+        format!("/orgs/acme/repos?type=all&per_page=100&page={page}")
+    }
+
+    fn fake_client(server: &Server) -> GithubClient {
+        GithubClient::new(
+            "ghp_test",
+            Some(&server.url),
+            false,
+            DEFAULT_TIMEOUT,
+            ProxySettings::default(),
+        )
+        .unwrap()
+        .with_max_retries(2)
+        .with_retry_delay(Duration::from_millis(1))
+    }
+
+    fn names(repos: &[OrgRepo]) -> Vec<&str> {
+        repos.iter().map(|r| r.name.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn org_repos_follow_next_links_until_the_last_page() {
+        let server = Server::start().await;
+        server
+            .route(
+                &repos_page(1),
+                vec![json(repos_json(&["a", "b"]))
+                    .insert_header("link", &server.next_link(&repos_page(2)))],
+            )
+            .await;
+        server
+            .route(&repos_page(2), vec![json(repos_json(&["c"]))])
+            .await;
+        // Never requested: page 2 has no next link
+        server
+            .route(&repos_page(3), vec![json(repos_json(&["d"]))])
+            .await;
+
+        let repos = fake_client(&server)
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(names(&repos), vec!["a", "b", "c"]);
+        assert_eq!(server.hits().await, vec![repos_page(1), repos_page(2)]);
+    }
+
+    /// A client for `server` with proxy settings read from `vars`.
+    fn proxied_client(server: &Server, vars: &[(&str, &str)]) -> GithubClient {
+        let settings = ProxySettings::from_vars(None, |name| {
+            vars.iter()
+                .find(|(var, _)| *var == name)
+                .map(|(_, value)| value.to_string())
+        })
+        .unwrap();
+        GithubClient::new(
+            "ghp_test",
+            Some(&server.url),
+            false,
+            DEFAULT_TIMEOUT,
+            settings,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn requests_tunnel_through_the_proxy() {
+        let server = Server::start().await;
+        server
+            .route(&repos_page(1), vec![json(repos_json(&["a"]))])
+            .await;
+        let (proxy, targets) = fake_proxy::start().await;
+
+        // The fake API is http://, so HTTPS_PROXY doesn't apply to it
+        let client = proxied_client(&server, &[("HTTPS_PROXY", &proxy)]);
+        client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert!(targets.lock().unwrap().is_empty());
+
+        let client = proxied_client(
+            &server,
+            &[("HTTP_PROXY", &proxy), ("NO_PROXY", "example.com")],
+        );
+        let repos = client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(names(&repos), vec!["a"]);
+        let authority = server.url.trim_start_matches("http://");
+        assert_eq!(*targets.lock().unwrap(), vec![authority.to_string()]);
+        assert_eq!(server.hits().await, vec![repos_page(1), repos_page(1)]);
+    }
+
+    #[tokio::test]
+    async fn no_proxy_hosts_skip_the_proxy() {
+        let server = Server::start().await;
+        server
+            .route(&repos_page(1), vec![json(repos_json(&["a"]))])
+            .await;
+        let (proxy, targets) = fake_proxy::start().await;
+
+        let client = proxied_client(
+            &server,
+            &[("ALL_PROXY", &proxy), ("NO_PROXY", "localhost,127.0.0.1")],
+        );
+        let repos = client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(names(&repos), vec!["a"]);
+        assert!(targets.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn repo_cap_stops_the_crawl_across_orgs() {
+        let server = Server::start().await;
+        server
+            .route(
+                &repos_page(1),
+                vec![json(repos_json(&["a", "b"]))
+                    .insert_header("link", &server.next_link(&repos_page(2)))],
+            )
+            .await;
+        server
+            .route(&repos_page(2), vec![json(repos_json(&["c"]))])
+            .await;
+
+        let client = fake_client(&server).with_max_repos(Some(1), OverCap::Stop);
+        let repos = client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(names(&repos), vec!["a", "b"]);
+        assert!(client.repo_cap_hit());
+        // Later orgs aren't crawled at all
+        let other = client
+            .list_org_repos("other", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert!(other.is_empty());
+        assert_eq!(server.hits().await, vec![repos_page(1)]);
+
+        let client = fake_client(&server).with_max_repos(Some(1), OverCap::Continue);
+        let repos = client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(names(&repos), vec!["a", "b", "c"]);
+        assert!(!client.repo_cap_hit());
+    }
+
+    #[tokio::test]
+    async fn org_repos_stop_at_max_pages() {
+        let server = Server::start().await;
+        server
+            .route(
+                &repos_page(1),
+                vec![json(repos_json(&["a"]))
+                    .insert_header("link", &server.next_link(&repos_page(2)))],
+            )
+            .await;
+
+        let client = fake_client(&server).with_max_pages(Some(1));
+        let repos = client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(names(&repos), vec!["a"]);
+        assert!(client.truncated.load(Ordering::Relaxed));
+    }
+
+    #[tokio::test]
+    async fn milestones_stop_at_max_pages() {
+        let server = Server::start().await;
+        let page =
+            |page: u32| format!("/repos/acme/tool/milestones?state=all&per_page=100&page={page}");
+        server
+            .route(
+                &page(1),
+                vec![json(serde_json::json!([{
+                    "url": "https://api.github.com/repos/acme/tool/milestones/1",
+                    "html_url": "https://github.com/acme/tool/milestone/1",
+                    "id": 1,
+                    "node_id": "MI_1",
+                    "number": 1,
+                    "title": "v1.0",
+                    "created_at": "2024-01-01T00:00:00Z",
+                }]))
+                .insert_header("link", &server.next_link(&page(2)))],
+            )
+            .await;
+
+        let client = fake_client(&server).with_max_pages(Some(1));
+        let milestones = client.list_repo_milestones("acme", "tool").await.unwrap();
+        assert_eq!(milestones.len(), 1);
+        assert!(client.truncated.load(Ordering::Relaxed));
+        assert_eq!(server.hits().await, vec![page(1)]);
+    }
+
+    #[test]
+    fn cache_scope_separates_hosts_and_tokens() {
+        let scope = |token, url| cache_scope(token, url);
+        assert!(scope("ghp_a", None).starts_with("api.github.com."));
+        assert!(scope("ghp_a", Some("https://ghe.example.com:8443/api/v3"))
+            .starts_with("ghe.example.com_8443."));
+        assert_eq!(scope("ghp_a", None), scope("ghp_a", None));
+        assert_ne!(scope("ghp_a", None), scope("ghp_b", None));
+        assert!(!scope("ghp_a", None).contains("ghp_a"));
+    }
+
+    #[tokio::test]
+    async fn cached_listings_are_kept_apart_per_host() {
+        let github = Server::start().await;
+        github
+            .route(&repos_page(1), vec![json(repos_json(&["public"]))])
+            .await;
+        let enterprise = Server::start().await;
+        enterprise
+            .route(&repos_page(1), vec![json(repos_json(&["internal"]))])
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let cached = |server: &Server| {
+            fake_client(server).with_cache(RepoCache::new(dir.path().to_path_buf(), 10, true))
+        };
+        for _ in 0..2 {
+            let repos = cached(&github)
+                .list_org_repos("acme", RepoVisibility::All)
+                .await
+                .unwrap();
+            assert_eq!(names(&repos), vec!["public"]);
+            let repos = cached(&enterprise)
+                .list_org_repos("acme", RepoVisibility::All)
+                .await
+                .unwrap();
+            assert_eq!(names(&repos), vec!["internal"]);
+        }
+        // The second round came from the cache
+        assert_eq!(github.hits().await.len(), 1);
+        assert_eq!(enterprise.hits().await.len(), 1);
+    }
+
+    fn search_page(page: u32) -> String {
+        format!(
+            "/search/issues?q={}&sort=updated&order=desc&per_page=100&page={page}",
+            encode_query("org:acme is:issue")
+        )
+    }
+
+    #[tokio::test]
+    async fn search_over_the_result_cap_is_none() {
+        let server = Server::start().await;
+        server
+            .route(
+                &search_page(1),
+                vec![json(serde_json::json!({
+                    "total_count": 1001,
+                    "incomplete_results": false,
+                    "items": [],
+                }))],
+            )
+            .await;
+
+        let searched = fake_client(&server)
+            .search_org_issues("acme", "org:acme is:issue")
+            .await
+            .unwrap();
+        assert!(searched.is_none());
+        assert_eq!(server.hits().await, vec![search_page(1)]);
+    }
+
+    #[tokio::test]
+    async fn unsearchable_org_is_not_found() {
+        let server = Server::start().await;
+        server
+            .route(&search_page(1), vec![status(422, "Validation Failed")])
+            .await;
+
+        let err = fake_client(&server)
+            .search_org_issues("acme", "org:acme is:issue")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitorgError::OrgNotFound(org) if org == "acme"));
+    }
+
+    /// Open issues of acme/tool with the given numbers, as the REST API
+    /// lists them.
+    fn issues_json(numbers: &[u64]) -> serde_json::Value {
+        let user = |path: &str| format!("https://api.github.com/users/octocat{path}");
+        let author = serde_json::json!({
+            "login": "octocat",
+            "id": 1,
+            "node_id": "U_1",
+            "avatar_url": "https://avatars.githubusercontent.com/u/1",
+            "gravatar_id": "",
+            "url": user(""),
+            "html_url": "https://github.com/octocat",
+            "followers_url": user("/followers"),
+            "following_url": user("/following"),
+            "gists_url": user("/gists"),
+            "starred_url": user("/starred"),
+            "subscriptions_url": user("/subscriptions"),
+            "organizations_url": user("/orgs"),
+            "repos_url": user("/repos"),
+            "events_url": user("/events"),
+            "received_events_url": user("/received_events"),
+            "type": "User",
+            "site_admin": false,
+        });
+        let repo = "https://api.github.com/repos/acme/tool";
+        serde_json::Value::Array(
+            numbers
+                .iter()
+                .map(|number| {
+                    let issue = format!("{repo}/issues/{number}");
+                    serde_json::json!({
+                        "id": number,
+                        "node_id": format!("I_{number}"),
+                        "url": issue,
+                        "repository_url": repo,
+                        "labels_url": format!("{issue}/labels"),
+                        "comments_url": format!("{issue}/comments"),
+                        "events_url": format!("{issue}/events"),
+                        "html_url": format!("https://github.com/acme/tool/issues/{number}"),
+                        "number": number,
+                        "state": "open",
+                        "title": format!("Issue {number}"),
+                        "body": null,
+                        "user": author,
+                        "labels": [],
+                        "assignees": [],
+                        "locked": false,
+                        "comments": 0,
+                        "created_at": "2024-01-01T00:00:00Z",
+                        "updated_at": "2024-01-02T00:00:00Z",
+                    })
+                })
+                .collect(),
+        )
+    }
+
+    fn issues_page(page: u32) -> String {
+        format!("/repos/acme/tool/issues?state=open&per_page=100&page={page}")
+    }
+
+    fn numbers(issues: &[Issue]) -> Vec<u64> {
+        issues.iter().map(|i| i.number).collect()
+    }
+
+    #[tokio::test]
+    async fn repo_issues_follow_next_links_until_the_last_page() {
+        let server = Server::start().await;
+        server
+            .route(
+                &issues_page(1),
+                vec![json(issues_json(&[1, 2]))
+                    .insert_header("link", &server.next_link(&issues_page(2)))],
+            )
+            .await;
+        server
+            .route(&issues_page(2), vec![json(issues_json(&[3]))])
+            .await;
+        // Never requested: page 2 has no next link
+        server
+            .route(&issues_page(3), vec![json(issues_json(&[4]))])
+            .await;
+
+        let issues = fake_client(&server)
+            .list_repo_issues("acme", "tool", None)
+            .await
+            .unwrap();
+        assert_eq!(numbers(&issues), vec![1, 2, 3]);
+        assert_eq!(server.hits().await, vec![issues_page(1), issues_page(2)]);
+    }
+
+    #[tokio::test]
+    async fn repo_issues_without_a_next_link_are_one_page() {
+        let server = Server::start().await;
+        server
+            .route(&issues_page(1), vec![json(issues_json(&[7]))])
+            .await;
+        server
+            .route(&issues_page(2), vec![json(issues_json(&[8]))])
+            .await;
+
+        let issues = fake_client(&server)
+            .list_repo_issues("acme", "tool", None)
+            .await
+            .unwrap();
+        assert_eq!(numbers(&issues), vec![7]);
+        assert_eq!(server.hits().await, vec![issues_page(1)]);
+    }
+
+    #[tokio::test]
+    async fn archiving_drops_the_orgs_cached_listings() {
+        let server = Server::start().await;
+        server
+            .route(&repos_page(1), vec![json(repos_json(&["a"]))])
+            .await;
+        server
+            .route("/repos/acme/a", vec![json(repos_json(&["a"])[0].clone())])
+            .await;
+
+        let dir = tempfile::tempdir().unwrap();
+        let client =
+            fake_client(&server).with_cache(RepoCache::new(dir.path().to_path_buf(), 10, true));
+        for _ in 0..2 {
+            client
+                .list_org_repos("acme", RepoVisibility::All)
+                .await
+                .unwrap();
+        }
+        client.set_repo_archived("acme", "a", true).await.unwrap();
+        client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(
+            server.hits().await,
+            vec![repos_page(1), "/repos/acme/a".to_string(), repos_page(1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn org_access_probe_treats_403_and_empty_as_no_access() {
+        let server = Server::start().await;
+        let probe = |org: &str| format!("/orgs/{org}/repos?type=all&per_page=1");
+        server
+            .route(&probe("open"), vec![json(repos_json(&["a"]))])
+            .await;
+        server
+            .route(&probe("hidden"), vec![json(repos_json(&[]))])
+            .await;
+        server
+            .route(
+                &probe("locked"),
+                vec![status(
+                    403,
+                    "Resource not accessible by personal access token",
+                )],
+            )
+            .await;
+
+        let client = fake_client(&server);
+        assert!(client.can_see_org_repos("open").await.unwrap());
+        assert!(!client.can_see_org_repos("hidden").await.unwrap());
+        assert!(!client.can_see_org_repos("locked").await.unwrap());
+        assert!(matches!(
+            client.can_see_org_repos("ghost").await,
+            Err(GitorgError::OrgNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn unknown_org_is_org_not_found() {
+        let server = Server::start().await;
+        let err = fake_client(&server)
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitorgError::OrgNotFound(org) if org == "acme"));
+    }
+
+    #[tokio::test]
+    async fn secondary_rate_limit_is_retried() {
+        let server = Server::start().await;
+        server
+            .route(
+                &repos_page(1),
+                vec![
+                    status(403, "You have exceeded a secondary rate limit")
+                        .insert_header("retry-after", "0"),
+                    json(repos_json(&["a"])),
+                ],
+            )
+            .await;
+
+        let client = fake_client(&server);
+        let repos = client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(names(&repos), vec!["a"]);
+        assert_eq!(server.hits().await.len(), 2);
+        assert_eq!(
+            client.retry_stats(),
+            RetryStats {
+                retries: 1,
+                retry_wait_secs: 0.0
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn secondary_rate_limit_gives_up_after_max_retries() {
+        let server = Server::start().await;
+        let limited = status(403, "You have exceeded a secondary rate limit")
+            .insert_header("retry-after", "0");
+        server
+            .route(&repos_page(1), vec![limited.clone(), limited])
+            .await;
+
+        let client = fake_client(&server).with_max_retries(1);
+        assert!(client
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .is_err());
+        assert_eq!(server.hits().await.len(), 2);
+        assert_eq!(client.retry_stats().retries, 1);
+    }
+
+    fn rate_limited(reset: i64) -> ResponseTemplate {
+        status(403, "API rate limit exceeded for user ID 1.")
+            .insert_header("x-ratelimit-remaining", "0")
+            .insert_header("x-ratelimit-reset", &reset.to_string())
+    }
+
+    #[tokio::test]
+    async fn exhausted_rate_limit_is_rate_limited() {
+        let server = Server::start().await;
+        server
+            .route(&repos_page(1), vec![rate_limited(1_700_000_000)])
+            .await;
+
+        let err = fake_client(&server)
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitorgError::RateLimited(reset) if reset == "22:13:20 UTC"));
+        assert_eq!(server.hits().await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn exhausted_rate_limit_waits_for_reset_when_asked() {
+        let server = Server::start().await;
+        let reset = chrono::Utc::now().timestamp() - 1;
+        server
+            .route(
+                &repos_page(1),
+                vec![rate_limited(reset), json(repos_json(&["a"]))],
+            )
+            .await;
+
+        let repos = fake_client(&server)
+            .with_wait_on_rate_limit(true)
+            .list_org_repos("acme", RepoVisibility::All)
+            .await
+            .unwrap();
+        assert_eq!(names(&repos), vec!["a"]);
+        assert_eq!(server.hits().await.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn dependabot_alerts_are_counted_across_cursor_pages() {
+        let server = Server::start().await;
+        let first = "/repos/acme/tool/dependabot/alerts?state=open&per_page=100";
+        let second = "/repos/acme/tool/dependabot/alerts?state=open&per_page=100&after=abc";
+        server
+            .route(
+                first,
+                vec![json(serde_json::json!([{ "number": 1 }, { "number": 2 }]))
+                    .insert_header("link", &server.next_link(second))],
+            )
+            .await;
+        server
+            .route(second, vec![json(serde_json::json!([{ "number": 3 }]))])
+            .await;
+
+        let count = fake_client(&server)
+            .count_dependabot_alerts("acme", "tool")
+            .await
+            .unwrap();
+        assert_eq!(count, Some(3));
+        assert_eq!(server.hits().await, vec![first, second]);
+    }
+
+    #[tokio::test]
+    async fn disabled_dependabot_alerts_count_as_unknown() {
+        let server = Server::start().await;
+        server
+            .route(
+                "/repos/acme/tool/dependabot/alerts?state=open&per_page=100",
+                vec![status(
+                    403,
+                    "Dependabot alerts are disabled for this repository.",
+                )],
+            )
+            .await;
+
+        let client = fake_client(&server);
+        assert_eq!(
+            client
+                .count_dependabot_alerts("acme", "tool")
+                .await
+                .unwrap(),
+            None
+        );
+        // No route at all: 404
+        assert_eq!(
+            client
+                .count_dependabot_alerts("acme", "other")
+                .await
+                .unwrap(),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn branch_protection_reads_reviews_and_checks() {
+        let server = Server::start().await;
+        server
+            .route(
+                "/repos/acme/tool/branches/main/protection",
+                vec![json(serde_json::json!({
+                    "required_pull_request_reviews": { "required_approving_review_count": 2 },
+                    "required_status_checks": { "strict": true, "contexts": ["ci"] },
+                }))],
+            )
+            .await;
+        server
+            .route(
+                "/repos/acme/locked/branches/main/protection",
+                vec![status(403, "Must have admin rights to Repository.")],
+            )
+            .await;
+
+        let client = fake_client(&server);
+        let protection = client
+            .get_branch_protection("acme", "tool", "main")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            protection
+                .required_pull_request_reviews
+                .map(|r| r.required_approving_review_count),
+            Some(2)
+        );
+        assert_eq!(protection.required_status_checks.unwrap().contexts, ["ci"]);
+
+        // Unprotected branches are a 404
+        assert!(client
+            .get_branch_protection("acme", "open", "main")
+            .await
+            .unwrap()
+            .is_none());
+        let err = client
+            .get_branch_protection("acme", "locked", "main")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("needs admin access"));
+    }
+
+    #[tokio::test]
+    async fn commit_activity_waits_out_202s() {
+        let server = Server::start().await;
+        // A 202 body carries no `all` while GitHub is computing
+        let accepted = status(202, "");
+        server
+            .route(
+                "/repos/acme/tool/stats/participation",
+                vec![
+                    accepted.clone(),
+                    json(serde_json::json!({ "all": [1, 0, 4], "owner": [0, 0, 1] })),
+                ],
+            )
+            .await;
+        server
+            .route(
+                "/repos/acme/cold/stats/participation",
+                vec![accepted.clone(), accepted.clone(), accepted],
+            )
+            .await;
+
+        let client = fake_client(&server);
+        assert_eq!(
+            client.get_commit_activity("acme", "tool").await.unwrap(),
+            Some(vec![1, 0, 4])
+        );
+        assert_eq!(
+            client.get_commit_activity("acme", "cold").await.unwrap(),
+            None
+        );
+        assert_eq!(server.hits().await.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn org_members_carry_their_role() {
+        let server = Server::start().await;
+        let logins = |names: &[&str]| {
+            serde_json::Value::Array(
+                names
+                    .iter()
+                    .map(|login| serde_json::json!({ "login": login }))
+                    .collect(),
+            )
+        };
+        server
+            .route(
+                "/orgs/acme/members?role=admin&per_page=100&page=1",
+                vec![json(logins(&["boss"]))],
+            )
+            .await;
+        server
+            .route(
+                "/orgs/acme/members?role=member&per_page=100&page=1",
+                vec![json(logins(&["dev", "intern"]))],
+            )
+            .await;
+        server
+            .route(
+                "/orgs/acme/members?filter=2fa_disabled&per_page=100&page=1",
+                vec![status(403, "Only owners can use this filter.")],
+            )
+            .await;
+
+        let client = fake_client(&server);
+        let members = client.list_org_members("acme").await.unwrap();
+        let roles: Vec<(&str, &str)> = members
+            .iter()
+            .map(|m| (m.login.as_str(), m.role.as_str()))
+            .collect();
+        assert_eq!(
+            roles,
+            vec![("boss", "admin"), ("dev", "member"), ("intern", "member")]
+        );
+        assert_eq!(client.list_members_without_2fa("acme").await.unwrap(), None);
+
+        // No route: the org doesn't exist
+        let err = client.list_org_members("ghost").await.unwrap_err();
+        assert!(matches!(err, GitorgError::OrgNotFound(org) if org == "ghost"));
+    }
+
+    #[tokio::test]
+    async fn member_count_comes_from_the_last_page() {
+        let server = Server::start().await;
+        server
+            .route(
+                "/orgs/acme/members?per_page=1",
+                vec![
+                    json(serde_json::json!([{ "login": "boss" }])).insert_header(
+                        "link",
+                        &format!(
+                            "<{0}/orgs/acme/members?per_page=1&page=2>; rel=\"next\", \
+                     <{0}/orgs/acme/members?per_page=1&page=42>; rel=\"last\"",
+                            server.url
+                        ),
+                    ),
+                ],
+            )
+            .await;
+        server
+            .route(
+                "/orgs/solo/members?per_page=1",
+                vec![json(serde_json::json!([{ "login": "me" }]))],
+            )
+            .await;
+        server
+            .route(
+                "/orgs/secret/members?per_page=1",
+                vec![status(403, "Must be an organization member")],
+            )
+            .await;
+
+        let client = fake_client(&server);
+        assert_eq!(client.count_org_members("acme").await.unwrap(), Some(42));
+        assert_eq!(client.count_org_members("solo").await.unwrap(), Some(1));
+        assert_eq!(client.count_org_members("secret").await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn user_orgs_follow_next_links_until_the_last_page() {
+        let server = Server::start().await;
+        let orgs = |count: usize, prefix: &str| {
+            serde_json::Value::Array(
+                (0..count)
+                    .map(|i| serde_json::json!({ "login": format!("{prefix}{i}") }))
+                    .collect(),
+            )
+        };
+        let page = |page: u32| format!("/user/orgs?per_page=100&page={page}");
+        server
+            .route(
+                &page(1),
+                vec![json(orgs(100, "a")).insert_header("link", &server.next_link(&page(2)))],
+            )
+            .await;
+        server.route(&page(2), vec![json(orgs(1, "b"))]).await;
+        // Never requested: page 2 has no next link
+        server.route(&page(3), vec![json(orgs(1, "c"))]).await;
+
+        let all = fake_client(&server).list_user_orgs().await.unwrap();
+        assert_eq!(all.len(), 101);
+        assert_eq!(all[100].login, "b0");
+        assert_eq!(server.hits().await, vec![page(1), page(2)]);
     }
 }
@@ -1,3 +1,11 @@
 mod client;
+mod proxy;
+mod scheduler;
 
-pub use client::GithubClient;
+pub use client::{
+    is_fine_grained, BranchProtection, Contributor, GithubClient, OrgDetail, OrgRepo, OverCap,
+    RateLimit, RateLimitResource, RepoVisibility, Team, DEFAULT_BASE_DELAY, DEFAULT_MAX_REPOS,
+    DEFAULT_MAX_RETRIES, DEFAULT_TIMEOUT,
+};
+pub use proxy::{build_octocrab, ProxySettings};
+pub use scheduler::{DEFAULT_MAX_CONCURRENCY, DEFAULT_RATE_LIMIT_HEADROOM};
@@ -0,0 +1,236 @@
+use super::client::{format_reset, wait_until};
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+use tokio::sync::Notify;
+
+/// Requests allowed in flight at once while the rate limit is plentiful.
+pub const DEFAULT_MAX_CONCURRENCY: usize = 8;
+/// Requests of the core rate limit to leave unspent for other tools.
+pub const DEFAULT_RATE_LIMIT_HEADROOM: u32 = 100;
+/// Spare requests (above the headroom) each concurrent slot needs, so
+/// concurrency winds down as the budget shrinks.
+const REQUESTS_PER_SLOT: u32 = 50;
+
+/// Gates every API request of a client: at most `max_concurrency` in
+/// flight, fewer as the core rate limit read from response headers runs
+/// down towards `headroom`.
+pub struct Scheduler {
+    max_concurrency: usize,
+    headroom: u32,
+    state: Mutex<State>,
+    released: Notify,
+    /// Reset epoch of the pause last warned about, so concurrent requests
+    /// warn once.
+    paused_until: AtomicI64,
+}
+
+#[derive(Default)]
+struct State {
+    in_flight: usize,
+    /// Core requests left in the window ending at `reset`; `None` until a
+    /// response says, and again once the window is over.
+    remaining: Option<u32>,
+    reset: i64,
+}
+
+/// A request's place in the scheduler, given back on drop.
+pub struct Slot<'a> {
+    scheduler: &'a Scheduler,
+}
+
+impl Drop for Slot<'_> {
+    fn drop(&mut self) {
+        self.scheduler.state.lock().unwrap().in_flight -= 1;
+        self.scheduler.released.notify_waiters();
+    }
+}
+
+impl Scheduler {
+    pub fn new(max_concurrency: usize, headroom: u32) -> Self {
+        Self {
+            max_concurrency: max_concurrency.max(1),
+            headroom,
+            state: Mutex::new(State::default()),
+            released: Notify::new(),
+            paused_until: AtomicI64::new(0),
+        }
+    }
+
+    pub fn max_concurrency(&self) -> usize {
+        self.max_concurrency
+    }
+
+    /// Waits for a free slot. Once the budget is down to the headroom,
+    /// requests go one at a time, or with `pause` wait for the window to
+    /// reset.
+    pub async fn acquire(&self, pause: bool) -> Slot<'_> {
+        loop {
+            // Registered before checking, so a release in between still wakes us
+            let released = self.released.notified();
+            let now = chrono::Utc::now().timestamp();
+            let reset = {
+                let mut state = self.state.lock().unwrap();
+                if state.reset <= now {
+                    state.remaining = None;
+                }
+                let budget = state.remaining.map(|r| r.saturating_sub(self.headroom));
+                if budget == Some(0) && pause {
+                    Some(state.reset)
+                } else if state.in_flight < allowed_concurrency(budget, self.max_concurrency) {
+                    state.in_flight += 1;
+                    return Slot { scheduler: self };
+                } else {
+                    None
+                }
+            };
+            match reset {
+                Some(reset) => {
+                    if self.paused_until.swap(reset, Ordering::Relaxed) != reset {
+                        crate::display::warn(&format!(
+                            "Down to the rate limit headroom of {}; pausing until {}",
+                            self.headroom,
+                            format_reset(reset)
+                        ));
+                    }
+                    tokio::time::sleep(wait_until(reset, now)).await;
+                }
+                None => released.await,
+            }
+        }
+    }
+
+    /// Notes the core rate limit a response reports. Search and other
+    /// resources have budgets of their own and are left out.
+    pub fn record(&self, headers: &http::HeaderMap) {
+        let header = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+        if header("x-ratelimit-resource").is_some_and(|resource| resource != "core") {
+            return;
+        }
+        let (Some(remaining), Some(reset)) = (
+            header("x-ratelimit-remaining").and_then(|v| v.parse::<u32>().ok()),
+            header("x-ratelimit-reset").and_then(|v| v.parse::<i64>().ok()),
+        ) else {
+            return;
+        };
+        self.update(remaining, reset);
+    }
+
+    /// Takes `remaining` core requests until `reset` into account.
+    pub fn update(&self, remaining: u32, reset: i64) {
+        let mut state = self.state.lock().unwrap();
+        // Responses to concurrent requests arrive in any order: within a
+        // window the lowest count is the latest
+        if reset > state.reset {
+            state.reset = reset;
+            state.remaining = Some(remaining);
+        } else if reset == state.reset {
+            state.remaining = Some(state.remaining.map_or(remaining, |r| r.min(remaining)));
+        }
+    }
+}
+
+/// Concurrency for a budget of spare requests: full while plentiful
+/// (or unknown), one slot per `REQUESTS_PER_SLOT` below that, never
+/// under one.
+fn allowed_concurrency(budget: Option<u32>, max: usize) -> usize {
+    budget.map_or(max, |budget| {
+        ((budget / REQUESTS_PER_SLOT) as usize).clamp(1, max)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    fn headers(remaining: u32, reset: i64) -> http::HeaderMap {
+        let mut headers = http::HeaderMap::new();
+        headers.insert("x-ratelimit-remaining", remaining.into());
+        headers.insert("x-ratelimit-reset", reset.into());
+        headers.insert("x-ratelimit-resource", "core".parse().unwrap());
+        headers
+    }
+
+    fn in_the_future() -> i64 {
+        chrono::Utc::now().timestamp() + 3600
+    }
+
+    fn remaining(scheduler: &Scheduler) -> Option<u32> {
+        scheduler.state.lock().unwrap().remaining
+    }
+
+    #[test]
+    fn concurrency_winds_down_with_the_budget() {
+        assert_eq!(allowed_concurrency(None, 8), 8);
+        assert_eq!(allowed_concurrency(Some(4000), 8), 8);
+        assert_eq!(allowed_concurrency(Some(200), 8), 4);
+        assert_eq!(allowed_concurrency(Some(10), 8), 1);
+        assert_eq!(allowed_concurrency(Some(0), 8), 1);
+    }
+
+    #[test]
+    fn record_keeps_the_lowest_count_of_the_newest_window() {
+        let scheduler = Scheduler::new(8, 100);
+        let reset = in_the_future();
+        scheduler.record(&headers(900, reset));
+        scheduler.record(&headers(950, reset));
+        assert_eq!(remaining(&scheduler), Some(900));
+        // A stale window is ignored; a new one starts over
+        scheduler.record(&headers(10, reset - 3600));
+        assert_eq!(remaining(&scheduler), Some(900));
+        scheduler.record(&headers(5000, reset + 3600));
+        assert_eq!(remaining(&scheduler), Some(5000));
+
+        let mut search = headers(1, reset + 3600);
+        search.insert("x-ratelimit-resource", "search".parse().unwrap());
+        scheduler.record(&search);
+        assert_eq!(remaining(&scheduler), Some(5000));
+    }
+
+    #[tokio::test]
+    async fn slots_are_limited_and_given_back() {
+        let scheduler = Arc::new(Scheduler::new(2, 100));
+        let first = scheduler.acquire(false).await;
+        let _second = scheduler.acquire(false).await;
+
+        let waiting = {
+            let scheduler = Arc::clone(&scheduler);
+            tokio::spawn(async move {
+                let _slot = scheduler.acquire(false).await;
+            })
+        };
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        tokio::time::timeout(Duration::from_secs(5), waiting)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn low_budget_serializes_requests() {
+        let scheduler = Scheduler::new(8, 100);
+        scheduler.record(&headers(120, in_the_future()));
+        let _slot = scheduler.acquire(false).await;
+        let second = tokio::time::timeout(Duration::from_millis(50), scheduler.acquire(false));
+        assert!(second.await.is_err());
+    }
+
+    #[tokio::test]
+    async fn exhausted_budget_pauses_until_the_reset() {
+        let scheduler = Scheduler::new(8, 100);
+        let now = chrono::Utc::now().timestamp();
+        scheduler.record(&headers(100, now + 3600));
+        let paused = tokio::time::timeout(Duration::from_millis(50), scheduler.acquire(true));
+        assert!(paused.await.is_err());
+
+        // Once the window is over the budget is unknown again
+        scheduler.state.lock().unwrap().reset = now - 1;
+        tokio::time::timeout(Duration::from_secs(1), scheduler.acquire(true))
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,109 @@
+use chrono::{DateTime, Utc};
+
+/// Points per health signal; they add up to 100.
+const RECENT_PUSH: u32 = 40;
+const ISSUE_ACTIVITY: u32 = 20;
+const NOT_ARCHIVED: u32 = 20;
+const LICENSE: u32 = 10;
+const README: u32 = 10;
+
+/// The repo fields a health score is computed from.
+#[derive(Debug, Clone, Copy)]
+pub struct HealthInputs {
+    pub pushed_at: Option<DateTime<Utc>>,
+    pub open_issues: u32,
+    pub archived: bool,
+    pub has_license: bool,
+    /// `None` when the README wasn't fetched; that signal is then left out
+    /// and the score is scaled over the remaining points.
+    pub has_readme: Option<bool>,
+}
+
+/// Scores a repo from 0 to 100:
+///
+/// - pushed within 90 days: 40 (within a year: 20)
+/// - has open issues: 20
+/// - not archived: 20
+/// - has a license: 10
+/// - has a README: 10
+pub fn health_score(inputs: &HealthInputs, now: DateTime<Utc>) -> u8 {
+    let mut earned = match inputs.pushed_at.map(|dt| (now - dt).num_days()) {
+        Some(days) if days <= 90 => RECENT_PUSH,
+        Some(days) if days <= 365 => RECENT_PUSH / 2,
+        _ => 0,
+    };
+    if inputs.open_issues > 0 {
+        earned += ISSUE_ACTIVITY;
+    }
+    if !inputs.archived {
+        earned += NOT_ARCHIVED;
+    }
+    if inputs.has_license {
+        earned += LICENSE;
+    }
+
+    let mut possible = RECENT_PUSH + ISSUE_ACTIVITY + NOT_ARCHIVED + LICENSE;
+    if let Some(has_readme) = inputs.has_readme {
+        possible += README;
+        if has_readme {
+            earned += README;
+        }
+    }
+
+    (earned * 100 / possible) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn inputs(days_since_push: Option<i64>, now: DateTime<Utc>) -> HealthInputs {
+        HealthInputs {
+            pushed_at: days_since_push.map(|d| now - Duration::days(d)),
+            open_issues: 3,
+            archived: false,
+            has_license: true,
+            has_readme: Some(true),
+        }
+    }
+
+    #[test]
+    fn healthy_repo_scores_100() {
+        let now = Utc::now();
+        assert_eq!(health_score(&inputs(Some(5), now), now), 100);
+    }
+
+    #[test]
+    fn push_age_is_tiered() {
+        let now = Utc::now();
+        assert_eq!(health_score(&inputs(Some(200), now), now), 80);
+        assert_eq!(health_score(&inputs(Some(800), now), now), 60);
+        assert_eq!(health_score(&inputs(None, now), now), 60);
+    }
+
+    #[test]
+    fn neglected_archived_repo_scores_zero() {
+        let now = Utc::now();
+        let neglected = HealthInputs {
+            pushed_at: None,
+            open_issues: 0,
+            archived: true,
+            has_license: false,
+            has_readme: Some(false),
+        };
+        assert_eq!(health_score(&neglected, now), 0);
+    }
+
+    #[test]
+    fn unknown_readme_is_scaled_out() {
+        let now = Utc::now();
+        let mut repo = inputs(Some(5), now);
+        repo.has_readme = None;
+        assert_eq!(health_score(&repo, now), 100);
+
+        repo.has_license = false;
+        // 80 of a possible 90 points
+        assert_eq!(health_score(&repo, now), 88);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::error::{GitorgError, Result};
+use keyring::Entry;
+
+/// Service the token is filed under in the OS secret store: the macOS
+/// Keychain, Windows Credential Manager, or the Secret Service on Linux.
+const SERVICE: &str = "gitorg";
+
+/// Saves `token` under `account` (the config profile name).
+pub fn store(account: &str, token: &str) -> Result<()> {
+    let account = account.to_string();
+    let token = token.to_string();
+    off_runtime(move || {
+        let entry = entry(&account)?;
+        entry.set_password(&token).map_err(unavailable)?;
+        // Some backends accept a write they can't give back
+        match entry.get_password() {
+            Ok(stored) if stored == token => Ok(()),
+            _ => Err(unavailable("the token didn't read back after saving")),
+        }
+    })
+}
+
+/// Reads the token stored for `account`, if any.
+pub fn load(account: &str) -> Result<Option<String>> {
+    let account = account.to_string();
+    off_runtime(move || match entry(&account)?.get_password() {
+        Ok(token) => Ok(Some(token.trim().to_string()).filter(|t| !t.is_empty())),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(unavailable(e)),
+    })
+}
+
+fn entry(account: &str) -> Result<Entry> {
+    Entry::new(SERVICE, account).map_err(unavailable)
+}
+
+/// Runs `f` on a thread of its own: the Secret Service backend blocks on a
+/// runtime of its own, which can't start inside the command's.
+fn off_runtime<T: Send>(f: impl FnOnce() -> Result<T> + Send) -> Result<T> {
+    std::thread::scope(|scope| {
+        scope
+            .spawn(f)
+            .join()
+            .unwrap_or_else(|_| Err(unavailable("the keyring backend panicked")))
+    })
+}
+
+fn unavailable(reason: impl std::fmt::Display) -> GitorgError {
+    GitorgError::Config(format!("OS keyring unavailable: {reason}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn missing_backend_is_an_error_not_a_panic() {
+        // Whatever the host offers, asking from inside a runtime must return
+        match load("gitorg-test-no-such-profile") {
+            Ok(token) => assert_eq!(token, None),
+            Err(e) => assert!(e.to_string().contains("OS keyring unavailable"), "{e}"),
+        }
+    }
+}
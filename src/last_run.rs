@@ -0,0 +1,92 @@
+use crate::config::config_dir;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Timestamps of the last `--since-last-run` invocation, keyed by command name.
+#[derive(Debug, Serialize, Deserialize, Default)]
+pub struct LastRun {
+    #[serde(default)]
+    pub commands: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl LastRun {
+    pub fn get(&self, command: &str) -> Option<DateTime<Utc>> {
+        self.commands.get(command).copied()
+    }
+}
+
+pub fn last_run_path() -> Result<PathBuf> {
+    Ok(config_dir()?.join("last_run.toml"))
+}
+
+pub fn load_last_run() -> Result<LastRun> {
+    let path = last_run_path()?;
+    if !path.exists() {
+        return Ok(LastRun::default());
+    }
+    let contents = fs::read_to_string(&path)?;
+    let last_run: LastRun = toml::from_str(&contents)?;
+    Ok(last_run)
+}
+
+pub fn record_last_run(command: &str, at: DateTime<Utc>) -> Result<()> {
+    let mut last_run = load_last_run()?;
+    last_run.commands.insert(command.to_string(), at);
+
+    let path = last_run_path()?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, toml::to_string_pretty(&last_run)?)?;
+    Ok(())
+}
+
+/// Returns true when an item last touched at `updated` should be shown for a
+/// run that only wants changes since `since`. A missing `since` (first run)
+/// shows everything.
+pub fn changed_since(updated: Option<DateTime<Utc>>, since: Option<DateTime<Utc>>) -> bool {
+    match since {
+        Some(since) => updated.is_some_and(|u| u > since),
+        None => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn last_run_roundtrip() {
+        let at = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let mut last_run = LastRun::default();
+        last_run.commands.insert("repos".to_string(), at);
+
+        let serialized = toml::to_string_pretty(&last_run).unwrap();
+        let deserialized: LastRun = toml::from_str(&serialized).unwrap();
+
+        assert_eq!(deserialized.get("repos"), Some(at));
+        assert_eq!(deserialized.get("issues"), None);
+    }
+
+    #[test]
+    fn first_run_shows_everything() {
+        assert!(changed_since(None, None));
+        assert!(changed_since(Some(Utc::now()), None));
+    }
+
+    #[test]
+    fn only_items_updated_after_last_run() {
+        let since = Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap();
+        let before = Utc.with_ymd_and_hms(2024, 2, 1, 0, 0, 0).unwrap();
+        let after = Utc.with_ymd_and_hms(2024, 3, 2, 0, 0, 0).unwrap();
+
+        assert!(changed_since(Some(after), Some(since)));
+        assert!(!changed_since(Some(before), Some(since)));
+        assert!(!changed_since(None, Some(since)));
+    }
+}
@@ -1,10 +1,19 @@
+mod badges;
+mod browser;
+mod cache;
 mod commands;
 mod config;
 mod display;
 mod error;
 mod github;
+mod health;
+mod keyring;
+mod last_run;
+mod snapshot;
+mod webhook;
 
 use clap::{Parser, Subcommand};
+use commands::GlobalArgs;
 
 #[derive(Parser)]
 #[command(
@@ -13,13 +22,8 @@ use clap::{Parser, Subcommand};
     about = "Manage and monitor multiple GitHub organizations"
 )]
 pub struct Cli {
-    /// Output results as JSON
-    #[arg(long, global = true)]
-    json: bool,
-
-    /// Show verbose output (rate limits, debug info)
-    #[arg(long, global = true)]
-    verbose: bool,
+    #[command(flatten)]
+    global: GlobalArgs,
 
     #[command(subcommand)]
     command: Commands,
@@ -28,76 +32,271 @@ pub struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Authenticate with a GitHub personal access token
+    ///
+    /// Run in a terminal without --token, it then lists your organizations
+    /// and saves the ones you pick as `defaults.orgs` in the config.
     Auth {
-        /// Token to use (if omitted, prompts interactively)
+        /// Token to use (if omitted, prompts interactively). Also skips the
+        /// default-org prompt, for scripts
         #[arg(long)]
         token: Option<String>,
+        /// Reuse the token the GitHub CLI (`gh`) is logged in with
+        #[arg(long, conflicts_with = "token")]
+        import_gh: bool,
+        /// Where to keep the token: the OS keyring or config.toml [default: current setting]
+        #[arg(long, value_enum)]
+        storage: Option<config::TokenStorage>,
+        /// Validate the token and show its user without saving anything
+        #[arg(long)]
+        dry_run: bool,
     },
     /// List your GitHub organizations
-    Orgs,
+    Orgs {
+        /// Add each org's repo count, member count, and website (two extra
+        /// requests per org)
+        #[arg(long)]
+        detailed: bool,
+    },
     /// List repositories across organizations
-    Repos {
-        /// Filter to a specific organization
+    Repos(commands::repos::ReposArgs),
+    /// Show details for a single repository
+    Repo {
+        /// Repository as <org>/<name>
+        slug: String,
+
+        /// Also open the repository's page in the browser
         #[arg(long)]
-        org: Option<String>,
-        /// Sort by: activity, stars, staleness, name
-        #[arg(long, default_value = "activity")]
-        sort: String,
+        open: bool,
+    },
+    /// Open an org, repository, or issue on GitHub in the browser
+    Open {
+        /// <org>, <org>/<name>, or <org>/<name>#<number> for an issue or PR
+        target: String,
+    },
+    /// Archive a repository (asks for confirmation unless --yes)
+    Archive {
+        /// Repository as <org>/<name>
+        slug: String,
+    },
+    /// Unarchive a repository (asks for confirmation unless --yes)
+    Unarchive {
+        /// Repository as <org>/<name>
+        slug: String,
     },
     /// Find stale repositories with no recent pushes
-    Stale {
-        /// Filter to a specific organization
+    Stale(commands::stale::StaleArgs),
+    /// List open issues across organizations
+    Issues(commands::issues::IssuesArgs),
+    /// Show aggregate statistics across organizations
+    Stats(commands::stats::StatsArgs),
+    /// Show a full dashboard overview
+    Overview(commands::overview::OverviewArgs),
+    /// List teams and their member counts across organizations
+    Teams {
+        /// Filter to specific organizations (comma-separated)
         #[arg(long)]
         org: Option<String>,
-        /// Number of days without a push to consider stale
-        #[arg(long, default_value = "90")]
-        days: u64,
     },
-    /// List open issues across organizations
-    Issues {
-        /// Filter to a specific organization
+    /// Aggregate contributors and their commit counts across organizations
+    Contributors {
+        /// Filter to specific organizations (comma-separated)
         #[arg(long)]
         org: Option<String>,
     },
-    /// Show aggregate statistics across organizations
-    Stats {
-        /// Filter to a specific organization
+    /// Rank the most active contributors across every repo of an org
+    Leaderboard {
+        /// Organizations to rank (comma-separated; default: configured orgs)
+        org: Option<String>,
+
+        /// How many contributors to show
+        #[arg(long, default_value_t = 20, value_parser = clap::value_parser!(u64).range(1..))]
+        top: u64,
+    },
+    /// Write orgs, stats, stale repos, and open issues as one report
+    /// (Markdown, or JSON with --json; save it with --output)
+    Export {
+        /// Filter to specific organizations (comma-separated)
         #[arg(long)]
         org: Option<String>,
+
+        /// Days without a push for the stale section [default: 90, or defaults.stale_days]
+        #[arg(long)]
+        days: Option<u64>,
     },
-    /// Show a full dashboard overview
-    Overview {
-        /// Filter to a specific organization
+    /// Show each repository's latest release and how long ago it shipped
+    Releases(commands::releases::ReleasesArgs),
+    /// List org members with their role and 2FA status, for seat reviews
+    Members {
+        /// Filter to specific organizations (comma-separated)
+        #[arg(long)]
+        org: Option<String>,
+    },
+    /// Count open Dependabot alerts per repository, most first
+    Alerts {
+        /// Filter to specific organizations (comma-separated)
+        #[arg(long)]
+        org: Option<String>,
+    },
+    /// Check whether each repository's default branch is protected
+    Protection {
+        /// Filter to specific organizations (comma-separated)
+        #[arg(long)]
+        org: Option<String>,
+
+        /// Show only repos whose default branch is unprotected
+        #[arg(long)]
+        unprotected_default: bool,
+    },
+    /// Show your remaining API budget (core, search, and GraphQL)
+    RateLimit,
+    /// Show who the token belongs to, its scopes, and the remaining rate limit
+    Whoami,
+    /// Manage the on-disk repo listing cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Save org repo state and compare saved snapshots over time
+    Snapshot {
+        #[command(subcommand)]
+        action: SnapshotAction,
+    },
+    /// List config profiles or switch the active one
+    Profile {
+        #[command(subcommand)]
+        action: Option<ProfileAction>,
+    },
+    /// List the record fields of each list command, for --format and --json
+    Fields {
+        /// Only this command's fields, e.g. `repos` or `snapshot list`
+        command: Vec<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheAction {
+    /// Delete all cached repo listings
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum SnapshotAction {
+    /// Save every org's repos (stars, archived, stale) to a timestamped snapshot
+    Save {
+        /// Filter to specific organizations (comma-separated)
         #[arg(long)]
         org: Option<String>,
-        /// Days threshold for stale repos in overview
-        #[arg(long, default_value = "90")]
-        days: u64,
+    },
+    /// List saved snapshots
+    List,
+    /// Show new, removed, archived, and newly stale repos plus star changes
+    Diff {
+        /// Older snapshot, by name or path [default: the second newest]
+        from: Option<String>,
+        /// Newer snapshot, by name or path [default: the newest]
+        to: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ProfileAction {
+    /// List profiles (the default action)
+    List,
+    /// Make a profile the active one
+    Use {
+        /// Profile name
+        name: String,
     },
 }
 
 #[tokio::main]
 async fn main() {
-    let cli = Cli::parse();
+    let mut cli = Cli::parse();
+    let config = cli.global.load_config().ok();
+    let defaults = config.as_ref().map(|config| config.defaults());
+    cli.global
+        .infer_output_format(defaults.and_then(|defaults| defaults.output_format));
+    display::set_staleness_thresholds(display::StalenessThresholds::from_config(
+        defaults.and_then(|defaults| defaults.staleness_yellow_days),
+        defaults.and_then(|defaults| defaults.staleness_red_days),
+    ));
+    display::init_color(cli.global.no_color || cli.global.markdown || cli.global.output.is_some());
+    display::set_markdown(cli.global.markdown);
+    display::set_ndjson(cli.global.ndjson);
+    display::set_compact_json(cli.global.json_compact);
+    display::set_quiet(cli.global.quiet);
+    display::set_table_style(cli.global.table_style);
+    display::set_format(cli.global.format.clone());
 
-    let result = match &cli.command {
-        Commands::Auth { token } => commands::auth::run(token).await,
-        Commands::Orgs => commands::orgs::run(cli.json, cli.verbose).await,
-        Commands::Repos { org, sort } => {
-            commands::repos::run(org, sort, cli.json, cli.verbose).await
-        }
-        Commands::Stale { org, days } => {
-            commands::stale::run(org, *days, cli.json, cli.verbose).await
+    match run(&cli).await {
+        Err(e) => {
+            display::error(&e.to_string());
+            std::process::exit(1);
         }
-        Commands::Issues { org } => commands::issues::run(org, cli.json, cli.verbose).await,
-        Commands::Stats { org } => commands::stats::run(org, cli.json, cli.verbose).await,
-        Commands::Overview { org, days } => {
-            commands::overview::run(org, *days, cli.json, cli.verbose).await
+        Ok(true) => std::process::exit(2),
+        Ok(false) => {}
+    }
+}
+
+/// Runs the chosen command. `Ok(true)` means `--fail-on-match` was given
+/// and the command found something.
+async fn run(cli: &Cli) -> error::Result<bool> {
+    if let Some(path) = &cli.global.output {
+        display::set_output_file(path)?;
+    }
+
+    let mut matched = false;
+
+    let result = match &cli.command {
+        Commands::Auth {
+            token,
+            import_gh,
+            storage,
+            dry_run,
+        } => commands::auth::run(token, *import_gh, *storage, *dry_run, &cli.global).await,
+        Commands::Orgs { detailed } => commands::orgs::run(*detailed, &cli.global).await,
+        Commands::Repos(args) => commands::repos::run(args, &cli.global).await,
+        Commands::Repo { slug, open } => commands::repo::run(slug, *open, &cli.global).await,
+        Commands::Open { target } => commands::open::run(target, &cli.global),
+        Commands::Archive { slug } => commands::archive::run(slug, true, &cli.global).await,
+        Commands::Unarchive { slug } => commands::archive::run(slug, false, &cli.global).await,
+        Commands::Stale(args) => commands::stale::run(args, &cli.global)
+            .await
+            .map(|found| matched = args.fail_on_match && found > 0),
+        Commands::Issues(args) => commands::issues::run(args, &cli.global)
+            .await
+            .map(|found| matched = args.fail_on_match && found > 0),
+        Commands::Stats(args) => commands::stats::run(args, &cli.global).await,
+        Commands::Overview(args) => commands::overview::run(args, &cli.global).await,
+        Commands::Teams { org } => commands::teams::run(org, &cli.global).await,
+        Commands::Contributors { org } => commands::contributors::run(org, &cli.global).await,
+        Commands::Leaderboard { org, top } => {
+            commands::contributors::leaderboard(org, *top as usize, &cli.global).await
         }
+        Commands::Export { org, days } => commands::export::run(org, *days, &cli.global).await,
+        Commands::Releases(args) => commands::releases::run(args, &cli.global).await,
+        Commands::Members { org } => commands::members::run(org, &cli.global).await,
+        Commands::Alerts { org } => commands::alerts::run(org, &cli.global).await,
+        Commands::Protection {
+            org,
+            unprotected_default,
+        } => commands::protection::run(org, *unprotected_default, &cli.global).await,
+        Commands::RateLimit => commands::rate_limit::run(&cli.global).await,
+        Commands::Whoami => commands::whoami::run(&cli.global).await,
+        Commands::Cache { action } => match action {
+            CacheAction::Clear => commands::cache::clear(),
+        },
+        Commands::Snapshot { action } => match action {
+            SnapshotAction::Save { org } => commands::snapshot::save(org, &cli.global).await,
+            SnapshotAction::List => commands::snapshot::list(&cli.global),
+            SnapshotAction::Diff { from, to } => commands::snapshot::diff(from, to, &cli.global),
+        },
+        Commands::Profile { action } => match action {
+            None | Some(ProfileAction::List) => commands::profile::list(&cli.global),
+            Some(ProfileAction::Use { name }) => commands::profile::switch(name, &cli.global),
+        },
+        Commands::Fields { command } => commands::fields::run(command, &cli.global),
     };
 
-    if let Err(e) = result {
-        display::error(&e.to_string());
-        std::process::exit(1);
-    }
+    result.map(|()| matched)
 }
@@ -0,0 +1,371 @@
+use crate::config::config_dir;
+use crate::error::{GitorgError, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The repos of every org at one point in time, saved by `snapshot save`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub taken_at: DateTime<Utc>,
+    pub repos: Vec<SnapshotRepo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SnapshotRepo {
+    pub org: String,
+    pub name: String,
+    pub stars: u32,
+    /// Missing from snapshots saved before forks were recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forks: Option<u32>,
+    pub archived: bool,
+    /// `active`, `stale`, or `archived`, as `repos` reports it
+    pub status: String,
+}
+
+impl SnapshotRepo {
+    fn slug(&self) -> String {
+        format!("{}/{}", self.org, self.name)
+    }
+
+    fn key(&self) -> String {
+        repo_key(&self.org, &self.name)
+    }
+}
+
+/// Matches a repo across runs: `org/name`, ignoring case as GitHub does.
+pub fn repo_key(org: &str, name: &str) -> String {
+    format!("{org}/{name}").to_lowercase()
+}
+
+/// What changed between two snapshots. Repos are named `org/name`.
+#[derive(Debug, Serialize)]
+pub struct SnapshotDiff {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub added: Vec<String>,
+    pub removed: Vec<String>,
+    pub archived: Vec<String>,
+    pub unarchived: Vec<String>,
+    pub went_stale: Vec<String>,
+    pub star_changes: Vec<StarChange>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.archived.is_empty()
+            && self.unarchived.is_empty()
+            && self.went_stale.is_empty()
+            && self.star_changes.is_empty()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct StarChange {
+    pub repo: String,
+    pub before: u32,
+    pub after: u32,
+    pub delta: i64,
+}
+
+/// How the repos counted now add up against a snapshot (`stats
+/// --compare-to`). Deltas are now minus then.
+#[derive(Debug, Serialize)]
+pub struct SnapshotComparison {
+    /// When the snapshot was taken
+    pub since: DateTime<Utc>,
+    pub repos: i64,
+    pub stars: i64,
+    /// `None` when the snapshot predates fork counts
+    pub forks: Option<i64>,
+    /// Repos in both whose stars or forks changed, biggest star change first
+    pub repo_changes: Vec<RepoDelta>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RepoDelta {
+    pub org: String,
+    pub name: String,
+    pub stars_before: u32,
+    pub stars: u32,
+    pub star_delta: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fork_delta: Option<i64>,
+}
+
+pub fn snapshot_dir() -> Result<PathBuf> {
+    Ok(config_dir()?.join("snapshots"))
+}
+
+/// Writes `snapshot` as `<taken_at>.json` in the snapshot directory.
+pub fn save_snapshot(snapshot: &Snapshot) -> Result<PathBuf> {
+    let dir = snapshot_dir()?;
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!(
+        "{}.json",
+        snapshot.taken_at.format("%Y%m%dT%H%M%SZ")
+    ));
+    let json = serde_json::to_string_pretty(snapshot)
+        .map_err(|e| GitorgError::Config(format!("Cannot serialize snapshot: {e}")))?;
+    fs::write(&path, json)?;
+    Ok(path)
+}
+
+/// Saved snapshots, oldest first (the names sort chronologically).
+pub fn list_snapshots() -> Result<Vec<PathBuf>> {
+    let dir = snapshot_dir()?;
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+/// Finds a snapshot given as a file path or as a name from `snapshot list`.
+pub fn resolve_snapshot(arg: &str) -> Result<PathBuf> {
+    let path = Path::new(arg);
+    if path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    let named = snapshot_dir()?.join(format!("{}.json", arg.trim_end_matches(".json")));
+    if named.is_file() {
+        return Ok(named);
+    }
+    Err(GitorgError::Config(format!(
+        "No snapshot '{arg}'. See `gitorg snapshot list`."
+    )))
+}
+
+pub fn load_snapshot(path: &Path) -> Result<Snapshot> {
+    let contents = fs::read_to_string(path)?;
+    serde_json::from_str(&contents)
+        .map_err(|e| GitorgError::Config(format!("Cannot read snapshot {}: {e}", path.display())))
+}
+
+/// Compares `old` with `new`. Star changes come biggest first.
+pub fn diff_snapshots(old: &Snapshot, new: &Snapshot) -> SnapshotDiff {
+    let by_slug = |s: &Snapshot| -> BTreeMap<String, SnapshotRepo> {
+        s.repos.iter().map(|r| (r.slug(), r.clone())).collect()
+    };
+    let before = by_slug(old);
+    let after = by_slug(new);
+
+    let mut diff = SnapshotDiff {
+        from: old.taken_at,
+        to: new.taken_at,
+        added: Vec::new(),
+        removed: before
+            .keys()
+            .filter(|slug| !after.contains_key(*slug))
+            .cloned()
+            .collect(),
+        archived: Vec::new(),
+        unarchived: Vec::new(),
+        went_stale: Vec::new(),
+        star_changes: Vec::new(),
+    };
+
+    for (slug, now) in &after {
+        let Some(was) = before.get(slug) else {
+            diff.added.push(slug.clone());
+            continue;
+        };
+        if !was.archived && now.archived {
+            diff.archived.push(slug.clone());
+        } else if was.archived && !now.archived {
+            diff.unarchived.push(slug.clone());
+        }
+        if was.status == "active" && now.status == "stale" {
+            diff.went_stale.push(slug.clone());
+        }
+        if was.stars != now.stars {
+            diff.star_changes.push(StarChange {
+                repo: slug.clone(),
+                before: was.stars,
+                after: now.stars,
+                delta: i64::from(now.stars) - i64::from(was.stars),
+            });
+        }
+    }
+
+    diff.star_changes
+        .sort_by_key(|c| std::cmp::Reverse(c.delta.abs()));
+    diff
+}
+
+/// Compares the repos counted now (`now`, from `orgs`) with `baseline`.
+/// A snapshot repo that is still listed (`listed` holds `repo_key`s) but
+/// wasn't counted is filtered out of this run, so it is left out of the
+/// baseline too; one no longer listed is gone, and its loss counts.
+pub fn compare_to_snapshot(
+    baseline: &Snapshot,
+    now: &[SnapshotRepo],
+    listed: &HashSet<String>,
+    orgs: &[String],
+) -> SnapshotComparison {
+    let counted: BTreeMap<String, &SnapshotRepo> = now.iter().map(|r| (r.key(), r)).collect();
+    let before: BTreeMap<String, &SnapshotRepo> = baseline
+        .repos
+        .iter()
+        .filter(|r| orgs.iter().any(|org| org.eq_ignore_ascii_case(&r.org)))
+        .map(|r| (r.key(), r))
+        .filter(|(key, _)| counted.contains_key(key) || !listed.contains(key))
+        .collect();
+
+    let stars = |repos: &BTreeMap<String, &SnapshotRepo>| -> i64 {
+        repos.values().map(|r| i64::from(r.stars)).sum()
+    };
+    let forks = |repos: &BTreeMap<String, &SnapshotRepo>| -> Option<i64> {
+        repos.values().map(|r| r.forks.map(i64::from)).sum()
+    };
+
+    let mut repo_changes: Vec<RepoDelta> = counted
+        .iter()
+        .filter_map(|(key, now)| {
+            let was = before.get(key)?;
+            let star_delta = i64::from(now.stars) - i64::from(was.stars);
+            let fork_delta = now
+                .forks
+                .zip(was.forks)
+                .map(|(now, was)| i64::from(now) - i64::from(was));
+            (star_delta != 0 || fork_delta.unwrap_or(0) != 0).then(|| RepoDelta {
+                org: now.org.clone(),
+                name: now.name.clone(),
+                stars_before: was.stars,
+                stars: now.stars,
+                star_delta,
+                fork_delta,
+            })
+        })
+        .collect();
+    repo_changes.sort_by_key(|c| std::cmp::Reverse(c.star_delta.abs()));
+
+    SnapshotComparison {
+        since: baseline.taken_at,
+        repos: counted.len() as i64 - before.len() as i64,
+        stars: stars(&counted) - stars(&before),
+        forks: forks(&counted)
+            .zip(forks(&before))
+            .map(|(now, was)| now - was),
+        repo_changes,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repo(name: &str, stars: u32, status: &str) -> SnapshotRepo {
+        SnapshotRepo {
+            org: "acme".into(),
+            name: name.into(),
+            stars,
+            forks: Some(1),
+            archived: status == "archived",
+            status: status.into(),
+        }
+    }
+
+    fn snapshot(at: &str, repos: Vec<SnapshotRepo>) -> Snapshot {
+        Snapshot {
+            taken_at: at.parse().unwrap(),
+            repos,
+        }
+    }
+
+    #[test]
+    fn diff_reports_each_kind_of_change() {
+        let old = snapshot(
+            "2024-01-01T00:00:00Z",
+            vec![
+                repo("gone", 1, "active"),
+                repo("retired", 5, "active"),
+                repo("quiet", 10, "active"),
+                repo("popular", 100, "active"),
+                repo("steady", 3, "stale"),
+            ],
+        );
+        let new = snapshot(
+            "2024-02-01T00:00:00Z",
+            vec![
+                repo("retired", 5, "archived"),
+                repo("quiet", 8, "stale"),
+                repo("popular", 140, "active"),
+                repo("steady", 3, "stale"),
+                repo("fresh", 0, "active"),
+            ],
+        );
+
+        let diff = diff_snapshots(&old, &new);
+        assert_eq!(diff.added, vec!["acme/fresh"]);
+        assert_eq!(diff.removed, vec!["acme/gone"]);
+        assert_eq!(diff.archived, vec!["acme/retired"]);
+        assert!(diff.unarchived.is_empty());
+        assert_eq!(diff.went_stale, vec!["acme/quiet"]);
+        let stars: Vec<(&str, i64)> = diff
+            .star_changes
+            .iter()
+            .map(|c| (c.repo.as_str(), c.delta))
+            .collect();
+        assert_eq!(stars, vec![("acme/popular", 40), ("acme/quiet", -2)]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn comparison_leaves_filtered_repos_out_of_the_baseline() {
+        let baseline = snapshot(
+            "2024-06-01T00:00:00Z",
+            vec![
+                repo("popular", 100, "active"),
+                repo("private-tool", 50, "active"),
+                repo("deleted", 7, "active"),
+            ],
+        );
+        let mut popular = repo("popular", 120, "active");
+        popular.forks = Some(4);
+        let now = vec![popular, repo("fresh", 2, "active")];
+        // private-tool is still listed, just filtered out of this run
+        let listed: HashSet<String> = ["popular", "fresh", "private-tool"]
+            .iter()
+            .map(|name| repo_key("ACME", name))
+            .collect();
+
+        let cmp = compare_to_snapshot(&baseline, &now, &listed, &["acme".to_string()]);
+        assert_eq!(cmp.repos, 0);
+        assert_eq!(cmp.stars, 122 - 107);
+        assert_eq!(cmp.forks, Some(5 - 2));
+        assert_eq!(cmp.repo_changes.len(), 1);
+        assert_eq!(cmp.repo_changes[0].name, "popular");
+        assert_eq!(cmp.repo_changes[0].star_delta, 20);
+        assert_eq!(cmp.repo_changes[0].fork_delta, Some(3));
+
+        // Snapshots saved before forks were recorded
+        let mut old = baseline;
+        old.repos.iter_mut().for_each(|r| r.forks = None);
+        let cmp = compare_to_snapshot(&old, &now, &listed, &["acme".to_string()]);
+        assert_eq!(cmp.forks, None);
+        assert_eq!(cmp.repo_changes[0].fork_delta, None);
+    }
+
+    #[test]
+    fn identical_snapshots_have_no_changes() {
+        let repos = vec![repo("tool", 7, "active")];
+        let diff = diff_snapshots(
+            &snapshot("2024-01-01T00:00:00Z", repos.clone()),
+            &snapshot("2024-01-02T00:00:00Z", repos),
+        );
+        assert!(diff.is_empty());
+    }
+}
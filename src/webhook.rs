@@ -0,0 +1,123 @@
+use crate::error::{GitorgError, Result};
+use crate::github::{build_octocrab, ProxySettings};
+use clap::ValueEnum;
+use octocrab::Octocrab;
+use serde::Serialize;
+use std::time::Duration;
+
+/// The body shape a webhook expects.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WebhookFormat {
+    /// The same JSON records `--json` prints
+    #[default]
+    Generic,
+    /// A Slack incoming-webhook message (`{"text": ...}`)
+    Slack,
+}
+
+/// Wraps `text` in Slack's incoming-webhook message schema.
+pub fn slack_message(text: &str) -> serde_json::Value {
+    serde_json::json!({ "text": text })
+}
+
+/// POSTs `body` as JSON to `url`, through the proxies in `proxy` if any.
+/// Any non-2xx reply is an error.
+pub async fn post_json<T: Serialize + ?Sized>(
+    url: &str,
+    body: &T,
+    timeout: Duration,
+    proxy: ProxySettings,
+) -> Result<()> {
+    // A client of its own, without credentials: the GitHub token must never
+    // be sent to a third-party host
+    let builder = Octocrab::builder()
+        .set_connect_timeout(Some(timeout))
+        .set_read_timeout(Some(timeout))
+        .set_write_timeout(Some(timeout));
+    let client = if proxy.is_empty() {
+        builder
+            .build()
+            .map_err(|e| GitorgError::Webhook(e.to_string()))?
+    } else {
+        build_octocrab(builder, None, None, proxy)?
+    };
+
+    let response = client
+        ._post(url, Some(body))
+        .await
+        .map_err(|e| GitorgError::Webhook(format!("POST to {url} failed: {e}")))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(GitorgError::Webhook(format!("{url} answered {status}")));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn posts_json_body() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .and(header("content-type", "application/json"))
+            .and(body_json(slack_message("2 stale")))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/hook", server.uri());
+        post_json(
+            &url,
+            &slack_message("2 stale"),
+            Duration::from_secs(5),
+            ProxySettings::default(),
+        )
+        .await
+        .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert!(!requests[0].headers.contains_key("authorization"));
+    }
+
+    #[tokio::test]
+    async fn error_status_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .mount(&server)
+            .await;
+
+        let err = post_json(
+            &server.uri(),
+            &[1, 2],
+            Duration::from_secs(5),
+            ProxySettings::default(),
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("400"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn posts_go_through_the_proxy() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        // Nothing listens on port 1, so a proxied POST can't get through
+        let proxy = ProxySettings::from_env(Some("http://127.0.0.1:1")).unwrap();
+        let err = post_json(&server.uri(), &[1, 2], Duration::from_secs(5), proxy)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("POST to"), "{err}");
+        assert!(server.received_requests().await.unwrap().is_empty());
+    }
+}
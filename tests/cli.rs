@@ -1,9 +1,12 @@
-use assert_cmd::Command;
+use assert_cmd::cargo::cargo_bin_cmd;
 use predicates::prelude::*;
+use std::time::Duration;
+use wiremock::matchers::{any, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
 
 #[test]
 fn help_shows_all_commands() {
-    let mut cmd = Command::cargo_bin("gitorg").unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
     cmd.arg("--help")
         .assert()
         .success()
@@ -13,12 +16,13 @@ fn help_shows_all_commands() {
         .stdout(predicate::str::contains("stale"))
         .stdout(predicate::str::contains("issues"))
         .stdout(predicate::str::contains("stats"))
-        .stdout(predicate::str::contains("overview"));
+        .stdout(predicate::str::contains("overview"))
+        .stdout(predicate::str::contains("profile"));
 }
 
 #[test]
 fn version_flag() {
-    let mut cmd = Command::cargo_bin("gitorg").unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
     cmd.arg("--version")
         .assert()
         .success()
@@ -27,25 +31,44 @@ fn version_flag() {
 
 #[test]
 fn auth_help() {
-    let mut cmd = Command::cargo_bin("gitorg").unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
     cmd.args(["auth", "--help"])
         .assert()
         .success()
-        .stdout(predicate::str::contains("token"));
+        .stdout(predicate::str::contains("token"))
+        .stdout(predicate::str::contains("defaults.orgs"));
+}
+
+#[test]
+fn visibility_rejects_unknown_value() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["stale", "--visibility", "internal"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("possible values"));
 }
 
 #[test]
 fn repos_help_shows_sort() {
-    let mut cmd = Command::cargo_bin("gitorg").unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
     cmd.args(["repos", "--help"])
         .assert()
         .success()
         .stdout(predicate::str::contains("--sort"));
 }
 
+#[test]
+fn protection_help_shows_unprotected_default() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["protection", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("--unprotected-default"));
+}
+
 #[test]
 fn stale_help_shows_days() {
-    let mut cmd = Command::cargo_bin("gitorg").unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
     cmd.args(["stale", "--help"])
         .assert()
         .success()
@@ -54,7 +77,7 @@ fn stale_help_shows_days() {
 
 #[test]
 fn no_subcommand_shows_help() {
-    let mut cmd = Command::cargo_bin("gitorg").unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
     cmd.assert()
         .failure()
         .stderr(predicate::str::contains("Usage"));
@@ -62,11 +85,484 @@ fn no_subcommand_shows_help() {
 
 #[test]
 fn orgs_without_auth_fails() {
-    let mut cmd = Command::cargo_bin("gitorg").unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
     // Use a temp config dir to ensure no real auth exists
     cmd.env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+        .env_remove("GITHUB_TOKEN")
         .arg("orgs")
         .assert()
         .failure()
         .stderr(predicate::str::contains("Not authenticated"));
 }
+
+#[test]
+fn list_commands_accept_limit() {
+    for command in ["repos", "stale", "issues"] {
+        let mut cmd = cargo_bin_cmd!("gitorg");
+        cmd.args([command, "--help"])
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("--limit"));
+    }
+}
+
+#[test]
+fn profile_use_unknown_fails() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path())
+        .args(["profile", "use", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown profile 'nope'"));
+}
+
+#[test]
+fn no_color_emits_plain_errors() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path())
+        .env("NO_COLOR", "1")
+        .args(["profile", "use", "nope", "--no-color"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::starts_with(
+            "error: Configuration error: Unknown profile",
+        ))
+        .stderr(predicate::str::contains("\x1b[").not());
+}
+
+#[test]
+fn archived_flags_are_mutually_exclusive() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["repos", "--exclude-archived", "--only-archived"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("cannot be used with"));
+}
+
+#[test]
+fn overview_watch_rejects_json() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path())
+        .args(["overview", "--watch", "--json"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "--watch can't be combined with --json",
+        ));
+}
+
+#[test]
+fn config_flag_reads_the_given_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("gitorg.toml");
+    std::fs::write(&path, "[profiles.work.auth]\ntoken = \"ghp_x\"\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path().join("xdg"))
+        .args(["profile", "--json", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"work\""));
+}
+
+#[test]
+fn repo_rejects_slug_without_slash() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["repo", "widgets"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Expected a repository as <org>/<name>",
+        ));
+}
+
+#[test]
+fn output_file_receives_results() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("reports").join("profiles.json");
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path())
+        .args(["profile", "--output"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+
+    let written = std::fs::read_to_string(&path).unwrap();
+    assert!(written.contains("\"name\": \"default\""));
+}
+
+#[test]
+fn missing_token_file_is_a_config_error() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path())
+        .env_remove("GITHUB_TOKEN")
+        .args(["orgs", "--token-file"])
+        .arg(dir.path().join("missing"))
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Cannot read token file"));
+}
+
+#[test]
+fn ndjson_prints_one_record_per_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "[profiles.work.auth]\ntoken = \"ghp_x\"\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["profile", "--ndjson", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("{\"name\":\"default\""))
+        .stdout(predicate::str::contains("}\n{\"name\":\"work\""));
+}
+
+#[test]
+fn repos_rejects_bad_created_after_date() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["repos", "--created-after", "last-week"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Invalid --created-after date 'last-week'",
+        ));
+}
+
+#[tokio::test]
+async fn slow_api_times_out() {
+    // Answers, but only long after the timeout
+    let server = MockServer::start().await;
+    Mock::given(any())
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(30)))
+        .mount(&server)
+        .await;
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("GITHUB_TOKEN", "ghp_test")
+        .env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+        .args(["orgs", "--timeout", "1", "--api-url"])
+        .arg(server.uri())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("request timed out after 1s"));
+}
+
+#[test]
+fn unsupported_proxy_scheme_is_rejected() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("GITHUB_TOKEN", "ghp_test")
+        .env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+        .args(["orgs", "--proxy", "socks5://proxy.corp:1080"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "socks5:// proxies aren't supported",
+        ));
+}
+
+#[test]
+fn open_prints_the_url_when_no_browser_can_start() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+        .env("PATH", "")
+        .args(["open", "acme/api#42", "--api-url"])
+        .arg("https://ghe.example.com/api/v3")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains(
+            "https://ghe.example.com/acme/api/issues/42",
+        ));
+}
+
+#[test]
+fn export_without_auth_fails() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+        .env_remove("GITHUB_TOKEN")
+        .args(["export", "--output", "/tmp/gitorg_test_export.md"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Not authenticated"));
+}
+
+#[test]
+fn whoami_without_auth_fails() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+        .env_remove("GITHUB_TOKEN")
+        .arg("whoami")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Not authenticated"));
+}
+
+#[test]
+fn archive_needs_yes_without_a_terminal() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["archive", "acme/old-tool"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains(
+            "Refusing to archive acme/old-tool without --yes",
+        ));
+}
+
+#[test]
+fn format_prints_one_template_line_per_record() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "[profiles.work.auth]\ntoken = \"ghp_x\"\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["profile", "--format", "profile={name}", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with(
+            "profile=default\nprofile=work\n",
+        ));
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["profile", "--format", "{nope}", "--config"])
+        .arg(&path)
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("Unknown field 'nope' in --format"));
+}
+
+#[test]
+fn fields_lists_a_commands_template_fields() {
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["fields", "releases", "--format", "{fields}"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "org,name,tag,published_at,days_since_release\n",
+        ));
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["fields", "overview"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("has no record fields"));
+}
+
+#[test]
+fn json_compact_prints_a_single_line() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "[profiles.work.auth]\ntoken = \"ghp_x\"\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["profile", "--json", "--json-compact", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("[{\"name\":\"default\""))
+        .stdout(predicate::str::contains("},{\"name\":\"work\""));
+}
+
+#[test]
+fn snapshot_diff_compares_the_two_newest() {
+    let dir = tempfile::tempdir().unwrap();
+    let snapshots = dir.path().join("gitorg").join("snapshots");
+    std::fs::create_dir_all(&snapshots).unwrap();
+    let snapshot = |at: &str, stars: u32, status: &str| {
+        format!(
+            r#"{{"taken_at":"{at}","repos":[{{"org":"acme","name":"tool","stars":{stars},"archived":false,"status":"{status}"}}]}}"#
+        )
+    };
+    std::fs::write(
+        snapshots.join("20240101T000000Z.json"),
+        snapshot("2024-01-01T00:00:00Z", 10, "active"),
+    )
+    .unwrap();
+    std::fs::write(
+        snapshots.join("20240201T000000Z.json"),
+        snapshot("2024-02-01T00:00:00Z", 15, "stale"),
+    )
+    .unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path())
+        .args(["snapshot", "diff", "--json"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "\"went_stale\": [\n    \"acme/tool\"\n  ]",
+        ))
+        .stdout(predicate::str::contains("\"delta\": 5"));
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path())
+        .args(["snapshot", "diff", "nope"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No snapshot 'nope'"));
+}
+
+#[test]
+fn stats_compare_to_unknown_snapshot_fails_before_fetching() {
+    let dir = tempfile::tempdir().unwrap();
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("XDG_CONFIG_HOME", dir.path())
+        .env_remove("GITHUB_TOKEN")
+        .args(["stats", "--compare-to", "last-week"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("No snapshot 'last-week'"));
+}
+
+#[test]
+fn quiet_drops_headers_and_footers() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.toml");
+    std::fs::write(&path, "[profiles.work.auth]\ntoken = \"ghp_x\"\n").unwrap();
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.args(["profile", "--quiet", "--no-color", "--config"])
+        .arg(&path)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("work"))
+        .stdout(predicate::str::contains("Profiles").not());
+}
+
+#[tokio::test]
+async fn one_missing_org_in_an_org_list_is_not_fatal() {
+    let server = MockServer::start().await;
+    Mock::given(path("/orgs/good/repos"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                "id": 1,
+                "name": "widget",
+                "url": "https://api.github.com/repos/good/widget",
+            }])),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(any())
+        .respond_with(
+            ResponseTemplate::new(404).set_body_json(serde_json::json!({"message": "Not Found"})),
+        )
+        .mount(&server)
+        .await;
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("GITHUB_TOKEN", "ghp_test")
+        .env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+        .args([
+            "repos",
+            "--org",
+            "good,missing",
+            "--json",
+            "--cache-ttl",
+            "0",
+        ])
+        .arg("--api-url")
+        .arg(server.uri())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("widget"))
+        .stderr(predicate::str::contains(
+            "Failed to fetch repos for missing",
+        ))
+        .stderr(predicate::str::contains("1 of 2 orgs failed to load"));
+}
+
+#[tokio::test]
+async fn ndjson_streams_repos_in_listing_order() {
+    let server = MockServer::start().await;
+    Mock::given(path("/orgs/acme/repos"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {
+                "id": 1,
+                "name": "old",
+                "url": "https://api.github.com/repos/acme/old",
+                "pushed_at": "2020-01-01T00:00:00Z",
+            },
+            {
+                "id": 2,
+                "name": "new",
+                "url": "https://api.github.com/repos/acme/new",
+                "pushed_at": "2024-01-01T00:00:00Z",
+            },
+        ])))
+        .mount(&server)
+        .await;
+
+    let run = |extra: &[&str]| {
+        let mut cmd = cargo_bin_cmd!("gitorg");
+        cmd.env("GITHUB_TOKEN", "ghp_test")
+            .env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+            .args(["repos", "--org", "acme", "--ndjson", "--cache-ttl", "0"])
+            .args(extra)
+            .arg("--api-url")
+            .arg(server.uri())
+            .output()
+            .unwrap()
+    };
+    let names = |output: std::process::Output| -> Vec<String> {
+        String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<serde_json::Value>(line).unwrap()["name"].to_string()
+            })
+            .collect()
+    };
+
+    // As listed, then sorted once --sort asks for every repo first
+    assert_eq!(names(run(&[])), vec!["\"old\"", "\"new\""]);
+    assert_eq!(
+        names(run(&["--sort", "activity"])),
+        vec!["\"new\"", "\"old\""]
+    );
+}
+
+#[tokio::test]
+async fn contributors_report_orgs_that_failed_to_load() {
+    let server = MockServer::start().await;
+    Mock::given(path("/orgs/acme/repos"))
+        .respond_with(
+            ResponseTemplate::new(200).set_body_json(serde_json::json!([{
+                "id": 1,
+                "name": "tool",
+                "url": "https://api.github.com/repos/acme/tool",
+                "size": 10,
+            }])),
+        )
+        .mount(&server)
+        .await;
+    Mock::given(path("/repos/acme/tool/contributors"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            { "login": "octocat", "contributions": 7 },
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(path("/orgs/ghost/repos"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(serde_json::json!({
+            "message": "Not Found",
+        })))
+        .mount(&server)
+        .await;
+
+    let mut cmd = cargo_bin_cmd!("gitorg");
+    cmd.env("GITHUB_TOKEN", "ghp_test")
+        .env("XDG_CONFIG_HOME", "/tmp/gitorg_test_nonexistent")
+        .args(["contributors", "--org", "acme,ghost", "--ndjson"])
+        .args(["--cache-ttl", "0", "--api-url"])
+        .arg(server.uri())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"octocat\""))
+        .stderr(predicate::str::contains("1 of 2 orgs failed to load"));
+}